@@ -0,0 +1,95 @@
+/* Generated by cbindgen from src/lib.rs. Regenerate with `UPDATE_HEADER=1 cargo test -p rust-calculator-ffi`. */
+
+#ifndef RUST_CALCULATOR_H
+#define RUST_CALCULATOR_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Outcome of a call. Evaluation errors have one code per kind of error.
+typedef enum RcalcStatus {
+  // Success
+  RCALC_STATUS_OK = 0,
+  // A required pointer argument was null
+  RCALC_STATUS_NULL_ARGUMENT = 1,
+  // A string argument was not valid UTF-8
+  RCALC_STATUS_INVALID_UTF8 = 2,
+  // Division by zero
+  RCALC_STATUS_DIVISION_BY_ZERO = 3,
+  // A malformed number
+  RCALC_STATUS_INVALID_NUMBER = 4,
+  // A malformed expression, e.g. mismatched parentheses
+  RCALC_STATUS_INVALID_EXPRESSION = 5,
+  // The expression is longer than the evaluator accepts
+  RCALC_STATUS_INPUT_TOO_LONG = 6,
+  // Characters that are not part of any expression, e.g. unknown variables
+  RCALC_STATUS_INVALID_CHARACTERS = 7,
+  // A number or result beyond ±1e100
+  RCALC_STATUS_NUMBER_OUT_OF_RANGE = 8,
+  // A function or operator applied outside its domain, e.g. `√(-1)`
+  RCALC_STATUS_DOMAIN_ERROR = 9,
+  // A name that can't hold a variable, e.g. a function name
+  RCALC_STATUS_INVALID_VARIABLE_NAME = 10,
+  // A bug in the library; please report it
+  RCALC_STATUS_INTERNAL_ERROR = 11,
+} RcalcStatus;
+
+// A calculator: its angle unit and variables. Opaque to C.
+typedef struct RcalcCalculator RcalcCalculator;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Creates a calculator that works in degrees and has no variables. Returns null if
+// it can't be created. Free it with `rcalc_free`.
+struct RcalcCalculator *rcalc_new(void);
+
+// Frees a calculator created by `rcalc_new`. Null is ignored.
+//
+// # Safety
+// `calculator` must be null or come from `rcalc_new`, and must not be used afterwards.
+void rcalc_free(struct RcalcCalculator *calculator);
+
+// Switches trigonometric functions to radians, or back to degrees.
+//
+// # Safety
+// `calculator` must be null or a live calculator from `rcalc_new`.
+enum RcalcStatus rcalc_set_radians(struct RcalcCalculator *calculator, bool radians);
+
+// Sets a variable that later expressions can use, e.g. `rate` in `100 x rate`. Names are
+// lowercase letters, digits and `_`, start with a letter and don't contain `x`. NaN and
+// infinite values are rejected with `NumberOutOfRange`.
+//
+// # Safety
+// `calculator` must be null or a live calculator from `rcalc_new`, and `name` null or a
+// NUL-terminated string.
+enum RcalcStatus rcalc_set_variable(struct RcalcCalculator *calculator,
+                                    const char *name,
+                                    double value);
+
+// Evaluates a UTF-8 expression such as `2x(3+4)` and stores its value in `*result`.
+//
+// On failure `*result` is left alone and the status tells the kind of error. The English
+// error message is written to `message` (cut to fit `message_len` bytes including the
+// terminating NUL); on success `message` is set to the empty string. `message` may be
+// null if the message isn't wanted.
+//
+// # Safety
+// `calculator` must be null or a live calculator from `rcalc_new`, `expression` null or a
+// NUL-terminated string, `result` null or valid for a write, and `message` null or valid
+// for writes of `message_len` bytes.
+enum RcalcStatus rcalc_evaluate(const struct RcalcCalculator *calculator,
+                                const char *expression,
+                                double *result,
+                                char *message,
+                                size_t message_len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RUST_CALCULATOR_H */
@@ -0,0 +1,234 @@
+//! # Calculator C API
+//!
+//! A C interface to the calculator's expression evaluator, built as a `cdylib`. The
+//! matching header is `include/rust_calculator.h`, generated from this file by cbindgen.
+//!
+//! Every function checks its pointers and catches panics, so no panic crosses the FFI
+//! boundary; a panic is reported as `RCALC_STATUS_INTERNAL_ERROR`.
+
+use rust_calculator::variables::Variables;
+use rust_calculator::{AngleMode, CalculatorError};
+use std::ffi::{CStr, c_char};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+/// Outcome of a call. Evaluation errors have one code per kind of error.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RcalcStatus {
+    /// Success
+    Ok = 0,
+    /// A required pointer argument was null
+    NullArgument = 1,
+    /// A string argument was not valid UTF-8
+    InvalidUtf8 = 2,
+    /// Division by zero
+    DivisionByZero = 3,
+    /// A malformed number
+    InvalidNumber = 4,
+    /// A malformed expression, e.g. mismatched parentheses
+    InvalidExpression = 5,
+    /// The expression is longer than the evaluator accepts
+    InputTooLong = 6,
+    /// Characters that are not part of any expression, e.g. unknown variables
+    InvalidCharacters = 7,
+    /// A number or result beyond ±1e100
+    NumberOutOfRange = 8,
+    /// A function or operator applied outside its domain, e.g. `√(-1)`
+    DomainError = 9,
+    /// A name that can't hold a variable, e.g. a function name
+    InvalidVariableName = 10,
+    /// A bug in the library; please report it
+    InternalError = 11,
+}
+
+impl From<&CalculatorError> for RcalcStatus {
+    fn from(error: &CalculatorError) -> Self {
+        match error {
+            CalculatorError::DivisionByZero => RcalcStatus::DivisionByZero,
+            CalculatorError::InvalidNumber(_) => RcalcStatus::InvalidNumber,
+            CalculatorError::InvalidExpression(_) => RcalcStatus::InvalidExpression,
+            CalculatorError::InputTooLong => RcalcStatus::InputTooLong,
+            CalculatorError::InvalidCharacters(_) => RcalcStatus::InvalidCharacters,
+            CalculatorError::NumberOutOfRange(_) => RcalcStatus::NumberOutOfRange,
+            CalculatorError::DomainError(_) => RcalcStatus::DomainError,
+            CalculatorError::InvalidVariableName(_) => RcalcStatus::InvalidVariableName,
+            // Stack errors of the RPN keypad; expressions can't cause them
+            CalculatorError::TooFewArguments | CalculatorError::NoLastX => {
+                RcalcStatus::InternalError
+            }
+        }
+    }
+}
+
+/// A calculator: its angle unit and variables. Opaque to C.
+#[derive(Debug, Default)]
+pub struct RcalcCalculator {
+    angle_mode: AngleMode,
+    variables: Variables,
+}
+
+/// Runs `body`, turning a panic into `RCALC_STATUS_INTERNAL_ERROR`.
+fn guard(body: impl FnOnce() -> RcalcStatus) -> RcalcStatus {
+    panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or(RcalcStatus::InternalError)
+}
+
+/// Reads a NUL-terminated UTF-8 string.
+///
+/// # Safety
+/// `text` must be null or point to a NUL-terminated string.
+unsafe fn read_str<'a>(text: *const c_char) -> Result<&'a str, RcalcStatus> {
+    if text.is_null() {
+        return Err(RcalcStatus::NullArgument);
+    }
+    unsafe { CStr::from_ptr(text) }
+        .to_str()
+        .map_err(|_| RcalcStatus::InvalidUtf8)
+}
+
+/// Copies `text` into a buffer of `len` bytes, cut at a character boundary so that it fits
+/// with its terminating NUL. Does nothing for a null buffer or a length of 0.
+///
+/// # Safety
+/// `buffer` must be null or valid for writes of `len` bytes.
+unsafe fn write_message(text: &str, buffer: *mut c_char, len: usize) {
+    if buffer.is_null() || len == 0 {
+        return;
+    }
+    let mut end = text.len().min(len - 1);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    unsafe {
+        ptr::copy_nonoverlapping(text.as_ptr().cast::<c_char>(), buffer, end);
+        *buffer.add(end) = 0;
+    }
+}
+
+/// Creates a calculator that works in degrees and has no variables. Returns null if
+/// it can't be created. Free it with `rcalc_free`.
+#[unsafe(no_mangle)]
+pub extern "C" fn rcalc_new() -> *mut RcalcCalculator {
+    panic::catch_unwind(|| Box::into_raw(Box::default())).unwrap_or(ptr::null_mut())
+}
+
+/// Frees a calculator created by `rcalc_new`. Null is ignored.
+///
+/// # Safety
+/// `calculator` must be null or come from `rcalc_new`, and must not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rcalc_free(calculator: *mut RcalcCalculator) {
+    if !calculator.is_null() {
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+            drop(unsafe { Box::from_raw(calculator) })
+        }));
+    }
+}
+
+/// Switches trigonometric functions to radians, or back to degrees.
+///
+/// # Safety
+/// `calculator` must be null or a live calculator from `rcalc_new`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rcalc_set_radians(
+    calculator: *mut RcalcCalculator,
+    radians: bool,
+) -> RcalcStatus {
+    guard(|| {
+        let Some(calculator) = (unsafe { calculator.as_mut() }) else {
+            return RcalcStatus::NullArgument;
+        };
+        calculator.angle_mode = if radians {
+            AngleMode::Radians
+        } else {
+            AngleMode::Degrees
+        };
+        RcalcStatus::Ok
+    })
+}
+
+/// Sets a variable that later expressions can use, e.g. `rate` in `100 x rate`. Names are
+/// lowercase letters, digits and `_`, start with a letter and don't contain `x`. NaN and
+/// infinite values are rejected with `NumberOutOfRange`.
+///
+/// # Safety
+/// `calculator` must be null or a live calculator from `rcalc_new`, and `name` null or a
+/// NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rcalc_set_variable(
+    calculator: *mut RcalcCalculator,
+    name: *const c_char,
+    value: f64,
+) -> RcalcStatus {
+    guard(|| {
+        let Some(calculator) = (unsafe { calculator.as_mut() }) else {
+            return RcalcStatus::NullArgument;
+        };
+        let name = match unsafe { read_str(name) } {
+            Ok(name) => name,
+            Err(status) => return status,
+        };
+        match calculator.variables.set(name, value) {
+            Ok(()) => RcalcStatus::Ok,
+            Err(error) => RcalcStatus::from(&error),
+        }
+    })
+}
+
+/// Evaluates a UTF-8 expression such as `2x(3+4)` and stores its value in `*result`.
+///
+/// On failure `*result` is left alone and the status tells the kind of error. The English
+/// error message is written to `message` (cut to fit `message_len` bytes including the
+/// terminating NUL); on success `message` is set to the empty string. `message` may be
+/// null if the message isn't wanted.
+///
+/// # Safety
+/// `calculator` must be null or a live calculator from `rcalc_new`, `expression` null or a
+/// NUL-terminated string, `result` null or valid for a write, and `message` null or valid
+/// for writes of `message_len` bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rcalc_evaluate(
+    calculator: *const RcalcCalculator,
+    expression: *const c_char,
+    result: *mut f64,
+    message: *mut c_char,
+    message_len: usize,
+) -> RcalcStatus {
+    let status = guard(|| {
+        let Some(calculator) = (unsafe { calculator.as_ref() }) else {
+            return RcalcStatus::NullArgument;
+        };
+        if result.is_null() {
+            return RcalcStatus::NullArgument;
+        }
+        let expression = match unsafe { read_str(expression) } {
+            Ok(expression) => expression,
+            Err(status) => return status,
+        };
+
+        match calculator
+            .variables
+            .evaluate(expression, calculator.angle_mode)
+        {
+            Ok(value) => {
+                unsafe { *result = value };
+                unsafe { write_message("", message, message_len) };
+                RcalcStatus::Ok
+            }
+            Err(error) => {
+                unsafe { write_message(&error.to_string(), message, message_len) };
+                RcalcStatus::from(&error.kind)
+            }
+        }
+    });
+
+    if let Some(text) = match status {
+        RcalcStatus::NullArgument => Some("A required argument is null"),
+        RcalcStatus::InvalidUtf8 => Some("The expression is not valid UTF-8"),
+        RcalcStatus::InternalError => Some("Internal error"),
+        _ => None,
+    } {
+        unsafe { write_message(text, message, message_len) };
+    }
+    status
+}
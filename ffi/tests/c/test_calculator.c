@@ -0,0 +1,71 @@
+/* Exercises the C API through the generated header. Prints each failed check and
+ * exits with the number of failures. */
+#include <math.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "rust_calculator.h"
+
+static int failures = 0;
+
+#define CHECK(condition)                                                     \
+    do {                                                                     \
+        if (!(condition)) {                                                  \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, \
+                    #condition);                                             \
+            failures++;                                                      \
+        }                                                                    \
+    } while (0)
+
+int main(void) {
+    RcalcCalculator *calculator = rcalc_new();
+    CHECK(calculator != NULL);
+
+    char message[64];
+    double result = 0.0;
+
+    CHECK(rcalc_evaluate(calculator, "7+8x3", &result, message, sizeof message) ==
+          RCALC_STATUS_OK);
+    CHECK(result == 31.0);
+    CHECK(strcmp(message, "") == 0);
+
+    CHECK(rcalc_evaluate(calculator, "sin(30)", &result, NULL, 0) == RCALC_STATUS_OK);
+    CHECK(fabs(result - 0.5) < 1e-12);
+    CHECK(rcalc_set_radians(calculator, true) == RCALC_STATUS_OK);
+    CHECK(rcalc_evaluate(calculator, "cos(π)", &result, NULL, 0) == RCALC_STATUS_OK);
+    CHECK(result == -1.0);
+
+    /* Errors leave the result alone and describe themselves */
+    result = 42.0;
+    CHECK(rcalc_evaluate(calculator, "1÷0", &result, message, sizeof message) ==
+          RCALC_STATUS_DIVISION_BY_ZERO);
+    CHECK(result == 42.0);
+    CHECK(strcmp(message, "Division by zero") == 0);
+    CHECK(rcalc_evaluate(calculator, "(1+2", &result, message, sizeof message) ==
+          RCALC_STATUS_INVALID_EXPRESSION);
+
+    /* Messages are cut to fit the buffer */
+    char small[9];
+    CHECK(rcalc_evaluate(calculator, "1÷0", &result, small, sizeof small) ==
+          RCALC_STATUS_DIVISION_BY_ZERO);
+    CHECK(strcmp(small, "Division") == 0);
+
+    CHECK(rcalc_set_variable(calculator, "rate", 1.5) == RCALC_STATUS_OK);
+    CHECK(rcalc_evaluate(calculator, "100xrate", &result, NULL, 0) == RCALC_STATUS_OK);
+    CHECK(result == 150.0);
+    CHECK(rcalc_set_variable(calculator, "sin", 1.0) == RCALC_STATUS_INVALID_VARIABLE_NAME);
+
+    /* Bad arguments are reported, not dereferenced */
+    CHECK(rcalc_evaluate(NULL, "1", &result, NULL, 0) == RCALC_STATUS_NULL_ARGUMENT);
+    CHECK(rcalc_evaluate(calculator, NULL, &result, NULL, 0) == RCALC_STATUS_NULL_ARGUMENT);
+    CHECK(rcalc_evaluate(calculator, "1", NULL, NULL, 0) == RCALC_STATUS_NULL_ARGUMENT);
+    CHECK(rcalc_evaluate(calculator, "\xff", &result, NULL, 0) == RCALC_STATUS_INVALID_UTF8);
+
+    rcalc_free(calculator);
+    rcalc_free(NULL);
+
+    if (failures == 0) {
+        printf("all checks passed\n");
+    }
+    return failures;
+}
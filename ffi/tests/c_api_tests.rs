@@ -0,0 +1,197 @@
+use rust_calculator_ffi::{
+    RcalcStatus, rcalc_evaluate, rcalc_free, rcalc_new, rcalc_set_radians, rcalc_set_variable,
+};
+use std::ffi::{CStr, c_char};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::ptr;
+
+const HEADER: &str = "include/rust_calculator.h";
+
+fn manifest_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Evaluates through the C API, returning the status, result and message.
+fn evaluate(expression: &CStr, radians: bool) -> (RcalcStatus, f64, String) {
+    let mut result = f64::NAN;
+    let mut message = [0 as c_char; 64];
+    unsafe {
+        let calculator = rcalc_new();
+        assert_eq!(rcalc_set_radians(calculator, radians), RcalcStatus::Ok);
+        let status = rcalc_evaluate(
+            calculator,
+            expression.as_ptr(),
+            &mut result,
+            message.as_mut_ptr(),
+            message.len(),
+        );
+        rcalc_free(calculator);
+        let message = CStr::from_ptr(message.as_ptr()).to_str().unwrap();
+        (status, result, message.to_string())
+    }
+}
+
+#[test]
+fn test_evaluate_reports_values_and_errors() {
+    assert_eq!(
+        evaluate(c"7+8x3", false),
+        (RcalcStatus::Ok, 31.0, String::new())
+    );
+    assert_eq!(evaluate(c"cos(π)", true).1, -1.0);
+
+    let (status, result, message) = evaluate(c"√(-4)", false);
+    assert_eq!(status, RcalcStatus::DomainError);
+    assert!(result.is_nan());
+    assert_eq!(message, "Domain error: √(-4)");
+    assert_eq!(evaluate(c"2+", false).0, RcalcStatus::InvalidExpression);
+    assert_eq!(evaluate(c"2+q", false).0, RcalcStatus::InvalidCharacters);
+}
+
+#[test]
+fn test_variables_and_invalid_arguments() {
+    unsafe {
+        let calculator = rcalc_new();
+        let mut result = 0.0;
+        assert_eq!(
+            rcalc_set_variable(calculator, c"rate".as_ptr(), 1.5),
+            RcalcStatus::Ok
+        );
+        assert_eq!(
+            rcalc_evaluate(
+                calculator,
+                c"100xrate".as_ptr(),
+                &mut result,
+                ptr::null_mut(),
+                0
+            ),
+            RcalcStatus::Ok
+        );
+        assert_eq!(result, 150.0);
+        assert_eq!(
+            rcalc_set_variable(calculator, c"2rate".as_ptr(), 1.0),
+            RcalcStatus::InvalidVariableName
+        );
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert_eq!(
+                rcalc_set_variable(calculator, c"rate".as_ptr(), value),
+                RcalcStatus::NumberOutOfRange
+            );
+        }
+        // The previous value is kept
+        assert_eq!(
+            rcalc_evaluate(
+                calculator,
+                c"rate".as_ptr(),
+                &mut result,
+                ptr::null_mut(),
+                0
+            ),
+            RcalcStatus::Ok
+        );
+        assert_eq!(result, 1.5);
+        assert_eq!(
+            rcalc_set_variable(calculator, ptr::null(), 1.0),
+            RcalcStatus::NullArgument
+        );
+        assert_eq!(
+            rcalc_evaluate(ptr::null(), c"1".as_ptr(), &mut result, ptr::null_mut(), 0),
+            RcalcStatus::NullArgument
+        );
+        assert_eq!(
+            rcalc_evaluate(
+                calculator,
+                c"\xff".as_ptr(),
+                &mut result,
+                ptr::null_mut(),
+                0
+            ),
+            RcalcStatus::InvalidUtf8
+        );
+        rcalc_free(calculator);
+    }
+}
+
+#[test]
+fn test_messages_are_cut_at_character_boundaries() {
+    unsafe {
+        let calculator = rcalc_new();
+        let mut result = 0.0;
+        // "Domain error: √(-4)" has a three-byte character at 14..17
+        let mut message = [1 as c_char; 16];
+        rcalc_evaluate(
+            calculator,
+            c"√(-4)".as_ptr(),
+            &mut result,
+            message.as_mut_ptr(),
+            message.len(),
+        );
+        assert_eq!(CStr::from_ptr(message.as_ptr()), c"Domain error: ");
+        rcalc_free(calculator);
+    }
+}
+
+/// The committed header must match what cbindgen generates from the current source.
+/// Run with `UPDATE_HEADER=1` to rewrite it.
+#[test]
+fn test_header_is_up_to_date() {
+    let config = cbindgen::Config::from_file(manifest_dir().join("cbindgen.toml")).unwrap();
+    let mut generated = Vec::new();
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(manifest_dir().join("src/lib.rs"))
+        .generate()
+        .unwrap()
+        .write(&mut generated);
+    let generated = String::from_utf8(generated).unwrap();
+
+    let path = manifest_dir().join(HEADER);
+    if std::env::var_os("UPDATE_HEADER").is_some() {
+        std::fs::write(&path, &generated).unwrap();
+    }
+    let committed = std::fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        committed == generated,
+        "{HEADER} is out of date; regenerate it with `UPDATE_HEADER=1 cargo test -p rust-calculator-ffi`"
+    );
+}
+
+/// Directory with the built `cdylib`: the parent of this test's `deps` directory.
+fn library_dir() -> PathBuf {
+    let exe = std::env::current_exe().unwrap();
+    exe.parent().unwrap().parent().unwrap().to_path_buf()
+}
+
+#[test]
+fn test_c_program_links_and_runs() {
+    let library_dir = library_dir();
+    let program = library_dir.join("test_calculator_c");
+    let compiled = Command::new(std::env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+        .arg(manifest_dir().join("tests/c/test_calculator.c"))
+        .arg("-std=c99")
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(manifest_dir().join("include"))
+        .arg("-L")
+        .arg(&library_dir)
+        .arg("-lrust_calculator_ffi")
+        .arg("-lm")
+        .arg(format!("-Wl,-rpath,{}", library_dir.display()))
+        .arg("-o")
+        .arg(&program)
+        .status()
+        .expect("a C compiler is needed to test the C API");
+    assert!(compiled.success());
+
+    let output = Command::new(&program).output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "all checks passed\n"
+    );
+}
@@ -0,0 +1,84 @@
+"""Tests of the `rust_calculator` extension module.
+
+Run them with `cargo test --features python`, or after `maturin develop` with
+`python -m unittest discover python/tests`.
+"""
+
+import math
+import unittest
+
+import rust_calculator
+from rust_calculator import CalculatorError, Expression, evaluate
+
+
+class EvaluateTests(unittest.TestCase):
+    def test_operator_precedence(self):
+        self.assertEqual(evaluate("7+8*3"), 31.0)
+        self.assertEqual(evaluate("2^3^2"), 512.0)
+        self.assertEqual(evaluate("5!÷(2+3)"), 24.0)
+
+    def test_angle_units(self):
+        self.assertAlmostEqual(evaluate("sin(30)"), 0.5)
+        self.assertAlmostEqual(evaluate("sin(π÷6)", angle="radians"), 0.5)
+        with self.assertRaises(ValueError):
+            evaluate("sin(1)", angle="gradians")
+
+    def test_errors_have_kind_and_position(self):
+        with self.assertRaises(CalculatorError) as raised:
+            evaluate("1+4÷(2-2)")
+        error = raised.exception
+        self.assertEqual(str(error), "Division by zero")
+        self.assertEqual(error.kind, "division_by_zero")
+        self.assertEqual((error.start, error.end), (5, 8))
+        self.assertEqual("1+4÷(2-2)"[error.start:error.end], "2-2")
+
+        with self.assertRaises(CalculatorError) as raised:
+            evaluate("2+q")
+        self.assertEqual(raised.exception.kind, "invalid_characters")
+        self.assertEqual((raised.exception.start, raised.exception.end), (2, 3))
+
+    def test_calculator_error_is_a_value_error(self):
+        self.assertTrue(issubclass(CalculatorError, ValueError))
+        with self.assertRaises(ValueError):
+            evaluate("√(-4)")
+
+
+class ExpressionTests(unittest.TestCase):
+    def test_evaluate_with_variables(self):
+        area = Expression("π x r^2")
+        self.assertEqual(area.variables, ["r"])
+        self.assertEqual(area.source, "π x r^2")
+        self.assertAlmostEqual(area.evaluate(r=2), 4 * math.pi)
+        self.assertAlmostEqual(area(r=1), math.pi)
+        self.assertEqual(repr(area), 'Expression("π x r^2")')
+
+    def test_syntax_errors_are_raised_when_compiling(self):
+        with self.assertRaises(CalculatorError) as raised:
+            Expression("2x(rate+")
+        self.assertEqual(raised.exception.kind, "invalid_expression")
+
+        # Errors that depend on the values wait for evaluation
+        ratio = Expression("a÷(a-4)")
+        with self.assertRaises(CalculatorError) as raised:
+            ratio.evaluate(a=4)
+        self.assertEqual(raised.exception.kind, "division_by_zero")
+        self.assertEqual((raised.exception.start, raised.exception.end), (3, 6))
+
+    def test_missing_and_invalid_variables(self):
+        growth = Expression("100 x rate", angle="radians")
+        with self.assertRaises(CalculatorError) as raised:
+            growth.evaluate()
+        self.assertEqual(raised.exception.kind, "invalid_characters")
+        self.assertEqual((raised.exception.start, raised.exception.end), (6, 10))
+
+        with self.assertRaises(CalculatorError) as raised:
+            growth.evaluate(rate=1, sin=2)
+        self.assertEqual(raised.exception.kind, "invalid_variable_name")
+
+    def test_module_exports(self):
+        self.assertEqual(rust_calculator.__name__, "rust_calculator")
+        self.assertIs(rust_calculator.Expression, Expression)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,269 @@
+use crate::calculator::ExpressionError;
+use crate::cli::EvalOptions;
+use serde::Serialize;
+use std::io::{self, BufRead, Write};
+
+/// How batch results are written.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum OutputFormat {
+    /// One result per line; errors as `error: <message>`
+    #[default]
+    Plain,
+    /// Comma-separated values with a `line,expression,result,error` header
+    Csv,
+    /// One JSON object per line
+    JsonLines,
+}
+
+impl OutputFormat {
+    /// All output formats.
+    pub const ALL: [OutputFormat; 3] = [
+        OutputFormat::Plain,
+        OutputFormat::Csv,
+        OutputFormat::JsonLines,
+    ];
+
+    /// Name used for the format on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            OutputFormat::Plain => "plain",
+            OutputFormat::Csv => "csv",
+            OutputFormat::JsonLines => "jsonl",
+        }
+    }
+}
+
+/// How a batch is evaluated and written.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct BatchOptions {
+    /// Evaluation and result formatting
+    pub eval: EvalOptions,
+    /// Output format
+    pub output: OutputFormat,
+    /// Stop at the first expression that fails
+    pub fail_fast: bool,
+}
+
+/// One line of batch input and what became of it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Entry {
+    /// An empty line
+    Blank,
+    /// A line starting with `#`, kept as it is
+    Comment(String),
+    /// An expression and its value, formatted as the options ask
+    Value {
+        expression: String,
+        value: f64,
+        formatted: String,
+    },
+    /// An expression that could not be evaluated
+    Error {
+        expression: String,
+        error: ExpressionError,
+    },
+}
+
+impl Entry {
+    /// Evaluates one line of input. Surrounding whitespace is ignored, so error positions
+    /// count from the first character of the expression; comments are kept as written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::batch::Entry;
+    /// use rust_calculator::cli::EvalOptions;
+    ///
+    /// let options = EvalOptions::default();
+    /// assert_eq!(Entry::evaluate("", &options), Entry::Blank);
+    /// assert_eq!(Entry::evaluate("# totals", &options), Entry::Comment("# totals".to_string()));
+    /// assert!(matches!(Entry::evaluate(" 2x3", &options), Entry::Value { value: 6.0, .. }));
+    /// assert!(matches!(Entry::evaluate("1/0", &options), Entry::Error { .. }));
+    /// ```
+    pub fn evaluate(line: &str, options: &EvalOptions) -> Self {
+        let expression = line.trim();
+        if expression.is_empty() {
+            return Entry::Blank;
+        }
+        if expression.starts_with('#') {
+            return Entry::Comment(line.to_string());
+        }
+
+        let calculator = options.calculator();
+        match calculator.evaluate_spanned(expression) {
+            Ok(value) => Entry::Value {
+                expression: expression.to_string(),
+                value,
+                formatted: calculator.format_value(value),
+            },
+            Err(error) => Entry::Error {
+                expression: expression.to_string(),
+                error,
+            },
+        }
+    }
+
+    /// Writes the entry in the given format, as one line. `line` is its line number,
+    /// counted from 1.
+    pub fn write(&self, out: &mut impl Write, format: OutputFormat, line: usize) -> io::Result<()> {
+        match format {
+            OutputFormat::Plain => match self {
+                Entry::Blank => writeln!(out),
+                Entry::Comment(comment) => writeln!(out, "{}", comment),
+                Entry::Value { formatted, .. } => writeln!(out, "{}", formatted),
+                Entry::Error { error, .. } => writeln!(out, "error: {}", error),
+            },
+            OutputFormat::Csv => {
+                let (expression, result, error) = match self {
+                    Entry::Blank => ("", String::new(), String::new()),
+                    Entry::Comment(comment) => (comment.as_str(), String::new(), String::new()),
+                    Entry::Value {
+                        expression,
+                        formatted,
+                        ..
+                    } => (expression.as_str(), formatted.clone(), String::new()),
+                    Entry::Error { expression, error } => {
+                        (expression.as_str(), String::new(), error.to_string())
+                    }
+                };
+                writeln!(
+                    out,
+                    "{},{},{},{}",
+                    line,
+                    csv_field(expression),
+                    csv_field(&result),
+                    csv_field(&error)
+                )
+            }
+            OutputFormat::JsonLines => {
+                serde_json::to_writer(&mut *out, &JsonEntry::new(self, line))?;
+                writeln!(out)
+            }
+        }
+    }
+}
+
+/// Header row of the CSV output.
+pub const CSV_HEADER: &str = "line,expression,result,error";
+
+/// JSON Lines form of an entry; exactly one of the optional fields is present.
+#[derive(Serialize)]
+struct JsonEntry<'a> {
+    line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    blank: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comment: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expression: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    formatted: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<JsonError>,
+}
+
+/// An error with the character range of the expression it is about.
+#[derive(Serialize)]
+struct JsonError {
+    kind: &'static str,
+    message: String,
+    start: usize,
+    end: usize,
+}
+
+impl<'a> JsonEntry<'a> {
+    fn new(entry: &'a Entry, line: usize) -> Self {
+        let mut json = JsonEntry {
+            line,
+            blank: None,
+            comment: None,
+            expression: None,
+            result: None,
+            formatted: None,
+            error: None,
+        };
+        match entry {
+            Entry::Blank => json.blank = Some(true),
+            Entry::Comment(comment) => json.comment = Some(comment),
+            Entry::Value {
+                expression,
+                value,
+                formatted,
+            } => {
+                json.expression = Some(expression);
+                json.result = Some(*value);
+                json.formatted = Some(formatted);
+            }
+            Entry::Error { expression, error } => {
+                json.expression = Some(expression);
+                json.error = Some(JsonError {
+                    kind: error.kind.name(),
+                    message: error.to_string(),
+                    start: error.span.start,
+                    end: error.span.end,
+                });
+            }
+        }
+        json
+    }
+}
+
+/// Quotes a CSV field if it contains a separator, a quote or surrounding spaces.
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) || text.trim() != text {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Evaluates every line of the inputs, one input after the other, and writes an output
+/// line for each. Lines are numbered across all inputs.
+///
+/// Returns the number of expressions that failed. With `fail_fast` the batch stops after
+/// writing the first failure.
+///
+/// # Examples
+///
+/// ```
+/// use rust_calculator::batch::{BatchOptions, OutputFormat, run};
+///
+/// let mut output = Vec::new();
+/// let options = BatchOptions { output: OutputFormat::Csv, ..BatchOptions::default() };
+/// let failed = run([&b"7+8*3\n\n1/0\n"[..]], &mut output, &options).unwrap();
+/// assert_eq!(failed, 1);
+/// assert_eq!(
+///     String::from_utf8(output).unwrap(),
+///     "line,expression,result,error\n1,7+8*3,31,\n2,,,\n3,1/0,,Division by zero\n"
+/// );
+/// ```
+pub fn run(
+    inputs: impl IntoIterator<Item = impl BufRead>,
+    out: &mut impl Write,
+    options: &BatchOptions,
+) -> io::Result<usize> {
+    if options.output == OutputFormat::Csv {
+        writeln!(out, "{}", CSV_HEADER)?;
+    }
+
+    let mut line_number = 0;
+    let mut failed = 0;
+    for input in inputs {
+        for line in input.lines() {
+            line_number += 1;
+            let entry = Entry::evaluate(&line?, &options.eval);
+            entry.write(out, options.output, line_number)?;
+            if let Entry::Error { .. } = entry {
+                failed += 1;
+                if options.fail_fast {
+                    out.flush()?;
+                    return Ok(failed);
+                }
+            }
+        }
+    }
+    out.flush()?;
+    Ok(failed)
+}
@@ -1,5 +1,13 @@
+use crate::expression;
+use crate::settings::{NumberFormat, Settings};
+
+pub use crate::expression::{
+    AngleMode, CalculatorError, Constant, ExpressionError, Function, SyntaxError,
+};
+
 /// Represents a basic calculator with expression evaluation capabilities.
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Calculator {
     /// The current expression being built
     pub expression: String,
@@ -7,10 +15,21 @@ pub struct Calculator {
     pub display: String,
     /// Whether the next input should start a new number
     pub new_input: bool,
+    /// Unit used for trigonometric functions
+    pub angle_mode: AngleMode,
+    /// Cursor position as a character index into `expression`.
+    /// `None` keeps the cursor pinned to the end of the expression.
+    pub cursor: Option<usize>,
+    /// Why the last evaluation of `expression` failed, and where; cleared by the next edit
+    pub error: Option<ExpressionError>,
+    /// Precision and notation of results
+    pub number_format: NumberFormat,
 }
 
 /// Mathematical operations supported by the calculator.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Operation {
     /// Addition operation
     Add,
@@ -20,464 +39,48 @@ pub enum Operation {
     Multiply,
     /// Division operation
     Divide,
+    /// Exponentiation operation
+    Power,
 }
 
-/// Tokens used in expression parsing for the shunting-yard algorithm.
-#[derive(Debug, Clone, PartialEq)]
-enum Token {
-    /// Numeric value
-    Number(f64),
-    /// Addition operator
-    Plus,
-    /// Binary subtraction operator
-    Minus,
-    /// Unary negation operator
-    UnaryMinus,
-    /// Multiplication operator
-    Multiply,
-    /// Division operator
-    Divide,
-    /// Left parenthesis
-    LeftParen,
-    /// Right parenthesis
-    RightParen,
-}
-
-/// Represents operator precedence and associativity.
-#[derive(Debug, Clone, Copy, PartialEq)]
-struct OperatorInfo {
-    precedence: u8,
-    left_associative: bool,
-}
-
-impl Token {
-    /// Returns operator information for tokens that are operators.
-    fn operator_info(&self) -> Option<OperatorInfo> {
-        match self {
-            Token::Plus | Token::Minus => Some(OperatorInfo {
-                precedence: 1,
-                left_associative: true,
-            }),
-            Token::Multiply | Token::Divide => Some(OperatorInfo {
-                precedence: 2, // Same precedence, left-associative
-                left_associative: true,
-            }),
-            Token::UnaryMinus => Some(OperatorInfo {
-                precedence: 3,           // Highest precedence for unary operators
-                left_associative: false, // Right-associative
-            }),
-            _ => None,
-        }
-    }
-
-    /// Checks if this token is a left parenthesis.
-    fn is_left_paren(&self) -> bool {
-        matches!(self, Token::LeftParen)
-    }
-}
-
-/// Custom error type for calculator operations.
-#[derive(Debug, Clone, PartialEq)]
-pub enum CalculatorError {
-    /// Division by zero error
-    DivisionByZero,
-    /// Invalid number format
-    InvalidNumber(String),
-    /// Invalid operation or syntax
-    InvalidExpression(String),
-    /// Input exceeds maximum allowed length
-    InputTooLong,
-    /// Input contains invalid characters
-    InvalidCharacters(String),
-    /// Numeric value out of allowed range
-    NumberOutOfRange(String),
-}
-
-impl std::fmt::Display for CalculatorError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            CalculatorError::DivisionByZero => write!(f, "Division by zero"),
-            CalculatorError::InvalidNumber(s) => write!(f, "Invalid number: {}", s),
-            CalculatorError::InvalidExpression(s) => write!(f, "Invalid expression: {}", s),
-            CalculatorError::InputTooLong => write!(f, "Input too long"),
-            CalculatorError::InvalidCharacters(s) => write!(f, "Invalid characters: {}", s),
-            CalculatorError::NumberOutOfRange(s) => write!(f, "Number out of range: {}", s),
-        }
-    }
-}
-
-impl std::error::Error for CalculatorError {}
-
 impl Calculator {
-    /// Tokenizes an input expression into tokens for the shunting-yard algorithm.
-    ///
-    /// Handles numbers, operators, and parentheses. Detects unary minus operations.
-    ///
-    /// # Arguments
-    /// * `input` - The input expression string
-    ///
-    /// # Returns
-    /// * `Ok(Vec<Token>)` - Successfully tokenized expression
-    /// * `Err(String)` - Tokenization error with description
-    fn tokenize(input: &str) -> Result<Vec<Token>, String> {
-        let mut tokens = Vec::new();
-        let mut chars = input.chars().peekable();
-        let mut expect_operand = true; // Track if we expect an operand (number/paren) or operator
-        let mut prev_was_binary_op = false; // Track if previous token was a binary operator
-
-        while let Some(&ch) = chars.peek() {
-            match ch {
-                '0'..='9' | '.' => {
-                    // Parse number (including scientific notation)
-                    let mut num_str = String::new();
-                    let mut has_dot = false;
-                    let mut has_e = false;
-
-                    while let Some(&c) = chars.peek() {
-                        match c {
-                            '0'..='9' => {
-                                num_str.push(c);
-                                chars.next();
-                            }
-                            '.' => {
-                                if has_dot {
-                                    return Err(format!(
-                                        "Invalid number format: multiple decimal points in '{}'",
-                                        num_str + "."
-                                    ));
-                                }
-                                has_dot = true;
-                                num_str.push(c);
-                                chars.next();
-                            }
-                            'e' | 'E' => {
-                                if has_e {
-                                    return Err(format!(
-                                        "Invalid number format: multiple 'e' in '{}'",
-                                        num_str + "e"
-                                    ));
-                                }
-                                has_e = true;
-                                num_str.push(c);
-                                chars.next();
-
-                                // Handle optional sign after 'e'
-                                if let Some(&next) = chars.peek()
-                                    && (next == '+' || next == '-')
-                                {
-                                    num_str.push(next);
-                                    chars.next();
-                                }
-                            }
-                            _ => break,
-                        }
-                    }
-
-                    // Parse the number
-                    match Self::safe_parse_number(&num_str) {
-                        Ok(num) => tokens.push(Token::Number(num)),
-                        Err(e) => return Err(e.to_string()),
-                    }
-                    expect_operand = false;
-                    prev_was_binary_op = false; // Numbers are not operators
-                }
-                '+' => {
-                    if expect_operand {
-                        return Err("Unexpected '+' operator".to_string());
-                    }
-                    // Check for consecutive operators
-                    if prev_was_binary_op {
-                        return Err("Consecutive operators".to_string());
-                    }
-                    tokens.push(Token::Plus);
-                    chars.next();
-                    expect_operand = true;
-                    prev_was_binary_op = true;
-                }
-                '-' => {
-                    chars.next();
-                    if expect_operand && !prev_was_binary_op {
-                        // Unary minus only allowed at the start or after parentheses
-                        tokens.push(Token::UnaryMinus);
-                        expect_operand = true;
-                        prev_was_binary_op = false; // Unary minus doesn't count as binary operator
-                    } else if expect_operand && prev_was_binary_op {
-                        // After a binary operator, - followed by nothing is invalid
-                        return Err("Consecutive operators".to_string());
-                    } else {
-                        // When not expecting an operand, - is a binary operator
-                        // Check for consecutive binary operators
-                        if prev_was_binary_op {
-                            return Err("Consecutive operators".to_string());
-                        }
-                        tokens.push(Token::Minus);
-                        expect_operand = true;
-                        prev_was_binary_op = true;
-                    }
-                }
-                'x' | 'X' | '*' => {
-                    if expect_operand {
-                        return Err("Unexpected multiplication operator".to_string());
-                    }
-                    // Check for consecutive operators
-                    if prev_was_binary_op {
-                        return Err("Consecutive operators".to_string());
-                    }
-                    tokens.push(Token::Multiply);
-                    chars.next();
-                    expect_operand = true;
-                    prev_was_binary_op = true;
-                }
-                '/' | '÷' => {
-                    if expect_operand {
-                        return Err("Unexpected division operator".to_string());
-                    }
-                    // Check for consecutive operators
-                    if prev_was_binary_op {
-                        return Err("Consecutive operators".to_string());
-                    }
-                    tokens.push(Token::Divide);
-                    chars.next();
-                    expect_operand = true;
-                    prev_was_binary_op = true;
-                }
-                '(' => {
-                    // Check for consecutive operators (parentheses can follow operators)
-                    tokens.push(Token::LeftParen);
-                    chars.next();
-                    expect_operand = true;
-                    prev_was_binary_op = false; // Parentheses are not operators
-                }
-                ')' => {
-                    if expect_operand {
-                        return Err("Unexpected ')' - missing operand".to_string());
-                    }
-                    tokens.push(Token::RightParen);
-                    chars.next();
-                    expect_operand = false;
-                    prev_was_binary_op = false; // Parentheses are not operators
-                }
-                ' ' => {
-                    // Skip whitespace
-                    chars.next();
-                }
-                _ => {
-                    return Err(format!("Invalid character: {}", ch));
-                }
-            }
-        }
+    /// Maximum allowed input length for security (prevents resource exhaustion)
+    pub const MAX_INPUT_LENGTH: usize = expression::MAX_INPUT_LENGTH;
 
-        Ok(tokens)
+    /// Validates input string for security constraints, like [`expression::validate_input`].
+    pub fn validate_input(input: &str) -> Result<(), CalculatorError> {
+        expression::validate_input(input)
     }
 
-    /// Converts infix tokens to postfix notation using the shunting-yard algorithm.
-    ///
-    /// # Arguments
-    /// * `tokens` - Vector of infix tokens
-    ///
-    /// # Returns
-    /// * `Ok(Vec<Token>)` - Postfix tokens ready for evaluation
-    /// * `Err(String)` - Conversion error with description
-    fn shunting_yard(tokens: Vec<Token>) -> Result<Vec<Token>, String> {
-        let mut output: Vec<Token> = Vec::new();
-        let mut operator_stack: Vec<Token> = Vec::new();
-
-        for token in tokens {
-            match token {
-                Token::Number(_) => {
-                    output.push(token);
-                }
-                Token::UnaryMinus => {
-                    operator_stack.push(token);
-                }
-                Token::Plus | Token::Minus | Token::Multiply | Token::Divide => {
-                    while let Some(top) = operator_stack.last() {
-                        if top.is_left_paren() {
-                            break;
-                        }
-
-                        if let (Some(current_info), Some(top_info)) =
-                            (token.operator_info(), top.operator_info())
-                        {
-                            if top_info.precedence > current_info.precedence
-                                || (top_info.precedence == current_info.precedence
-                                    && current_info.left_associative)
-                            {
-                                output.push(operator_stack.pop().unwrap());
-                            } else {
-                                break;
-                            }
-                        } else {
-                            break;
-                        }
-                    }
-                    operator_stack.push(token);
-                }
-                Token::LeftParen => {
-                    operator_stack.push(token);
-                }
-                Token::RightParen => {
-                    let mut found_left_paren = false;
-                    while let Some(op) = operator_stack.pop() {
-                        if op.is_left_paren() {
-                            found_left_paren = true;
-                            break;
-                        }
-                        output.push(op);
-                    }
-                    if !found_left_paren {
-                        return Err("Mismatched parentheses".to_string());
-                    }
-                }
-            }
-        }
-
-        // Pop remaining operators
-        while let Some(op) = operator_stack.pop() {
-            if op.is_left_paren() {
-                return Err("Mismatched parentheses".to_string());
-            }
-            output.push(op);
-        }
-
-        Ok(output)
+    /// Parses a number with bounds checking, like [`expression::safe_parse_number`].
+    pub fn safe_parse_number(s: &str) -> Result<f64, CalculatorError> {
+        expression::safe_parse_number(s)
     }
 
-    /// Evaluates postfix notation tokens.
+    /// Normalizes text coming from outside the keypad (e.g. the clipboard) into the
+    /// calculator's own notation.
     ///
-    /// # Arguments
-    /// * `tokens` - Vector of postfix tokens
+    /// Whitespace and thousands separators are dropped, `*`, `×` and `X` become `x`,
+    /// `/` becomes `÷` and the typographic minus `−` becomes `-`.
     ///
-    /// # Returns
-    /// * `Ok(f64)` - Result of the evaluation
-    /// * `Err(String)` - Evaluation error with description
-    fn evaluate_postfix(tokens: Vec<Token>) -> Result<f64, String> {
-        let mut stack = Vec::new();
-
-        for token in tokens {
-            match token {
-                Token::Number(num) => {
-                    stack.push(num);
-                }
-                Token::UnaryMinus => {
-                    let a = stack.pop().ok_or("Invalid expression: missing operand")?;
-                    stack.push(-a);
-                }
-                Token::Plus => {
-                    let b = stack.pop().ok_or("Invalid expression: missing operand")?;
-                    let a = stack.pop().ok_or("Invalid expression: missing operand")?;
-                    stack.push(a + b);
-                }
-                Token::Minus => {
-                    let b = stack.pop().ok_or("Invalid expression: missing operand")?;
-                    let a = stack.pop().ok_or("Invalid expression: missing operand")?;
-                    stack.push(a - b);
-                }
-                Token::Multiply => {
-                    let b = stack.pop().ok_or("Invalid expression: missing operand")?;
-                    let a = stack.pop().ok_or("Invalid expression: missing operand")?;
-                    stack.push(a * b);
-                }
-                Token::Divide => {
-                    let b = stack.pop().ok_or("Invalid expression: missing operand")?;
-                    let a = stack.pop().ok_or("Invalid expression: missing operand")?;
-                    if b == 0.0 {
-                        return Err(CalculatorError::DivisionByZero.to_string());
-                    }
-                    stack.push(a / b);
-                }
-                _ => {
-                    return Err(format!(
-                        "Unexpected token in postfix evaluation: {:?}",
-                        token
-                    ));
-                }
-            }
-        }
-
-        if stack.len() != 1 {
-            return Err("Invalid expression: too many operands".to_string());
-        }
-
-        let result = stack[0];
-        // Check final result bounds
-        if !result.is_finite() || result.abs() > 1e100 {
-            return Err(CalculatorError::NumberOutOfRange(result.to_string()).to_string());
-        }
-
-        Ok(result)
-    }
-
-    /// Maximum allowed input length for security (prevents resource exhaustion)
-    pub const MAX_INPUT_LENGTH: usize = 1000;
-
-    /// Validates input string for security constraints
+    /// # Examples
     ///
-    /// # Arguments
-    /// * `input` - The input string to validate
+    /// ```
+    /// use rust_calculator::Calculator;
     ///
-    /// # Returns
-    /// * `Ok(())` if input is valid
-    /// * `Err(CalculatorError)` if input is invalid
-    pub fn validate_input(input: &str) -> Result<(), CalculatorError> {
-        // Check input length
-        if input.len() > Self::MAX_INPUT_LENGTH {
-            return Err(CalculatorError::InputTooLong);
-        }
-
-        // Check for valid characters only (digits, operators, decimal point, scientific notation, whitespace, parentheses)
-        // Parentheses are allowed for display purposes but not evaluated
-        let invalid_chars: Vec<char> = input
+    /// assert_eq!(Calculator::normalize_input(" 1,234 * (5 − 2) "), "1234x(5-2)");
+    /// ```
+    pub fn normalize_input(input: &str) -> String {
+        input
             .chars()
-            .filter(|&c| {
-                !matches!(
-                    c,
-                    '0'..='9'
-                        | '+'
-                        | '-'
-                        | 'x'
-                        | 'X'
-                        | '*'
-                        | '/'
-                        | '÷'
-                        | '.'
-                        | 'e'
-                        | 'E'
-                        | '('
-                        | ')'
-                        | ' '
-                )
+            .filter(|c| !c.is_whitespace() && *c != ',')
+            .map(|c| match c {
+                '*' | '×' | 'X' => 'x',
+                '/' => '÷',
+                '−' => '-',
+                c => c,
             })
-            .collect();
-
-        if !invalid_chars.is_empty() {
-            return Err(CalculatorError::InvalidCharacters(
-                invalid_chars.into_iter().collect(),
-            ));
-        }
-
-        Ok(())
-    }
-
-    /// Safely parses a number with bounds checking
-    ///
-    /// # Arguments
-    /// * `s` - String slice to parse
-    ///
-    /// # Returns
-    /// * `Ok(f64)` if parsing succeeds and number is in valid range
-    /// * `Err(CalculatorError)` if parsing fails or number is out of range
-    pub fn safe_parse_number(s: &str) -> Result<f64, CalculatorError> {
-        let num = s
-            .parse::<f64>()
-            .map_err(|_| CalculatorError::InvalidNumber(s.to_string()))?;
-
-        // Check for reasonable bounds to prevent extreme values
-        if !num.is_finite() || num.abs() > 1e100 {
-            return Err(CalculatorError::NumberOutOfRange(s.to_string()));
-        }
-
-        Ok(num)
+            .collect()
     }
 
     /// Creates a new calculator instance with default values.
@@ -486,6 +89,23 @@ impl Calculator {
             expression: "0".to_string(),
             display: "0".to_string(),
             new_input: false,
+            angle_mode: AngleMode::Degrees,
+            cursor: None,
+            error: None,
+            number_format: NumberFormat::default(),
+        }
+    }
+
+    /// Takes over the angle mode and result format from the user settings and
+    /// reformats a result that is currently shown.
+    pub fn apply_settings(&mut self, settings: &Settings) {
+        self.angle_mode = settings.angle_mode;
+        self.number_format = settings.number_format();
+        if self.new_input
+            && self.error.is_none()
+            && let Ok(value) = self.expression.parse::<f64>()
+        {
+            self.display = self.format_value(value);
         }
     }
 
@@ -495,6 +115,7 @@ impl Calculator {
     /// Parentheses have the highest precedence, followed by multiplication and division,
     /// then addition and subtraction. Supports unary minus operations.
     /// Input is validated for security constraints before evaluation.
+    /// Errors are returned as English text; `evaluate_spanned` returns them typed.
     ///
     /// # Examples
     ///
@@ -508,29 +129,41 @@ impl Calculator {
     /// assert_eq!(calc.evaluate("-5+3"), Ok(-2.0));
     /// ```
     pub fn evaluate(&self, expr: &str) -> Result<f64, String> {
-        // Security: Validate input first
-        if let Err(e) = Self::validate_input(expr) {
-            return Err(e.to_string());
-        }
-
-        let trimmed = expr.trim();
-        if trimmed.is_empty() || trimmed == "0" {
-            return Ok(0.0);
-        }
-
-        // For single numbers, validate the number directly
-        if !trimmed.contains(&['+', '-', 'x', 'X', '*', '/', '÷', '(', ')'][..]) {
-            return Self::safe_parse_number(trimmed).map_err(|e| e.to_string());
-        }
-
-        // Tokenize the input
-        let tokens = Self::tokenize(trimmed)?;
+        self.evaluate_spanned(expr)
+            .map_err(|error| error.to_string())
+    }
 
-        // Convert to postfix notation
-        let postfix = Self::shunting_yard(tokens)?;
+    /// Evaluates an expression like `evaluate`, but errors also tell which characters of
+    /// the expression are at fault.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::Calculator;
+    ///
+    /// let calc = Calculator::new();
+    /// assert_eq!(calc.evaluate_spanned("5+*3").unwrap_err().span, 2..3);
+    /// assert_eq!(calc.evaluate_spanned("(2+3").unwrap_err().span, 0..1);
+    /// assert_eq!(calc.evaluate_spanned("1+4÷(2-2)").unwrap_err().span, 5..8);
+    /// ```
+    pub fn evaluate_spanned(&self, expr: &str) -> Result<f64, ExpressionError> {
+        expression::evaluate(expr, self.angle_mode)
+    }
 
-        // Evaluate the postfix expression
-        Self::evaluate_postfix(postfix)
+    /// Checks that an expression is well formed without evaluating it, so errors that
+    /// depend on operand values (division by zero, domain errors) are not reported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::Calculator;
+    ///
+    /// assert_eq!(Calculator::check_syntax("1÷0"), Ok(()));
+    /// assert_eq!(Calculator::check_syntax("ln(-1)"), Ok(()));
+    /// assert!(Calculator::check_syntax("2x(3+").is_err());
+    /// ```
+    pub fn check_syntax(expr: &str) -> Result<(), CalculatorError> {
+        expression::check_syntax(expr)
     }
 
     /// Extracts the operands around an operator position with bounds checking.
@@ -690,10 +323,10 @@ impl Calculator {
     }
 
     /// Evaluates addition and subtraction operations with bounds checking.
-    pub fn evaluate_add_sub_safe(&self, expr: &str) -> Result<f64, String> {
+    pub fn evaluate_add_sub_safe(&self, expr: &str) -> Result<f64, CalculatorError> {
         // If the expression contains no operators, just parse the number directly
         if !expr.contains(&['+', '-'][..]) {
-            return Self::safe_parse_number(expr.trim()).map_err(|e| e.to_string());
+            return Self::safe_parse_number(expr.trim());
         }
 
         let mut result = 0.0;
@@ -705,7 +338,7 @@ impl Calculator {
                 current_num.push(c);
             } else if c == '+' || c == '-' {
                 if !current_num.is_empty() {
-                    let num = Self::safe_parse_number(&current_num).map_err(|e| e.to_string())?;
+                    let num = Self::safe_parse_number(&current_num)?;
                     match current_op {
                         '+' => result += num,
                         '-' => result -= num,
@@ -716,13 +349,13 @@ impl Calculator {
                 current_op = c;
             } else {
                 // If we encounter any other character, it's an error
-                return Err(format!("Invalid character '{}' in expression", c));
+                return Err(CalculatorError::InvalidCharacters(c.to_string()));
             }
         }
 
         // Handle the last number
         if !current_num.is_empty() {
-            let num = Self::safe_parse_number(&current_num).map_err(|e| e.to_string())?;
+            let num = Self::safe_parse_number(&current_num)?;
             match current_op {
                 '+' => result += num,
                 '-' => result -= num,
@@ -732,14 +365,14 @@ impl Calculator {
 
         // Check final result bounds
         if !result.is_finite() || result.abs() > 1e100 {
-            return Err(CalculatorError::NumberOutOfRange(result.to_string()).to_string());
+            return Err(CalculatorError::NumberOutOfRange(result.to_string()));
         }
 
         Ok(result)
     }
 
     /// Evaluates addition and subtraction operations.
-    pub fn evaluate_add_sub(&self, expr: &str) -> Result<f64, String> {
+    pub fn evaluate_add_sub(&self, expr: &str) -> Result<f64, CalculatorError> {
         let mut result = 0.0;
         let mut current_op = '+';
         let mut current_num = String::new();
@@ -751,7 +384,7 @@ impl Calculator {
                 if !current_num.is_empty() {
                     let num: f64 = current_num
                         .parse()
-                        .map_err(|_| "Invalid number".to_string())?;
+                        .map_err(|_| CalculatorError::InvalidNumber(current_num.clone()))?;
                     match current_op {
                         '+' => result += num,
                         '-' => result -= num,
@@ -767,7 +400,7 @@ impl Calculator {
         if !current_num.is_empty() {
             let num: f64 = current_num
                 .parse()
-                .map_err(|_| "Invalid number".to_string())?;
+                .map_err(|_| CalculatorError::InvalidNumber(current_num.clone()))?;
             match current_op {
                 '+' => result += num,
                 '-' => result -= num,
@@ -788,7 +421,7 @@ impl Calculator {
     /// let calc = Calculator::new();
     /// assert_eq!(calc.calculate(Operation::Add, 5.0, 3.0), Ok(8.0));
     /// ```
-    pub fn calculate(&self, operation: Operation, a: f64, b: f64) -> Result<f64, String> {
+    pub fn calculate(&self, operation: Operation, a: f64, b: f64) -> Result<f64, CalculatorError> {
         match operation {
             Operation::Add => Ok(a + b),
             Operation::Subtract => Ok(a - b),
@@ -797,9 +430,10 @@ impl Calculator {
                 if b != 0.0 {
                     Ok(a / b)
                 } else {
-                    Err("Error".to_string())
+                    Err(CalculatorError::DivisionByZero)
                 }
             }
+            Operation::Power => expression::power(a, b),
         }
     }
 }
@@ -0,0 +1,271 @@
+use crate::batch::{BatchOptions, OutputFormat};
+use crate::calculator::{AngleMode, Calculator, ExpressionError};
+use crate::settings::{DisplayFormat, NumberFormat, Settings};
+use std::fmt;
+
+/// Usage text printed by `--help` and after a usage error.
+pub const USAGE: &str = "\
+Usage: rust-calculator [OPTIONS]
+       rust-calculator repl [OPTIONS]
+       rust-calculator batch [OPTIONS] [FILE]...
+       rust-calculator serve (--stdio | --http <ADDR>) [OPTIONS]
+
+Without options the calculator window opens. `repl` starts an interactive
+session in the terminal; type :help there for its commands. `batch` evaluates
+one expression per line of the files, or of standard input without files or
+for `-`, keeping blank lines and `#` comments in its output. `serve --stdio`
+answers JSON-RPC 2.0 requests for editor integrations, one per line on standard
+input; `serve --http` answers POST /evaluate, POST /batch and GET /health.
+
+Options:
+  -e, --eval <EXPR>        Print the value of EXPR and exit
+  -p, --precision <N>      Decimal places of results, 0 to 15 (default 8)
+  -f, --format <FORMAT>    auto, fixed or scientific (default auto)
+  -a, --angle <UNIT>       degrees or radians (default degrees)
+  -o, --output <FORMAT>    batch output: plain, csv or jsonl (default plain)
+      --fail-fast          batch: stop at the first error and exit with 1
+      --stdio              serve: talk over standard input and output
+      --http <ADDR>        serve: listen for HTTP on ADDR, e.g. 127.0.0.1:8080
+  -h, --help               Print this help
+";
+
+/// What the program was asked to do on the command line.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Open the calculator window
+    Gui,
+    /// Evaluate one expression, print the result and exit
+    Evaluate {
+        expression: String,
+        options: EvalOptions,
+    },
+    /// Start an interactive session in the terminal
+    Repl { options: EvalOptions },
+    /// Evaluate every line of the files, or of standard input if there are none
+    Batch {
+        files: Vec<String>,
+        options: BatchOptions,
+    },
+    /// Answer requests of editors or dashboards, evaluated with `options` by default
+    Serve {
+        transport: Transport,
+        options: EvalOptions,
+    },
+    /// Print the usage text
+    Help,
+}
+
+/// Where `serve` takes its requests from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Transport {
+    /// JSON-RPC, one message per line on standard input and output
+    Stdio,
+    /// HTTP on the given address, e.g. `127.0.0.1:8080`
+    Http(String),
+}
+
+/// How expressions are evaluated and results are written on the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct EvalOptions {
+    /// Precision and notation of results
+    pub number_format: NumberFormat,
+    /// Unit for trigonometric functions
+    pub angle_mode: AngleMode,
+}
+
+impl EvalOptions {
+    /// A calculator set up with these options.
+    pub fn calculator(&self) -> Calculator {
+        Calculator {
+            angle_mode: self.angle_mode,
+            number_format: self.number_format,
+            ..Calculator::new()
+        }
+    }
+
+    /// Evaluates an expression and formats the result.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::cli::EvalOptions;
+    /// use rust_calculator::settings::{DisplayFormat, NumberFormat};
+    ///
+    /// let options = EvalOptions::default();
+    /// assert_eq!(options.evaluate("7+8*3").unwrap(), "31");
+    /// assert!(options.evaluate("1/0").is_err());
+    ///
+    /// let fixed = EvalOptions {
+    ///     number_format: NumberFormat { precision: 2, display_format: DisplayFormat::Fixed },
+    ///     ..EvalOptions::default()
+    /// };
+    /// assert_eq!(fixed.evaluate("2/3").unwrap(), "0.67");
+    /// ```
+    pub fn evaluate(&self, expression: &str) -> Result<String, ExpressionError> {
+        let calculator = self.calculator();
+        calculator
+            .evaluate_spanned(expression)
+            .map(|value| calculator.format_value(value))
+    }
+}
+
+/// Parses the command-line arguments, without the program name.
+///
+/// # Examples
+///
+/// ```
+/// use rust_calculator::cli::{Command, EvalOptions, parse_args};
+///
+/// assert_eq!(parse_args(Vec::<String>::new()), Ok(Command::Gui));
+/// assert_eq!(
+///     parse_args(["-e", "7+8*3"].map(String::from)),
+///     Ok(Command::Evaluate {
+///         expression: "7+8*3".to_string(),
+///         options: EvalOptions::default(),
+///     })
+/// );
+/// assert!(parse_args(["--precision", "99", "-e", "1"].map(String::from)).is_err());
+/// assert!(matches!(
+///     parse_args(["repl", "-p", "3"].map(String::from)),
+///     Ok(Command::Repl { options }) if options.number_format.precision == 3
+/// ));
+/// assert!(matches!(
+///     parse_args(["batch", "-o", "csv", "exprs.txt"].map(String::from)),
+///     Ok(Command::Batch { files, .. }) if files == ["exprs.txt"]
+/// ));
+/// ```
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let mut args = args.into_iter().peekable();
+    let subcommand = args.next_if(|arg| ["repl", "batch", "serve"].contains(&arg.as_str()));
+    let batch = subcommand.as_deref() == Some("batch");
+    let serve = subcommand.as_deref() == Some("serve");
+    let mut expression = None;
+    let mut options = EvalOptions::default();
+    let mut has_options = false;
+    let mut output = OutputFormat::default();
+    let mut fail_fast = false;
+    let mut transport = None;
+    let mut files = Vec::new();
+
+    while let Some(arg) = args.next() {
+        // `--name=value` is the same as `--name value`
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        let mut value = || {
+            inline_value
+                .map(str::to_string)
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("`{}` needs a value", flag))
+        };
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-e" | "--eval" => expression = Some(value()?),
+            "-p" | "--precision" => {
+                options.number_format.precision = parse_precision(&value()?)?;
+                has_options = true;
+            }
+            "-f" | "--format" => {
+                options.number_format.display_format = parse_format(&value()?)?;
+                has_options = true;
+            }
+            "-a" | "--angle" => {
+                options.angle_mode = parse_angle_mode(&value()?)?;
+                has_options = true;
+            }
+            "-o" | "--output" if batch => output = parse_output(&value()?)?,
+            "--fail-fast" if batch => fail_fast = true,
+            "--stdio" | "--http" if serve && transport.is_some() => {
+                return Err("`serve` takes one of --stdio and --http".to_string());
+            }
+            "--stdio" if serve => transport = Some(Transport::Stdio),
+            "--http" if serve => transport = Some(Transport::Http(value()?)),
+            // Standard input can only be read once
+            "-" if batch && files.iter().any(|file| file == "-") => {
+                return Err("standard input (`-`) can only be given once".to_string());
+            }
+            _ if batch && (arg == "-" || !arg.starts_with('-')) => files.push(arg),
+            _ => return Err(format!("unexpected argument `{}`", arg)),
+        }
+    }
+
+    match (subcommand.as_deref(), expression) {
+        (Some(subcommand), Some(_)) => Err(format!(
+            "`{}` reads its expressions from {}, not -e",
+            subcommand,
+            match subcommand {
+                "batch" => "files",
+                "serve" => "requests",
+                _ => "the terminal",
+            }
+        )),
+        (Some("repl"), None) => Ok(Command::Repl { options }),
+        (Some("serve"), None) => match transport {
+            Some(transport) => Ok(Command::Serve { transport, options }),
+            None => Err("`serve` needs --stdio or --http <ADDR>".to_string()),
+        },
+        (Some(_), None) => Ok(Command::Batch {
+            files,
+            options: BatchOptions {
+                eval: options,
+                output,
+                fail_fast,
+            },
+        }),
+        (None, Some(expression)) => Ok(Command::Evaluate {
+            expression,
+            options,
+        }),
+        (None, None) if has_options => {
+            Err("options need an expression to evaluate (-e)".to_string())
+        }
+        (None, None) => Ok(Command::Gui),
+    }
+}
+
+pub(crate) fn parse_precision(value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .map_err(|_| precision_error(value))
+        .and_then(validate_precision)
+}
+
+/// Checks a precision given as a number, as the JSON-RPC and HTTP servers receive it.
+pub(crate) fn validate_precision(precision: usize) -> Result<usize, String> {
+    if precision <= Settings::MAX_PRECISION {
+        Ok(precision)
+    } else {
+        Err(precision_error(precision))
+    }
+}
+
+fn precision_error(value: impl fmt::Display) -> String {
+    format!(
+        "precision must be a whole number from 0 to {}, not `{}`",
+        Settings::MAX_PRECISION,
+        value
+    )
+}
+
+pub(crate) fn parse_format(value: &str) -> Result<DisplayFormat, String> {
+    DisplayFormat::ALL
+        .into_iter()
+        .find(|format| format.name() == value)
+        .ok_or_else(|| format!("format must be auto, fixed or scientific, not `{}`", value))
+}
+
+fn parse_output(value: &str) -> Result<OutputFormat, String> {
+    OutputFormat::ALL
+        .into_iter()
+        .find(|format| format.name() == value)
+        .ok_or_else(|| format!("output must be plain, csv or jsonl, not `{}`", value))
+}
+
+pub(crate) fn parse_angle_mode(value: &str) -> Result<AngleMode, String> {
+    match value {
+        "degrees" | "deg" => Ok(AngleMode::Degrees),
+        "radians" | "rad" => Ok(AngleMode::Radians),
+        _ => Err(format!("angle must be degrees or radians, not `{}`", value)),
+    }
+}
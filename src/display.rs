@@ -1,49 +1,22 @@
 use crate::calculator::Calculator;
+use crate::settings::NumberFormat;
 
 impl Calculator {
     /// Formats large numbers in a string to scientific notation.
     pub fn format_large_numbers(&self, expr: &str) -> String {
-        let mut result = String::new();
-        let chars: Vec<char> = expr.chars().collect();
-        let mut i = 0;
-        while i < chars.len() {
-            let c = chars[i];
-            if c.is_ascii_digit()
-                || c == '.'
-                || (c == '-' && i + 1 < chars.len() && chars[i + 1].is_ascii_digit())
-            {
-                let start = i;
-                i += 1;
-                while i < chars.len() && {
-                    let nc = chars[i];
-                    nc.is_ascii_digit()
-                        || nc == '.'
-                        || (nc == 'e' && i + 1 < chars.len() && {
-                            let next = chars[i + 1];
-                            next.is_ascii_digit() || next == '+' || next == '-'
-                        })
-                } {
-                    i += 1;
-                }
-                let num_str: String = chars[start..i].iter().collect();
-                if let Ok(value) = num_str.parse::<f64>() {
-                    if value.abs() >= 1e9
-                        || (value.abs() < 1.0 && value.abs() > 0.0)
-                        || (num_str.len() > 10 && !num_str.contains('.') && !num_str.contains('e'))
-                    {
-                        result.push_str(&format!("{:.1e}", value));
-                    } else {
-                        result.push_str(&num_str);
-                    }
-                } else {
-                    result.push_str(&num_str);
-                }
-            } else {
-                result.push(c);
-                i += 1;
-            }
-        }
-        result
+        format_large_numbers_mapped(expr, None).0
+    }
+
+    /// Formats an evaluation result for display with the default settings.
+    /// Very large and very small values use scientific notation; otherwise up to
+    /// 8 decimal places are shown with trailing zeros removed.
+    pub fn format_result(result: f64) -> String {
+        NumberFormat::default().format(result)
+    }
+
+    /// Formats an evaluation result for display with the configured precision and notation.
+    pub fn format_value(&self, result: f64) -> String {
+        self.number_format.format(result)
     }
 
     /// Returns the current expression for display purposes.
@@ -52,52 +25,238 @@ impl Calculator {
     /// Scientific notation is also used for results after equals.
     /// Negative operands in expressions are shown with parentheses for clarity.
     pub fn display_string(&self) -> String {
-        // Apply scientific notation formatting to the expression
-        let formatted = self.format_large_numbers(&self.expression);
+        self.format_expression(&self.expression)
+    }
+
+    /// Applies the display formatting rules to any expression: long numbers in
+    /// scientific notation and negative operands in parentheses.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::Calculator;
+    ///
+    /// let calculator = Calculator::new();
+    /// assert_eq!(calculator.format_expression("12x(3+4)"), "12x(3+4)");
+    /// assert_eq!(calculator.format_expression("5+-3"), "5+(-3)");
+    /// ```
+    pub fn format_expression(&self, expr: &str) -> String {
+        format_expression_mapped(expr, None).0
+    }
+
+    /// Returns the display text together with the position of the caret in it, in
+    /// characters. The caret is `None` while the cursor is pinned to the end.
+    ///
+    /// The text is formatted as by `display_string`; only a number the cursor is inside
+    /// is shown as typed, so the caret can sit between its digits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::Calculator;
+    ///
+    /// let mut calc = Calculator::new();
+    /// calc.expression = "5+-3".to_string();
+    /// assert_eq!(calc.display_string_and_caret(), ("5+(-3)".to_string(), None));
+    /// calc.cursor = Some(2);
+    /// assert_eq!(calc.display_string_and_caret(), ("5+(-3)".to_string(), Some(2)));
+    /// ```
+    pub fn display_string_and_caret(&self) -> (String, Option<usize>) {
+        let pos = self.cursor_position();
+        if pos == self.expression.chars().count() {
+            return (self.display_string(), None);
+        }
+        let (text, map) = format_expression_mapped(&self.expression, Some(pos));
+        (text, Some(map[pos]))
+    }
+
+    /// Splits the display text around the location of the last evaluation error, so the GUI
+    /// can highlight it. Returns the text before, the offending characters and the text
+    /// after, or `None` when there is no error.
+    ///
+    /// The raw expression is used so the highlighted range lines up with the characters it
+    /// refers to; the caret goes at `cursor_position` in it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::Calculator;
+    ///
+    /// let mut calc = Calculator::new();
+    /// calc.expression = "5+*3".to_string();
+    /// calc.handle_equals_input();
+    /// assert_eq!(
+    ///     calc.error_display_parts(),
+    ///     Some(("5+".to_string(), "*".to_string(), "3".to_string()))
+    /// );
+    /// ```
+    pub fn error_display_parts(&self) -> Option<(String, String, String)> {
+        let error = self.error.as_ref()?;
+        let chars: Vec<char> = self.expression.chars().collect();
+        let len = chars.len();
+
+        // Keep the range inside the expression and at least one character wide
+        let mut start = error.span.start.min(len);
+        let mut end = error.span.end.clamp(start, len);
+        if start == end {
+            if end < len {
+                end += 1;
+            } else {
+                start = start.saturating_sub(1);
+            }
+        }
+
+        Some((
+            chars[..start].iter().collect(),
+            chars[start..end].iter().collect(),
+            chars[end..].iter().collect(),
+        ))
+    }
 
-        // Add parentheses around negative operands in expressions
-        self.add_parentheses_to_negative_operands(&formatted)
+    /// Returns the closing parentheses that are still missing from the expression.
+    /// The GUI shows them as dimmed ghost characters; they are added for real on equals.
+    pub fn ghost_parens(&self) -> String {
+        ")".repeat(self.open_paren_depth())
+    }
+
+    /// Returns the cursor position as a fraction of the expression length (0.0 to 1.0).
+    /// Used by the GUI to keep the caret visible inside the scrollable display.
+    pub fn cursor_scroll_fraction(&self) -> f32 {
+        let len = self.expression.chars().count();
+        if len == 0 {
+            1.0
+        } else {
+            self.cursor_position() as f32 / len as f32
+        }
     }
 
     /// Adds parentheses around negative operands in expressions for display clarity.
     /// For example: "7+-9" becomes "7+(-9)"
     pub fn add_parentheses_to_negative_operands(&self, expr: &str) -> String {
-        let mut result = String::new();
-        let chars: Vec<char> = expr.chars().collect();
-        let mut i = 0;
-
-        while i < chars.len() {
-            let c = chars[i];
-
-            // Check if this is an operator followed by a negative number
-            if "+-x÷".contains(c) && i + 1 < chars.len() {
-                let next_char = chars[i + 1];
-                if next_char == '-' {
-                    // Found operator followed by negative sign
-                    result.push(c);
-                    result.push('(');
-                    result.push(next_char);
-                    i += 2;
-
-                    // Collect the rest of the negative number
-                    while i < chars.len() {
-                        let nc = chars[i];
-                        if nc.is_ascii_digit() || nc == '.' || nc == 'e' || nc == '+' || nc == '-' {
-                            result.push(nc);
-                            i += 1;
-                        } else {
-                            break;
-                        }
-                    }
-                    result.push(')');
-                    continue;
+        add_parentheses_mapped(expr).0
+    }
+}
+
+/// Applies the display formatting rules like `Calculator::format_expression`, and also
+/// returns where each character of `expr` ends up: the map has the position in the
+/// formatted text of every character, followed by the length of the formatted text.
+///
+/// A number that `keep` points inside of is left as typed.
+fn format_expression_mapped(expr: &str, keep: Option<usize>) -> (String, Vec<usize>) {
+    let (numbers, number_map) = format_large_numbers_mapped(expr, keep);
+    let (text, paren_map) = add_parentheses_mapped(&numbers);
+    let map = number_map.into_iter().map(|pos| paren_map[pos]).collect();
+    (text, map)
+}
+
+/// Writes long and tiny numbers in scientific notation, except a number that `keep`
+/// points inside of. Every character of a rewritten number maps to its start.
+fn format_large_numbers_mapped(expr: &str, keep: Option<usize>) -> (String, Vec<usize>) {
+    let mut result = String::new();
+    let mut map = Vec::new();
+    let mut len = 0;
+    let chars: Vec<char> = expr.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_ascii_digit()
+            || c == '.'
+            || (c == '-' && i + 1 < chars.len() && chars[i + 1].is_ascii_digit())
+        {
+            let start = i;
+            i += 1;
+            while i < chars.len() && {
+                let nc = chars[i];
+                nc.is_ascii_digit()
+                    || nc == '.'
+                    || (nc == 'e' && i + 1 < chars.len() && {
+                        let next = chars[i + 1];
+                        next.is_ascii_digit() || next == '+' || next == '-'
+                    })
+            } {
+                i += 1;
+            }
+            let num_str: String = chars[start..i].iter().collect();
+            let edited = keep.is_some_and(|pos| start < pos && pos < i);
+            let formatted = match num_str.parse::<f64>() {
+                Ok(value)
+                    if !edited
+                        && (value.abs() >= 1e9
+                            || (value.abs() < 1.0 && value.abs() > 0.0)
+                            || (num_str.len() > 10
+                                && !num_str.contains('.')
+                                && !num_str.contains('e'))) =>
+                {
+                    format!("{:.1e}", value)
                 }
+                _ => num_str,
+            };
+            if formatted.chars().count() == i - start {
+                map.extend(len..len + formatted.chars().count());
+            } else {
+                map.extend(std::iter::repeat_n(len, i - start));
             }
-
+            len += formatted.chars().count();
+            result.push_str(&formatted);
+        } else {
+            map.push(len);
+            len += 1;
             result.push(c);
             i += 1;
         }
+    }
+    map.push(len);
+    (result, map)
+}
+
+/// Puts negative operands in parentheses. The minus sign maps to the opening parenthesis,
+/// so a caret before it stays outside.
+fn add_parentheses_mapped(expr: &str) -> (String, Vec<usize>) {
+    let mut result = String::new();
+    let mut map = Vec::new();
+    let mut len = 0;
+    let chars: Vec<char> = expr.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        // Check if this is an operator followed by a negative number
+        if "+-x÷^".contains(c) && i + 1 < chars.len() {
+            let next_char = chars[i + 1];
+            if next_char == '-' {
+                // Found operator followed by negative sign
+                map.extend([len, len + 1]);
+                result.push(c);
+                result.push('(');
+                result.push(next_char);
+                len += 3;
+                i += 2;
+
+                // Collect the rest of the negative number
+                while i < chars.len() {
+                    let nc = chars[i];
+                    if nc.is_ascii_digit() || nc == '.' || nc == 'e' || nc == '+' || nc == '-' {
+                        map.push(len);
+                        result.push(nc);
+                        len += 1;
+                        i += 1;
+                    } else {
+                        break;
+                    }
+                }
+                result.push(')');
+                len += 1;
+                continue;
+            }
+        }
 
-        result
+        map.push(len);
+        result.push(c);
+        len += 1;
+        i += 1;
     }
+    map.push(len);
+
+    (result, map)
 }
@@ -0,0 +1,1128 @@
+use crate::locale::Locale;
+use crate::math;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
+
+/// Unit used for the arguments of trigonometric functions and the results of their inverses.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum AngleMode {
+    /// Angles in degrees
+    #[default]
+    Degrees,
+    /// Angles in radians
+    Radians,
+}
+
+impl AngleMode {
+    /// Returns the short indicator shown in the GUI.
+    pub fn label(self) -> &'static str {
+        match self {
+            AngleMode::Degrees => "DEG",
+            AngleMode::Radians => "RAD",
+        }
+    }
+
+    /// Returns the other angle mode.
+    pub fn toggled(self) -> Self {
+        match self {
+            AngleMode::Degrees => AngleMode::Radians,
+            AngleMode::Radians => AngleMode::Degrees,
+        }
+    }
+}
+
+impl fmt::Display for AngleMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AngleMode::Degrees => write!(f, "Degrees"),
+            AngleMode::Radians => write!(f, "Radians"),
+        }
+    }
+}
+
+/// Mathematical constants that can be used in expressions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Constant {
+    /// π (written as `π`)
+    Pi,
+    /// Euler's number (written as `e`)
+    E,
+}
+
+impl Constant {
+    /// Returns the symbol used for the constant in expressions.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Constant::Pi => "π",
+            Constant::E => "e",
+        }
+    }
+
+    /// Returns the numeric value of the constant.
+    pub fn value(self) -> f64 {
+        match self {
+            Constant::Pi => core::f64::consts::PI,
+            Constant::E => core::f64::consts::E,
+        }
+    }
+}
+
+/// Scientific functions that can be used in expressions, written as `name(argument)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Function {
+    /// Sine
+    Sin,
+    /// Cosine
+    Cos,
+    /// Tangent
+    Tan,
+    /// Inverse sine
+    Asin,
+    /// Inverse cosine
+    Acos,
+    /// Inverse tangent
+    Atan,
+    /// Natural logarithm
+    Ln,
+    /// Base-10 logarithm
+    Log,
+    /// Square root (written as `√`)
+    Sqrt,
+    /// Cube root (written as `∛`)
+    Cbrt,
+    /// Absolute value
+    Abs,
+}
+
+impl Function {
+    /// All supported functions.
+    pub const ALL: [Function; 11] = [
+        Function::Sin,
+        Function::Cos,
+        Function::Tan,
+        Function::Asin,
+        Function::Acos,
+        Function::Atan,
+        Function::Ln,
+        Function::Log,
+        Function::Sqrt,
+        Function::Cbrt,
+        Function::Abs,
+    ];
+
+    /// Returns the name used for the function in expressions.
+    pub fn name(self) -> &'static str {
+        match self {
+            Function::Sin => "sin",
+            Function::Cos => "cos",
+            Function::Tan => "tan",
+            Function::Asin => "asin",
+            Function::Acos => "acos",
+            Function::Atan => "atan",
+            Function::Ln => "ln",
+            Function::Log => "log",
+            Function::Sqrt => "√",
+            Function::Cbrt => "∛",
+            Function::Abs => "abs",
+        }
+    }
+
+    /// Looks up a function by the name used in expressions.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.name() == name)
+    }
+
+    /// Applies the function to a value.
+    ///
+    /// # Arguments
+    /// * `x` - The argument
+    /// * `angle_mode` - Unit for trigonometric arguments and inverse trigonometric results
+    ///
+    /// # Returns
+    /// * `Ok(f64)` - The function value
+    /// * `Err(CalculatorError::DomainError)` - The function is undefined for `x`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::{AngleMode, Function};
+    ///
+    /// assert_eq!(Function::Sin.apply(90.0, AngleMode::Degrees), Ok(1.0));
+    /// assert!(Function::Sqrt.apply(-4.0, AngleMode::Degrees).is_err());
+    /// ```
+    pub fn apply(self, x: f64, angle_mode: AngleMode) -> Result<f64, CalculatorError> {
+        let domain_error = || CalculatorError::DomainError(format!("{}({})", self.name(), x));
+        let to_radians = |v: f64| match angle_mode {
+            AngleMode::Degrees => v.to_radians(),
+            AngleMode::Radians => v,
+        };
+        let from_radians = |v: f64| match angle_mode {
+            AngleMode::Degrees => v.to_degrees(),
+            AngleMode::Radians => v,
+        };
+        // In degrees, exact multiples of 90° give exact results instead of rounding noise
+        let degrees = angle_mode == AngleMode::Degrees;
+
+        match self {
+            Function::Sin if degrees && x % 180.0 == 0.0 => Ok(0.0),
+            Function::Sin => Ok(math::sin(to_radians(x))),
+            Function::Cos if degrees && (x - 90.0) % 180.0 == 0.0 => Ok(0.0),
+            Function::Cos => Ok(math::cos(to_radians(x))),
+            Function::Tan if degrees && (x - 90.0) % 180.0 == 0.0 => Err(domain_error()),
+            Function::Tan if degrees && x % 180.0 == 0.0 => Ok(0.0),
+            Function::Tan => Ok(math::tan(to_radians(x))),
+            Function::Asin | Function::Acos if !(-1.0..=1.0).contains(&x) => Err(domain_error()),
+            Function::Asin => Ok(from_radians(math::asin(x))),
+            Function::Acos => Ok(from_radians(math::acos(x))),
+            Function::Atan => Ok(from_radians(math::atan(x))),
+            Function::Ln | Function::Log if x <= 0.0 => Err(domain_error()),
+            Function::Ln => Ok(math::ln(x)),
+            Function::Log => Ok(math::log10(x)),
+            Function::Sqrt if x < 0.0 => Err(domain_error()),
+            Function::Sqrt => Ok(math::sqrt(x)),
+            Function::Cbrt => Ok(math::cbrt(x)),
+            Function::Abs => Ok(math::abs(x)),
+        }
+    }
+}
+
+/// Tokens used in expression parsing for the shunting-yard algorithm.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// Numeric value
+    Number(f64),
+    /// Addition operator
+    Plus,
+    /// Binary subtraction operator
+    Minus,
+    /// Unary negation operator
+    UnaryMinus,
+    /// Multiplication operator
+    Multiply,
+    /// Division operator
+    Divide,
+    /// Exponentiation operator
+    Power,
+    /// Postfix factorial operator
+    Factorial,
+    /// Function applied to the following parenthesized group
+    Function(Function),
+    /// Left parenthesis
+    LeftParen,
+    /// Right parenthesis
+    RightParen,
+}
+
+/// A token together with the characters of the expression it came from.
+#[derive(Debug, Clone, PartialEq)]
+struct Spanned {
+    token: Token,
+    span: Range<usize>,
+}
+
+impl Spanned {
+    fn new(token: Token, span: Range<usize>) -> Self {
+        Self { token, span }
+    }
+}
+
+/// Represents operator precedence and associativity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct OperatorInfo {
+    precedence: u8,
+    left_associative: bool,
+}
+
+impl Token {
+    /// Returns operator information for tokens that are operators.
+    fn operator_info(&self) -> Option<OperatorInfo> {
+        match self {
+            Token::Plus | Token::Minus => Some(OperatorInfo {
+                precedence: 1,
+                left_associative: true,
+            }),
+            Token::Multiply | Token::Divide => Some(OperatorInfo {
+                precedence: 2, // Same precedence, left-associative
+                left_associative: true,
+            }),
+            Token::UnaryMinus => Some(OperatorInfo {
+                precedence: 3,           // Higher precedence than binary arithmetic
+                left_associative: false, // Right-associative
+            }),
+            Token::Power => Some(OperatorInfo {
+                precedence: 4,           // Binds tighter than unary minus: -2^2 = -4
+                left_associative: false, // Right-associative: 2^3^2 = 2^9
+            }),
+            _ => None,
+        }
+    }
+
+    /// Checks if this token is a left parenthesis.
+    fn is_left_paren(&self) -> bool {
+        matches!(self, Token::LeftParen)
+    }
+}
+
+/// Custom error type for calculator operations.
+///
+/// Errors are matched by variant, never by their text; `Display` gives the English
+/// message, and [`Locale::error_message`] the message in another language.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CalculatorError {
+    /// Division by zero error
+    DivisionByZero,
+    /// Invalid number format
+    InvalidNumber(String),
+    /// Invalid operation or syntax
+    InvalidExpression(SyntaxError),
+    /// Input exceeds maximum allowed length
+    InputTooLong,
+    /// Input contains invalid characters
+    InvalidCharacters(String),
+    /// Numeric value out of allowed range
+    NumberOutOfRange(String),
+    /// Function or operator applied outside its domain
+    DomainError(String),
+    /// An RPN operation needs more stack levels than are filled
+    TooFewArguments,
+    /// LASTx was pressed before any operation consumed an X value
+    NoLastX,
+    /// A name that can't hold a variable, e.g. a function name
+    InvalidVariableName(String),
+}
+
+/// Why an expression is malformed.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SyntaxError {
+    /// There is no expression at all
+    Empty,
+    /// A number has a second decimal point, e.g. `1.2.`
+    MultipleDecimalPoints(String),
+    /// A number has a second exponent, e.g. `1e2e`
+    MultipleExponents(String),
+    /// An operator, constant or name where it can't stand, e.g. the `+` of `(+2)`
+    Unexpected(String),
+    /// Two binary operators in a row
+    ConsecutiveOperators,
+    /// An operator or parenthesis lacks an operand
+    MissingOperand,
+    /// Operands that no operator connects
+    TooManyOperands,
+    /// A function name without its opening parenthesis
+    ExpectedParenthesis(String),
+    /// A name that is neither a function nor a constant
+    UnknownFunction(String),
+    /// A closing parenthesis without an opening one, or the other way round
+    MismatchedParentheses,
+}
+
+impl From<SyntaxError> for CalculatorError {
+    fn from(error: SyntaxError) -> Self {
+        CalculatorError::InvalidExpression(error)
+    }
+}
+
+impl CalculatorError {
+    /// A stable snake_case name of the kind of error, for machine-readable output.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::CalculatorError;
+    ///
+    /// assert_eq!(CalculatorError::DivisionByZero.name(), "division_by_zero");
+    /// ```
+    pub fn name(&self) -> &'static str {
+        match self {
+            CalculatorError::DivisionByZero => "division_by_zero",
+            CalculatorError::InvalidNumber(_) => "invalid_number",
+            CalculatorError::InvalidExpression(_) => "invalid_expression",
+            CalculatorError::InputTooLong => "input_too_long",
+            CalculatorError::InvalidCharacters(_) => "invalid_characters",
+            CalculatorError::NumberOutOfRange(_) => "number_out_of_range",
+            CalculatorError::DomainError(_) => "domain_error",
+            CalculatorError::TooFewArguments => "too_few_arguments",
+            CalculatorError::NoLastX => "no_last_x",
+            CalculatorError::InvalidVariableName(_) => "invalid_variable_name",
+        }
+    }
+}
+
+impl fmt::Display for CalculatorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&Locale::English.error_message(self))
+    }
+}
+
+impl core::error::Error for CalculatorError {}
+
+/// An evaluation error together with the part of the expression it refers to.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExpressionError {
+    /// What went wrong
+    pub kind: CalculatorError,
+    /// Character range of the offending part of the expression, e.g. the second operator
+    /// of `5+*3`, an unmatched parenthesis or the divisor of a division by zero
+    pub span: Range<usize>,
+}
+
+impl ExpressionError {
+    /// Creates an error for the given character range.
+    pub fn new(kind: impl Into<CalculatorError>, span: Range<usize>) -> Self {
+        Self {
+            kind: kind.into(),
+            span,
+        }
+    }
+}
+
+impl fmt::Display for ExpressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind)
+    }
+}
+
+impl core::error::Error for ExpressionError {}
+
+/// Tokenizes an input expression into tokens for the shunting-yard algorithm.
+///
+/// Handles numbers, constants, functions, operators, and parentheses.
+/// Detects unary minus operations. Every token records the characters it came from.
+///
+/// # Arguments
+/// * `input` - The input expression string
+///
+/// # Returns
+/// * `Ok(Vec<Spanned>)` - Successfully tokenized expression
+/// * `Err(ExpressionError)` - Tokenization error with description and location
+fn tokenize(input: &str) -> Result<Vec<Spanned>, ExpressionError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().enumerate().peekable();
+    let mut expect_operand = true; // Track if we expect an operand (number/paren) or operator
+    let mut prev_was_binary_op = false; // Track if previous token was a binary operator
+
+    while let Some(&(start, ch)) = chars.peek() {
+        // Location of a single-character token or error
+        let here = start..start + 1;
+        match ch {
+            '0'..='9' | '.' => {
+                // Parse number (including scientific notation)
+                let mut num_str = String::new();
+                let mut has_dot = false;
+                let mut has_e = false;
+
+                while let Some(&(pos, c)) = chars.peek() {
+                    match c {
+                        '0'..='9' => {
+                            num_str.push(c);
+                            chars.next();
+                        }
+                        '.' => {
+                            if has_dot {
+                                return Err(ExpressionError::new(
+                                    SyntaxError::MultipleDecimalPoints(num_str + "."),
+                                    start..pos + 1,
+                                ));
+                            }
+                            has_dot = true;
+                            num_str.push(c);
+                            chars.next();
+                        }
+                        'e' | 'E' => {
+                            if has_e {
+                                return Err(ExpressionError::new(
+                                    SyntaxError::MultipleExponents(num_str + "e"),
+                                    start..pos + 1,
+                                ));
+                            }
+                            has_e = true;
+                            num_str.push(c);
+                            chars.next();
+
+                            // Handle optional sign after 'e'
+                            if let Some(&(_, next)) = chars.peek()
+                                && (next == '+' || next == '-')
+                            {
+                                num_str.push(next);
+                                chars.next();
+                            }
+                        }
+                        _ => break,
+                    }
+                }
+
+                // Parse the number
+                let span = start..start + num_str.len();
+                match safe_parse_number(&num_str) {
+                    Ok(num) => tokens.push(Spanned::new(Token::Number(num), span)),
+                    Err(e) => return Err(ExpressionError::new(e, span)),
+                }
+                expect_operand = false;
+                prev_was_binary_op = false; // Numbers are not operators
+            }
+            '+' => {
+                if expect_operand {
+                    return Err(ExpressionError::new(
+                        SyntaxError::Unexpected(ch.to_string()),
+                        here,
+                    ));
+                }
+                // Check for consecutive operators
+                if prev_was_binary_op {
+                    return Err(ExpressionError::new(
+                        SyntaxError::ConsecutiveOperators,
+                        here,
+                    ));
+                }
+                tokens.push(Spanned::new(Token::Plus, here));
+                chars.next();
+                expect_operand = true;
+                prev_was_binary_op = true;
+            }
+            '-' => {
+                chars.next();
+                // A sign may follow x, ÷ and ^, so `2^-1` and `3x-2` work; `1+-2` stays an error
+                let signs_operand = matches!(
+                    tokens.last().map(|spanned| &spanned.token),
+                    Some(Token::Multiply | Token::Divide | Token::Power)
+                );
+                if expect_operand && (!prev_was_binary_op || signs_operand) {
+                    // Unary minus: at the start, after parentheses or as the sign of an operand
+                    tokens.push(Spanned::new(Token::UnaryMinus, here));
+                    expect_operand = true;
+                    prev_was_binary_op = false; // Unary minus doesn't count as binary operator
+                } else if expect_operand && prev_was_binary_op {
+                    // After + or -, another - is two operators in a row
+                    return Err(ExpressionError::new(
+                        SyntaxError::ConsecutiveOperators,
+                        here,
+                    ));
+                } else {
+                    // When not expecting an operand, - is a binary operator
+                    // Check for consecutive binary operators
+                    if prev_was_binary_op {
+                        return Err(ExpressionError::new(
+                            SyntaxError::ConsecutiveOperators,
+                            here,
+                        ));
+                    }
+                    tokens.push(Spanned::new(Token::Minus, here));
+                    expect_operand = true;
+                    prev_was_binary_op = true;
+                }
+            }
+            'x' | 'X' | '*' => {
+                if expect_operand {
+                    return Err(ExpressionError::new(
+                        SyntaxError::Unexpected(ch.to_string()),
+                        here,
+                    ));
+                }
+                // Check for consecutive operators
+                if prev_was_binary_op {
+                    return Err(ExpressionError::new(
+                        SyntaxError::ConsecutiveOperators,
+                        here,
+                    ));
+                }
+                tokens.push(Spanned::new(Token::Multiply, here));
+                chars.next();
+                expect_operand = true;
+                prev_was_binary_op = true;
+            }
+            '/' | '÷' => {
+                if expect_operand {
+                    return Err(ExpressionError::new(
+                        SyntaxError::Unexpected(ch.to_string()),
+                        here,
+                    ));
+                }
+                // Check for consecutive operators
+                if prev_was_binary_op {
+                    return Err(ExpressionError::new(
+                        SyntaxError::ConsecutiveOperators,
+                        here,
+                    ));
+                }
+                tokens.push(Spanned::new(Token::Divide, here));
+                chars.next();
+                expect_operand = true;
+                prev_was_binary_op = true;
+            }
+            '^' => {
+                if expect_operand {
+                    return Err(ExpressionError::new(
+                        SyntaxError::Unexpected(ch.to_string()),
+                        here,
+                    ));
+                }
+                // Check for consecutive operators
+                if prev_was_binary_op {
+                    return Err(ExpressionError::new(
+                        SyntaxError::ConsecutiveOperators,
+                        here,
+                    ));
+                }
+                tokens.push(Spanned::new(Token::Power, here));
+                chars.next();
+                expect_operand = true;
+                prev_was_binary_op = true;
+            }
+            '!' => {
+                if expect_operand {
+                    return Err(ExpressionError::new(SyntaxError::MissingOperand, here));
+                }
+                // Postfix operator: still expecting an operator afterwards
+                tokens.push(Spanned::new(Token::Factorial, here));
+                chars.next();
+            }
+            'π' => {
+                if !expect_operand {
+                    return Err(ExpressionError::new(
+                        SyntaxError::Unexpected(ch.to_string()),
+                        here,
+                    ));
+                }
+                tokens.push(Spanned::new(Token::Number(Constant::Pi.value()), here));
+                chars.next();
+                expect_operand = false;
+                prev_was_binary_op = false;
+            }
+            '√' | '∛' | 'a'..='z' => {
+                // Function names and the constant 'e' ('x' is always multiplication)
+                let mut name = String::new();
+                if ch == '√' || ch == '∛' {
+                    name.push(ch);
+                    chars.next();
+                } else {
+                    while let Some(&(_, c)) = chars.peek() {
+                        if !c.is_ascii_lowercase() || c == 'x' {
+                            break;
+                        }
+                        name.push(c);
+                        chars.next();
+                    }
+                }
+                let span = start..start + name.chars().count();
+
+                if !expect_operand {
+                    return Err(ExpressionError::new(SyntaxError::Unexpected(name), span));
+                }
+                if name == Constant::E.symbol() {
+                    tokens.push(Spanned::new(Token::Number(Constant::E.value()), span));
+                    expect_operand = false;
+                } else if let Some(function) = Function::from_name(&name) {
+                    if chars.peek().map(|&(_, c)| c) != Some('(') {
+                        return Err(ExpressionError::new(
+                            SyntaxError::ExpectedParenthesis(name),
+                            span,
+                        ));
+                    }
+                    tokens.push(Spanned::new(Token::Function(function), span));
+                } else {
+                    return Err(ExpressionError::new(
+                        SyntaxError::UnknownFunction(name),
+                        span,
+                    ));
+                }
+                prev_was_binary_op = false;
+            }
+            '(' => {
+                // Check for consecutive operators (parentheses can follow operators)
+                tokens.push(Spanned::new(Token::LeftParen, here));
+                chars.next();
+                expect_operand = true;
+                prev_was_binary_op = false; // Parentheses are not operators
+            }
+            ')' => {
+                if expect_operand {
+                    return Err(ExpressionError::new(SyntaxError::MissingOperand, here));
+                }
+                tokens.push(Spanned::new(Token::RightParen, here));
+                chars.next();
+                expect_operand = false;
+                prev_was_binary_op = false; // Parentheses are not operators
+            }
+            ' ' => {
+                // Skip whitespace
+                chars.next();
+            }
+            _ => {
+                return Err(ExpressionError::new(
+                    CalculatorError::InvalidCharacters(ch.to_string()),
+                    here,
+                ));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Converts infix tokens to postfix notation using the shunting-yard algorithm.
+///
+/// A function token's span is extended to its closing parenthesis, so it covers the
+/// whole call.
+///
+/// # Arguments
+/// * `tokens` - Vector of infix tokens
+///
+/// # Returns
+/// * `Ok(Vec<Spanned>)` - Postfix tokens ready for evaluation
+/// * `Err(ExpressionError)` - Conversion error pointing at the unmatched parenthesis
+fn shunting_yard(tokens: Vec<Spanned>) -> Result<Vec<Spanned>, ExpressionError> {
+    let mut output: Vec<Spanned> = Vec::new();
+    let mut operator_stack: Vec<Spanned> = Vec::new();
+
+    for spanned in tokens {
+        match spanned.token {
+            Token::Number(_) => {
+                output.push(spanned);
+            }
+            Token::UnaryMinus | Token::Function(_) => {
+                operator_stack.push(spanned);
+            }
+            Token::Factorial => {
+                // Postfix operators bind tightest and apply to the operand just output
+                output.push(spanned);
+            }
+            Token::Plus | Token::Minus | Token::Multiply | Token::Divide | Token::Power => {
+                while let Some(top) = operator_stack.last() {
+                    if top.token.is_left_paren() {
+                        break;
+                    }
+
+                    if let (Some(current_info), Some(top_info)) =
+                        (spanned.token.operator_info(), top.token.operator_info())
+                    {
+                        if top_info.precedence > current_info.precedence
+                            || (top_info.precedence == current_info.precedence
+                                && current_info.left_associative)
+                        {
+                            output.push(operator_stack.pop().unwrap());
+                        } else {
+                            break;
+                        }
+                    } else {
+                        break;
+                    }
+                }
+                operator_stack.push(spanned);
+            }
+            Token::LeftParen => {
+                operator_stack.push(spanned);
+            }
+            Token::RightParen => {
+                let mut found_left_paren = false;
+                while let Some(op) = operator_stack.pop() {
+                    if op.token.is_left_paren() {
+                        found_left_paren = true;
+                        break;
+                    }
+                    output.push(op);
+                }
+                if !found_left_paren {
+                    return Err(ExpressionError::new(
+                        SyntaxError::MismatchedParentheses,
+                        spanned.span,
+                    ));
+                }
+                // A function applies to the group that just closed
+                if let Some(Token::Function(_)) = operator_stack.last().map(|op| &op.token) {
+                    let mut function = operator_stack.pop().unwrap();
+                    function.span.end = spanned.span.end;
+                    output.push(function);
+                }
+            }
+        }
+    }
+
+    // Pop remaining operators
+    while let Some(op) = operator_stack.pop() {
+        if op.token.is_left_paren() {
+            return Err(ExpressionError::new(
+                SyntaxError::MismatchedParentheses,
+                op.span,
+            ));
+        }
+        output.push(op);
+    }
+
+    Ok(output)
+}
+
+/// Evaluates postfix notation tokens.
+///
+/// Every intermediate value keeps the range of the subexpression it came from, so an
+/// error points at the operands involved, e.g. the divisor of a division by zero.
+///
+/// # Arguments
+/// * `tokens` - Vector of postfix tokens
+///
+/// # Returns
+/// * `Ok(f64)` - Result of the evaluation
+/// * `Err(ExpressionError)` - Evaluation error with description and location
+fn evaluate_postfix(tokens: Vec<Spanned>, angle_mode: AngleMode) -> Result<f64, ExpressionError> {
+    let mut stack: Vec<(f64, Range<usize>)> = Vec::new();
+    let missing_operand =
+        |span: &Range<usize>| ExpressionError::new(SyntaxError::MissingOperand, span.clone());
+
+    for Spanned { token, span } in tokens {
+        let entry = match token {
+            Token::Number(num) => (num, span),
+            Token::UnaryMinus => {
+                let (a, a_span) = stack.pop().ok_or_else(|| missing_operand(&span))?;
+                (-a, span.start..a_span.end)
+            }
+            Token::Plus | Token::Minus | Token::Multiply | Token::Divide | Token::Power => {
+                let (b, b_span) = stack.pop().ok_or_else(|| missing_operand(&span))?;
+                let (a, a_span) = stack.pop().ok_or_else(|| missing_operand(&span))?;
+                let whole = a_span.start..b_span.end;
+                let value = match token {
+                    Token::Plus => a + b,
+                    Token::Minus => a - b,
+                    Token::Multiply => a * b,
+                    Token::Divide if b == 0.0 => {
+                        return Err(ExpressionError::new(
+                            CalculatorError::DivisionByZero,
+                            b_span,
+                        ));
+                    }
+                    Token::Divide => a / b,
+                    _ => power(a, b).map_err(|e| ExpressionError::new(e, whole.clone()))?,
+                };
+                (value, whole)
+            }
+            Token::Factorial => {
+                let (a, a_span) = stack.pop().ok_or_else(|| missing_operand(&span))?;
+                let whole = a_span.start..span.end;
+                let value = factorial(a).map_err(|e| ExpressionError::new(e, whole.clone()))?;
+                (value, whole)
+            }
+            Token::Function(function) => {
+                let (a, a_span) = stack.pop().ok_or_else(|| missing_operand(&span))?;
+                let whole = span.start..span.end.max(a_span.end);
+                let value = function
+                    .apply(a, angle_mode)
+                    .map_err(|e| ExpressionError::new(e, whole.clone()))?;
+                (value, whole)
+            }
+            Token::LeftParen | Token::RightParen => {
+                return Err(ExpressionError::new(
+                    SyntaxError::MismatchedParentheses,
+                    span,
+                ));
+            }
+        };
+        stack.push(entry);
+    }
+
+    if stack.len() != 1 {
+        // Point at the first operand that no operator connects to the rest
+        let span = stack.get(1).map_or(0..0, |(_, span)| span.clone());
+        return Err(ExpressionError::new(SyntaxError::TooManyOperands, span));
+    }
+
+    let (result, span) = stack.remove(0);
+    // Check final result bounds
+    if !result.is_finite() || math::abs(result) > 1e100 {
+        return Err(ExpressionError::new(
+            CalculatorError::NumberOutOfRange(result.to_string()),
+            span,
+        ));
+    }
+
+    Ok(result)
+}
+
+/// Maximum allowed input length for security (prevents resource exhaustion)
+pub const MAX_INPUT_LENGTH: usize = 1000;
+
+/// Validates input string for security constraints
+///
+/// # Arguments
+/// * `input` - The input string to validate
+///
+/// # Returns
+/// * `Ok(())` if input is valid
+/// * `Err(CalculatorError)` if input is invalid
+pub fn validate_input(input: &str) -> Result<(), CalculatorError> {
+    // Check input length
+    if input.len() > MAX_INPUT_LENGTH {
+        return Err(CalculatorError::InputTooLong);
+    }
+
+    // Function names are allowed as whole words only, so skip them before checking characters
+    let mut functions = Function::ALL;
+    functions.sort_by_key(|f| core::cmp::Reverse(f.name().len())); // "asin" before "sin"
+    let mut remaining = input.to_string();
+    for function in functions {
+        remaining = remaining.replace(&format!("{}(", function.name()), "(");
+    }
+
+    // Check for valid characters only (digits, operators, decimal point, scientific notation,
+    // constants, whitespace, parentheses)
+    let invalid_chars: Vec<char> = remaining
+        .chars()
+        .filter(|&c| {
+            !matches!(
+                c,
+                '0'..='9'
+                    | '+'
+                    | '-'
+                    | 'x'
+                    | 'X'
+                    | '*'
+                    | '/'
+                    | '÷'
+                    | '.'
+                    | 'e'
+                    | 'E'
+                    | '^'
+                    | '!'
+                    | 'π'
+                    | '('
+                    | ')'
+                    | ' '
+            )
+        })
+        .collect();
+
+    if !invalid_chars.is_empty() {
+        return Err(CalculatorError::InvalidCharacters(
+            invalid_chars.into_iter().collect(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Safely parses a number with bounds checking
+///
+/// # Arguments
+/// * `s` - String slice to parse
+///
+/// # Returns
+/// * `Ok(f64)` if parsing succeeds and number is in valid range
+/// * `Err(CalculatorError)` if parsing fails or number is out of range
+pub fn safe_parse_number(s: &str) -> Result<f64, CalculatorError> {
+    let num = s
+        .parse::<f64>()
+        .map_err(|_| CalculatorError::InvalidNumber(s.to_string()))?;
+
+    // Check for reasonable bounds to prevent extreme values
+    if !num.is_finite() || math::abs(num) > 1e100 {
+        return Err(CalculatorError::NumberOutOfRange(s.to_string()));
+    }
+
+    Ok(num)
+}
+
+/// Raises `base` to `exponent`, rejecting results that are not real numbers.
+pub fn power(base: f64, exponent: f64) -> Result<f64, CalculatorError> {
+    let result = math::powf(base, exponent);
+    if result.is_nan() {
+        return Err(CalculatorError::DomainError(format!(
+            "{}^{}",
+            base, exponent
+        )));
+    }
+    if base == 0.0 && exponent < 0.0 {
+        return Err(CalculatorError::DivisionByZero);
+    }
+    Ok(result)
+}
+
+/// Computes the factorial of a non-negative integer.
+pub fn factorial(n: f64) -> Result<f64, CalculatorError> {
+    if n < 0.0 || math::fract(n) != 0.0 {
+        return Err(CalculatorError::DomainError(format!("{}!", n)));
+    }
+    // 170! is the largest factorial that fits in an f64
+    if n > 170.0 {
+        return Err(CalculatorError::NumberOutOfRange(format!("{}!", n)));
+    }
+    Ok((1..=n as u32).fold(1.0, |acc, i| acc * i as f64))
+}
+
+/// Evaluates an expression with operator precedence, using the shunting-yard algorithm.
+///
+/// Input is validated for security constraints first. Errors tell which characters of the
+/// expression are at fault.
+///
+/// # Examples
+///
+/// ```
+/// use rust_calculator::AngleMode;
+/// use rust_calculator::expression::evaluate;
+///
+/// assert_eq!(evaluate("7+8x3", AngleMode::Degrees), Ok(31.0));
+/// assert_eq!(evaluate("sin(90)", AngleMode::Degrees), Ok(1.0));
+/// assert_eq!(evaluate("5+*3", AngleMode::Degrees).unwrap_err().span, 2..3);
+/// assert_eq!(evaluate("1+4÷(2-2)", AngleMode::Degrees).unwrap_err().span, 5..8);
+/// ```
+pub fn evaluate(expr: &str, angle_mode: AngleMode) -> Result<f64, ExpressionError> {
+    let whole = 0..expr.chars().count();
+
+    // Security: Validate input first
+    if let Err(e) = validate_input(expr) {
+        // The tokenizer knows where an invalid character is
+        let span = match e {
+            CalculatorError::InvalidCharacters(_) => {
+                tokenize(expr).err().map_or(whole, |error| error.span)
+            }
+            _ => whole,
+        };
+        return Err(ExpressionError::new(e, span));
+    }
+
+    let trimmed = expr.trim();
+    if trimmed.is_empty() || trimmed == "0" {
+        return Ok(0.0);
+    }
+
+    // For single numbers, validate the number directly
+    if trimmed.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+        && !trimmed.contains(&['+', '-', 'x', 'X', '*', '/', '÷', '(', ')', '^', '!', 'π'][..])
+    {
+        return safe_parse_number(trimmed).map_err(|e| ExpressionError::new(e, whole));
+    }
+
+    // Tokenize the input; leading spaces are skipped, so spans index into `expr`
+    let tokens = tokenize(expr)?;
+
+    // Convert to postfix notation
+    let postfix = shunting_yard(tokens)?;
+
+    // Evaluate the postfix expression
+    evaluate_postfix(postfix, angle_mode)
+}
+
+/// Checks that an expression is well formed without evaluating it, so errors that
+/// depend on operand values (division by zero, domain errors) are not reported.
+///
+/// # Examples
+///
+/// ```
+/// use rust_calculator::expression::check_syntax;
+///
+/// assert_eq!(check_syntax("1÷0"), Ok(()));
+/// assert!(check_syntax("2x(3+").is_err());
+/// ```
+pub fn check_syntax(expr: &str) -> Result<(), CalculatorError> {
+    validate_input(expr)?;
+    let postfix = tokenize(expr).and_then(shunting_yard).map_err(|e| e.kind)?;
+
+    // Dry run of evaluate_postfix that only tracks the stack depth
+    let mut depth = 0usize;
+    for Spanned { token, .. } in postfix {
+        let operands = match token {
+            Token::Number(_) => 0,
+            Token::UnaryMinus | Token::Factorial | Token::Function(_) => 1,
+            Token::Plus | Token::Minus | Token::Multiply | Token::Divide | Token::Power => 2,
+            Token::LeftParen | Token::RightParen => {
+                return Err(SyntaxError::MismatchedParentheses.into());
+            }
+        };
+        if depth < operands {
+            return Err(SyntaxError::MissingOperand.into());
+        }
+        depth = depth - operands + 1;
+    }
+
+    match depth {
+        0 => Err(SyntaxError::MissingOperand.into()),
+        1 => Ok(()),
+        _ => Err(SyntaxError::TooManyOperands.into()),
+    }
+}
+
+/// What a token of an expression is, as reported by `tokens`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TokenKind {
+    /// A number or constant, with its value
+    Number(f64),
+    /// A variable, with its value; only `Variables::tokens` reads these
+    Variable(f64),
+    /// A prefix, infix or postfix operator
+    Operator,
+    /// A function name
+    Function(Function),
+    /// Opening parenthesis
+    LeftParen,
+    /// Closing parenthesis
+    RightParen,
+}
+
+impl TokenKind {
+    /// Name of the kind of token, for machine-readable output.
+    pub fn name(self) -> &'static str {
+        match self {
+            TokenKind::Number(_) => "number",
+            TokenKind::Variable(_) => "variable",
+            TokenKind::Operator => "operator",
+            TokenKind::Function(_) => "function",
+            TokenKind::LeftParen => "left_paren",
+            TokenKind::RightParen => "right_paren",
+        }
+    }
+}
+
+/// Splits an expression into its tokens, each with the characters it was read from.
+/// Only the tokens are checked, not whether they form a valid expression.
+///
+/// # Examples
+///
+/// ```
+/// use rust_calculator::expression::{TokenKind, tokens};
+/// use rust_calculator::Function;
+///
+/// assert_eq!(
+///     tokens("2x sin(π)").unwrap(),
+///     [
+///         (TokenKind::Number(2.0), 0..1),
+///         (TokenKind::Operator, 1..2),
+///         (TokenKind::Function(Function::Sin), 3..6),
+///         (TokenKind::LeftParen, 6..7),
+///         (TokenKind::Number(core::f64::consts::PI), 7..8),
+///         (TokenKind::RightParen, 8..9),
+///     ]
+/// );
+/// assert_eq!(tokens("2+q").unwrap_err().span, 2..3);
+/// ```
+pub fn tokens(expr: &str) -> Result<Vec<(TokenKind, Range<usize>)>, ExpressionError> {
+    if expr.len() > MAX_INPUT_LENGTH {
+        let whole = 0..expr.chars().count();
+        return Err(ExpressionError::new(CalculatorError::InputTooLong, whole));
+    }
+    let tokens = tokenize(expr)?;
+    Ok(tokens
+        .into_iter()
+        .map(|Spanned { token, span }| {
+            let kind = match token {
+                Token::Number(value) => TokenKind::Number(value),
+                Token::Function(function) => TokenKind::Function(function),
+                Token::LeftParen => TokenKind::LeftParen,
+                Token::RightParen => TokenKind::RightParen,
+                Token::Plus
+                | Token::Minus
+                | Token::UnaryMinus
+                | Token::Multiply
+                | Token::Divide
+                | Token::Power
+                | Token::Factorial => TokenKind::Operator,
+            };
+            (kind, span)
+        })
+        .collect())
+}
@@ -0,0 +1,299 @@
+use crate::calculator::Operation;
+use crate::keymap::{Key, Modifiers, NamedKey};
+use crate::layout::Arrangement;
+use crate::scientific::ScientificKey;
+use crate::ui::{CalculatorUIState, InputMode, MessageResult, UIMessage};
+
+/// Direction of a focus move with the arrow keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum FocusDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// A button of the keypads that can take the keyboard focus.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeypadButton {
+    /// The message the button sends when pressed
+    pub message: UIMessage,
+    /// Whether the button sits on the scientific panel rather than the basic keypad;
+    /// tells apart the parentheses that appear on both
+    pub scientific: bool,
+}
+
+impl KeypadButton {
+    fn basic(message: UIMessage) -> Option<Self> {
+        Some(Self {
+            message,
+            scientific: false,
+        })
+    }
+
+    fn scientific(message: UIMessage) -> Option<Self> {
+        Some(Self {
+            message,
+            scientific: true,
+        })
+    }
+}
+
+impl CalculatorUIState {
+    /// Returns the keypad buttons as they are arranged on screen, row by row.
+    ///
+    /// With the scientific panel beside the keypad the rows are joined side by side and the
+    /// panel's missing rows are `None`; stacked, the panel rows come first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::{CalculatorUIState, UIMessage};
+    ///
+    /// let ui_state = CalculatorUIState::new();
+    /// let grid = ui_state.keypad_grid();
+    /// assert_eq!(grid.len(), 6);
+    /// assert_eq!(
+    ///     grid[2][0].as_ref().map(|button| &button.message),
+    ///     Some(&UIMessage::NumberPressed(7))
+    /// );
+    /// ```
+    pub fn keypad_grid(&self) -> Vec<Vec<Option<KeypadButton>>> {
+        let keypad = self.basic_keypad_rows();
+        let panel: Vec<Vec<Option<KeypadButton>>> = std::iter::once(
+            [
+                UIMessage::SecondPressed,
+                UIMessage::AngleModePressed,
+                UIMessage::OpenParenPressed,
+                UIMessage::CloseParenPressed,
+            ]
+            .into_iter()
+            .map(KeypadButton::scientific)
+            .collect(),
+        )
+        .chain(ScientificKey::LAYOUT.iter().map(|keys| {
+            keys.iter()
+                .map(|&key| KeypadButton::scientific(UIMessage::ScientificKeyPressed(key)))
+                .collect()
+        }))
+        .collect();
+
+        match self.arrangement() {
+            Arrangement::Basic => keypad,
+            Arrangement::ScientificPortrait => panel.into_iter().chain(keypad).collect(),
+            Arrangement::ScientificLandscape => keypad
+                .into_iter()
+                .enumerate()
+                .map(|(index, keypad_row)| {
+                    let panel_row = panel.get(index).cloned().unwrap_or_else(|| vec![None; 4]);
+                    panel_row.into_iter().chain(keypad_row).collect()
+                })
+                .collect(),
+        }
+    }
+
+    /// The basic keypad of the current input mode, row by row.
+    fn basic_keypad_rows(&self) -> Vec<Vec<Option<KeypadButton>>> {
+        let (top_row, second_row, last_key) = match self.mode {
+            InputMode::Algebraic => (
+                [
+                    UIMessage::OpenParenPressed,
+                    UIMessage::CloseParenPressed,
+                    UIMessage::CursorLeft,
+                    UIMessage::CursorRight,
+                ],
+                [
+                    UIMessage::BackspacePressed,
+                    UIMessage::ClearPressed,
+                    UIMessage::PercentagePressed,
+                    UIMessage::OperationPressed(Operation::Divide),
+                ],
+                UIMessage::EqualsPressed,
+            ),
+            InputMode::Rpn => (
+                [
+                    UIMessage::EnterPressed,
+                    UIMessage::SwapPressed,
+                    UIMessage::RollDownPressed,
+                    UIMessage::LastXPressed,
+                ],
+                [
+                    UIMessage::BackspacePressed,
+                    UIMessage::ClearPressed,
+                    UIMessage::DropPressed,
+                    UIMessage::OperationPressed(Operation::Divide),
+                ],
+                UIMessage::PercentagePressed,
+            ),
+        };
+        let number_rows = [
+            (7, Operation::Multiply),
+            (4, Operation::Subtract),
+            (1, Operation::Add),
+        ]
+        .map(|(first, operation)| {
+            [
+                UIMessage::NumberPressed(first),
+                UIMessage::NumberPressed(first + 1),
+                UIMessage::NumberPressed(first + 2),
+                UIMessage::OperationPressed(operation),
+            ]
+        });
+        let bottom_row = [
+            UIMessage::SignTogglePressed,
+            UIMessage::NumberPressed(0),
+            UIMessage::DecimalPressed,
+            last_key,
+        ];
+
+        [top_row, second_row]
+            .into_iter()
+            .chain(number_rows)
+            .chain([bottom_row])
+            .map(|row| row.into_iter().map(KeypadButton::basic).collect())
+            .collect()
+    }
+
+    /// How the keypads are arranged, following the scientific panel and the window shape.
+    pub fn arrangement(&self) -> Arrangement {
+        match self.scientific_panel {
+            false => Arrangement::Basic,
+            true if self.wide_window => Arrangement::ScientificLandscape,
+            true => Arrangement::ScientificPortrait,
+        }
+    }
+
+    /// Returns the focused button, if focus navigation is active and the button is shown.
+    pub fn focused_button(&self) -> Option<KeypadButton> {
+        let (row, column) = self.focus?;
+        self.keypad_grid().get(row)?.get(column)?.clone()
+    }
+
+    /// Translates a key press into a focus navigation message.
+    ///
+    /// Tab and Shift+Tab move the focus through the buttons in reading order and start
+    /// focus navigation. While a button is focused the arrow keys move the focus across the
+    /// grid, Space and Enter press the focused button and Escape ends focus navigation.
+    /// Other keys, and all keys while the graph or settings view is shown, give `None`
+    /// and keep their key bindings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::keymap::{Key, Modifiers, NamedKey};
+    /// use rust_calculator::focus::FocusDirection;
+    /// use rust_calculator::{CalculatorUIState, UIMessage};
+    ///
+    /// let mut ui_state = CalculatorUIState::new();
+    /// let left = Key::Named(NamedKey::ArrowLeft);
+    /// assert_eq!(ui_state.focus_message(&left, Modifiers::NONE), None);
+    ///
+    /// ui_state.process_message(UIMessage::FocusNext);
+    /// assert_eq!(
+    ///     ui_state.focus_message(&left, Modifiers::NONE),
+    ///     Some(UIMessage::FocusMove(FocusDirection::Left))
+    /// );
+    /// ```
+    pub fn focus_message(&self, key: &Key, modifiers: Modifiers) -> Option<UIMessage> {
+        if self.graph_view || self.settings_view {
+            return None;
+        }
+        let Key::Named(named) = key else {
+            return None;
+        };
+        if modifiers.ctrl || modifiers.alt || modifiers.logo {
+            return None;
+        }
+        match named {
+            NamedKey::Tab if modifiers.shift => Some(UIMessage::FocusPrevious),
+            NamedKey::Tab => Some(UIMessage::FocusNext),
+            _ if self.focus.is_none() || modifiers.shift => None,
+            NamedKey::ArrowUp => Some(UIMessage::FocusMove(FocusDirection::Up)),
+            NamedKey::ArrowDown => Some(UIMessage::FocusMove(FocusDirection::Down)),
+            NamedKey::ArrowLeft => Some(UIMessage::FocusMove(FocusDirection::Left)),
+            NamedKey::ArrowRight => Some(UIMessage::FocusMove(FocusDirection::Right)),
+            NamedKey::Enter | NamedKey::Space => Some(UIMessage::FocusActivate),
+            NamedKey::Escape => Some(UIMessage::FocusClear),
+            _ => None,
+        }
+    }
+
+    /// Applies a focus navigation message; the focused button's own message is processed
+    /// on `FocusActivate`.
+    pub(crate) fn apply_focus_message(&mut self, message: UIMessage) -> MessageResult {
+        let grid = self.keypad_grid();
+        let positions: Vec<(usize, usize)> = grid
+            .iter()
+            .enumerate()
+            .flat_map(|(row, buttons)| {
+                buttons
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, button)| button.is_some())
+                    .map(move |(column, _)| (row, column))
+            })
+            .collect();
+        let current = self
+            .focus
+            .and_then(|focus| positions.iter().position(|&position| position == focus));
+
+        match message {
+            UIMessage::FocusNext => {
+                let next = current.map_or(0, |index| (index + 1) % positions.len());
+                self.focus = positions.get(next).copied();
+            }
+            UIMessage::FocusPrevious => {
+                let previous = current.map_or(positions.len().saturating_sub(1), |index| {
+                    (index + positions.len() - 1) % positions.len()
+                });
+                self.focus = positions.get(previous).copied();
+            }
+            UIMessage::FocusMove(direction) => {
+                self.focus = match current {
+                    Some(index) => Some(step(&grid, positions[index], direction)),
+                    None => positions.first().copied(),
+                };
+            }
+            UIMessage::FocusActivate => {
+                if let Some(button) = self.focused_button() {
+                    return self.process_message(button.message);
+                }
+            }
+            UIMessage::FocusClear => self.focus = None,
+            _ => {}
+        }
+        MessageResult::NoScroll
+    }
+}
+
+/// Moves from `start` to the nearest button in `direction`, skipping empty cells and
+/// staying put at the edge of the grid.
+fn step(
+    grid: &[Vec<Option<KeypadButton>>],
+    start: (usize, usize),
+    direction: FocusDirection,
+) -> (usize, usize) {
+    let (mut row, mut column) = start;
+    loop {
+        let next = match direction {
+            FocusDirection::Up => row.checked_sub(1).map(|row| (row, column)),
+            FocusDirection::Down => Some((row + 1, column)),
+            FocusDirection::Left => column.checked_sub(1).map(|column| (row, column)),
+            FocusDirection::Right => Some((row, column + 1)),
+        };
+        let Some((next_row, next_column)) = next else {
+            return start;
+        };
+        match grid
+            .get(next_row)
+            .and_then(|buttons| buttons.get(next_column))
+        {
+            Some(Some(_)) => return (next_row, next_column),
+            // An empty cell: keep looking further along
+            Some(None) => (row, column) = (next_row, next_column),
+            None => return start,
+        }
+    }
+}
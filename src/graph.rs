@@ -0,0 +1,395 @@
+use crate::calculator::{AngleMode, Calculator, CalculatorError, SyntaxError};
+
+/// Region of the plane shown by the graph, in graph coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Viewport {
+    /// Left edge
+    pub x_min: f64,
+    /// Right edge
+    pub x_max: f64,
+    /// Bottom edge
+    pub y_min: f64,
+    /// Top edge
+    pub y_max: f64,
+}
+
+impl Viewport {
+    /// Smallest width or height the viewport can be zoomed in to.
+    pub const MIN_SPAN: f64 = 1e-6;
+    /// Largest width or height the viewport can be zoomed out to.
+    pub const MAX_SPAN: f64 = 1e6;
+
+    /// Width of the viewport.
+    pub fn width(&self) -> f64 {
+        self.x_max - self.x_min
+    }
+
+    /// Height of the viewport.
+    pub fn height(&self) -> f64 {
+        self.y_max - self.y_min
+    }
+
+    /// Scales the viewport by `factor` around the point (`x`, `y`), which stays at the same
+    /// place on screen. A factor below 1 zooms in.
+    ///
+    /// The factor is limited so that both spans stay between `MIN_SPAN` and `MAX_SPAN`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::graph::Viewport;
+    ///
+    /// let mut viewport = Viewport::default();
+    /// viewport.zoom(0.5, 10.0, 0.0);
+    /// assert_eq!((viewport.x_min, viewport.x_max), (0.0, 10.0));
+    /// assert_eq!((viewport.y_min, viewport.y_max), (-5.0, 5.0));
+    /// ```
+    pub fn zoom(&mut self, factor: f64, x: f64, y: f64) {
+        let (width, height) = (self.width(), self.height());
+        let min_factor = Self::MIN_SPAN / width.min(height);
+        let max_factor = Self::MAX_SPAN / width.max(height);
+        if !factor.is_finite() || factor <= 0.0 || min_factor > max_factor {
+            return;
+        }
+        let factor = factor.clamp(min_factor, max_factor);
+
+        self.x_min = x + (self.x_min - x) * factor;
+        self.x_max = x + (self.x_max - x) * factor;
+        self.y_min = y + (self.y_min - y) * factor;
+        self.y_max = y + (self.y_max - y) * factor;
+    }
+
+    /// Moves the viewport by (`dx`, `dy`) in graph coordinates.
+    pub fn pan(&mut self, dx: f64, dy: f64) {
+        if !dx.is_finite() || !dy.is_finite() {
+            return;
+        }
+        self.x_min += dx;
+        self.x_max += dx;
+        self.y_min += dy;
+        self.y_max += dy;
+    }
+
+    /// Converts a point to screen coordinates in an area of the given size, with the origin
+    /// at the top left and y pointing down.
+    pub fn to_screen(&self, x: f64, y: f64, width: f32, height: f32) -> (f32, f32) {
+        (
+            ((x - self.x_min) / self.width() * width as f64) as f32,
+            ((self.y_max - y) / self.height() * height as f64) as f32,
+        )
+    }
+
+    /// Converts a screen position in an area of the given size back to graph coordinates.
+    pub fn from_screen(&self, px: f32, py: f32, width: f32, height: f32) -> (f64, f64) {
+        (
+            self.x_min + px as f64 / width as f64 * self.width(),
+            self.y_max - py as f64 / height as f64 * self.height(),
+        )
+    }
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self {
+            x_min: -10.0,
+            x_max: 10.0,
+            y_min: -10.0,
+            y_max: 10.0,
+        }
+    }
+}
+
+/// Returns a round grid spacing (1, 2 or 5 times a power of ten) that divides `span`
+/// into at most about `max_lines` parts.
+///
+/// # Examples
+///
+/// ```
+/// use rust_calculator::graph::grid_step;
+///
+/// assert_eq!(grid_step(20.0, 10), 2.0);
+/// assert_eq!(grid_step(0.3, 10), 0.05);
+/// ```
+pub fn grid_step(span: f64, max_lines: usize) -> f64 {
+    let raw = span / max_lines.max(1) as f64;
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|multiple| multiple * magnitude)
+        .find(|&step| step >= raw * (1.0 - 1e-9))
+        .unwrap_or(10.0 * magnitude);
+    // Round away the noise of powf so labels come out clean
+    (step / magnitude).round() * magnitude
+}
+
+/// Returns the multiples of `step` between `min` and `max`, inclusive.
+///
+/// Returns nothing for an invalid step or when there would be more than 1000 lines.
+pub fn grid_lines(min: f64, max: f64, step: f64) -> Vec<f64> {
+    if step.is_nan() || step <= 0.0 || !min.is_finite() || !max.is_finite() {
+        return Vec::new();
+    }
+    let first = (min / step).ceil() as i64;
+    let last = (max / step).floor() as i64;
+    if last.saturating_sub(first) > 1000 {
+        return Vec::new();
+    }
+    (first..=last).map(|i| i as f64 * step).collect()
+}
+
+/// Formats a grid line value with as many decimals as the grid step needs.
+///
+/// # Examples
+///
+/// ```
+/// use rust_calculator::graph::format_tick;
+///
+/// assert_eq!(format_tick(2.5, 0.5), "2.5");
+/// assert_eq!(format_tick(-4.0, 2.0), "-4");
+/// assert_eq!(format_tick(0.30000000000000004, 0.1), "0.3");
+/// ```
+pub fn format_tick(value: f64, step: f64) -> String {
+    let decimals = (-step.log10().floor()).max(0.0) as usize;
+    let formatted = format!("{:.*}", decimals, value);
+    // Avoid "-0" for values that round to zero
+    match formatted
+        .trim_start_matches('-')
+        .trim_matches(|c| c == '0' || c == '.')
+    {
+        "" => "0".to_string(),
+        _ => formatted,
+    }
+}
+
+/// A function `y = f(x)` entered as a calculator expression in the variable `x`.
+///
+/// Multiplication must be written as `*` or `×`, since `x` is the variable. A number or
+/// closing parenthesis directly before `x` multiplies it, so `2x` means `2*x`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub struct PlotFunction {
+    expression: String,
+}
+
+impl PlotFunction {
+    /// Parses a function, rejecting expressions that are not well formed.
+    ///
+    /// Values where the function is undefined (such as `x = 0` for `1/x`) are not errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::AngleMode;
+    /// use rust_calculator::graph::PlotFunction;
+    ///
+    /// let f = PlotFunction::parse("2x^2 - 1").unwrap();
+    /// assert_eq!(f.evaluate(3.0, AngleMode::Radians), Some(17.0));
+    /// assert!(PlotFunction::parse("2x^").is_err());
+    /// ```
+    pub fn parse(expression: &str) -> Result<Self, CalculatorError> {
+        let function = Self {
+            expression: expression.trim().to_string(),
+        };
+        if function.expression.is_empty() {
+            return Err(SyntaxError::Empty.into());
+        }
+        Calculator::check_syntax(&function.substitute(1.0))?;
+        Ok(function)
+    }
+
+    /// The expression as entered.
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// Evaluates the function at `x`; returns `None` where it is undefined.
+    pub fn evaluate(&self, x: f64, angle_mode: AngleMode) -> Option<f64> {
+        let calculator = Calculator {
+            angle_mode,
+            ..Calculator::new()
+        };
+        self.evaluate_with(&calculator, x)
+    }
+
+    fn evaluate_with(&self, calculator: &Calculator, x: f64) -> Option<f64> {
+        calculator.evaluate(&self.substitute(x)).ok()
+    }
+
+    /// Replaces the variable with a parenthesized value, making implicit products explicit.
+    fn substitute(&self, x: f64) -> String {
+        let value = format!("({:e})", x);
+        let mut result = String::with_capacity(self.expression.len() + value.len());
+        let mut previous: Option<char> = None;
+
+        for c in self.expression.chars().filter(|c| !c.is_whitespace()) {
+            let c = match c {
+                '×' => '*',
+                '−' => '-',
+                c => c,
+            };
+            if c == 'x' || c == 'X' {
+                if previous.is_some_and(|p| p.is_ascii_digit() || ".)!πxX".contains(p)) {
+                    result.push('*');
+                }
+                result.push_str(&value);
+            } else {
+                if previous.is_some_and(|p| p == 'x' || p == 'X')
+                    && (c.is_ascii_alphanumeric() || ".(π√∛".contains(c))
+                {
+                    result.push('*');
+                }
+                result.push(c);
+            }
+            previous = Some(c);
+        }
+        result
+    }
+
+    /// Samples the function across the viewport at `samples` evenly spaced values of x.
+    ///
+    /// Returns the curve as separate polylines: a polyline ends where the function is
+    /// undefined and where it jumps, e.g. at the poles of `tan(x)` or `1/x`, so no vertical
+    /// line is drawn across a discontinuity. Values far outside the viewport are clamped to
+    /// one viewport height above or below it to keep screen coordinates manageable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::AngleMode;
+    /// use rust_calculator::graph::{PlotFunction, Viewport};
+    ///
+    /// let f = PlotFunction::parse("1/x").unwrap();
+    /// let curves = f.sample(&Viewport::default(), 100, AngleMode::Radians);
+    /// assert_eq!(curves.len(), 2);
+    /// assert!(curves[0].iter().all(|&(x, _)| x < 0.0));
+    /// assert!(curves[1].iter().all(|&(x, _)| x > 0.0));
+    /// ```
+    pub fn sample(
+        &self,
+        viewport: &Viewport,
+        samples: usize,
+        angle_mode: AngleMode,
+    ) -> Vec<Vec<(f64, f64)>> {
+        let calculator = Calculator {
+            angle_mode,
+            ..Calculator::new()
+        };
+        let samples = samples.max(2);
+        let step = viewport.width() / (samples - 1) as f64;
+        let clamp_margin = viewport.height();
+
+        let mut curves = Vec::new();
+        let mut curve: Vec<(f64, f64)> = Vec::new();
+        let mut previous: Option<(f64, f64)> = None;
+
+        for i in 0..samples {
+            let x = viewport.x_min + i as f64 * step;
+            let Some(y) = self.evaluate_with(&calculator, x) else {
+                if !curve.is_empty() {
+                    curves.push(std::mem::take(&mut curve));
+                }
+                previous = None;
+                continue;
+            };
+            if let Some(start) = previous
+                && self.jumps_between(&calculator, viewport, start, (x, y))
+                && !curve.is_empty()
+            {
+                curves.push(std::mem::take(&mut curve));
+            }
+            let clamped = y.clamp(viewport.y_min - clamp_margin, viewport.y_max + clamp_margin);
+            curve.push((x, clamped));
+            previous = Some((x, y));
+        }
+        if !curve.is_empty() {
+            curves.push(curve);
+        }
+        curves
+    }
+
+    /// Whether the function is discontinuous between two neighbouring samples.
+    ///
+    /// A change of more than a tenth of the viewport height counts as a jump unless the value
+    /// halfway between the samples lies between their values, as it would for a steep but
+    /// continuous curve.
+    fn jumps_between(
+        &self,
+        calculator: &Calculator,
+        viewport: &Viewport,
+        (x0, y0): (f64, f64),
+        (x1, y1): (f64, f64),
+    ) -> bool {
+        if (y1 - y0).abs() <= viewport.height() / 10.0 {
+            return false;
+        }
+        match self.evaluate_with(calculator, (x0 + x1) / 2.0) {
+            Some(middle) => middle < y0.min(y1) || middle > y0.max(y1),
+            None => true,
+        }
+    }
+}
+
+impl TryFrom<String> for PlotFunction {
+    type Error = CalculatorError;
+
+    fn try_from(expression: String) -> Result<Self, Self::Error> {
+        Self::parse(&expression)
+    }
+}
+
+impl From<PlotFunction> for String {
+    fn from(function: PlotFunction) -> Self {
+        function.expression
+    }
+}
+
+/// Functions and visible region of the graph view.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Graph {
+    /// Text of the function field; several functions are separated by `;`
+    pub input: String,
+    /// The well-formed functions of `input`, in order
+    pub functions: Vec<PlotFunction>,
+    /// The first function of `input` that couldn't be plotted, and why
+    pub error: Option<(String, CalculatorError)>,
+    /// The visible region
+    pub viewport: Viewport,
+}
+
+impl Graph {
+    /// Replaces the function field text and re-parses the functions.
+    ///
+    /// Malformed functions are left out and reported in `error`, so the others are still
+    /// plotted while one is being typed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::graph::Graph;
+    ///
+    /// let mut graph = Graph::default();
+    /// graph.set_input("sin(x); x^2; 3+");
+    /// assert_eq!(graph.functions.len(), 2);
+    /// assert!(graph.error.is_some());
+    /// ```
+    pub fn set_input(&mut self, input: &str) {
+        self.input = input.to_string();
+        self.functions.clear();
+        self.error = None;
+        for part in input.split(';').filter(|part| !part.trim().is_empty()) {
+            match PlotFunction::parse(part) {
+                Ok(function) => self.functions.push(function),
+                Err(error) => {
+                    self.error
+                        .get_or_insert_with(|| (part.trim().to_string(), error));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,1881 @@
+use iced::widget::{
+    button, canvas, column, container, pick_list, rich_text, row, scrollable, span, text,
+    text_input,
+};
+use iced::{Element, Task, Theme, application, event, keyboard, mouse};
+use rust_calculator::focus::KeypadButton;
+use rust_calculator::graph::{self, Graph};
+use rust_calculator::keymap::{self, Key, Keymap, NamedKey};
+use rust_calculator::layout::{self, Arrangement, Layout};
+use rust_calculator::locale::{Locale, Text};
+use rust_calculator::settings::{self, DecimalSeparator, DisplayFormat, Settings};
+use rust_calculator::theme::{self, Palette, Rgb, ThemeChoice};
+use rust_calculator::{
+    AngleMode, CalculatorUIState, InputMode, MessageResult, Operation, RpnCalculator,
+    ScientificKey, UIMessage,
+};
+use std::sync::LazyLock;
+
+// Static ID for the display scrollable widget - must be reused for scroll_to to work
+static DISPLAY_SCROLL_ID: LazyLock<scrollable::Id> =
+    LazyLock::new(|| scrollable::Id::new("display_scroll"));
+// The result line scrolls on its own, independently of the expression line
+static RESULT_SCROLL_ID: LazyLock<scrollable::Id> =
+    LazyLock::new(|| scrollable::Id::new("result_scroll"));
+
+struct Calculator {
+    ui_state: CalculatorUIState,
+    /// Keys held down, with the message each one triggered, for visual feedback
+    pressed_keys: std::collections::HashMap<iced::keyboard::Key, UIMessage>,
+    keymap: Keymap,
+    window_size: iced::Size,
+    /// Whether the operating system prefers a dark appearance, for the System theme
+    system_dark: bool,
+    /// Axes, grid and curves of the graph view, redrawn only when the state changes
+    graph_cache: canvas::Cache,
+    /// Keymap file path being typed in the settings view, applied on Enter
+    keymap_draft: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    NumberPressed(u8),
+    DecimalPressed,
+    OperationPressed(Operation),
+    EqualsPressed,
+    ClearPressed,
+    BackspacePressed,
+    PercentagePressed,
+    SignTogglePressed,
+    OpenParenPressed,
+    CloseParenPressed,
+    CursorLeft,
+    CursorRight,
+    Pasted(Option<String>),
+    ModeTogglePressed,
+    EnterPressed,
+    SwapPressed,
+    DropPressed,
+    RollDownPressed,
+    LastXPressed,
+    ScientificTogglePressed,
+    SecondPressed,
+    AngleModePressed,
+    ScientificKeyPressed(ScientificKey),
+    ThemeSelected(ThemeChoice),
+    GraphTogglePressed,
+    GraphInputChanged(String),
+    GraphZoom {
+        factor: f64,
+        x: f64,
+        y: f64,
+    },
+    GraphPan {
+        dx: f64,
+        dy: f64,
+    },
+    GraphResetPressed,
+    SettingsTogglePressed,
+    SettingsChanged(Settings),
+    KeymapPathEdited(String),
+    KeymapPathSubmitted,
+    KeyPressed {
+        key: iced::keyboard::Key,
+        modified_key: iced::keyboard::Key,
+        modifiers: keyboard::Modifiers,
+        numpad: bool,
+    },
+    KeyReleased(iced::keyboard::Key),
+    WindowResized(iced::Size),
+    CloseRequested(iced::window::Id),
+}
+
+/// Opens the calculator window and runs until it is closed.
+pub fn run() -> iced::Result {
+    // Restore the window size from the last run, falling back to the basic design size
+    let (width, height) = layout::window_size_path()
+        .and_then(|path| layout::load_window_size(&path))
+        .unwrap_or(Layout::BASE_WINDOW_SIZE);
+    let window_size = iced::Size::new(width, height);
+    let (min_width, min_height) = Layout::min_window_size();
+
+    application("Rust Calculator", Calculator::update, Calculator::view)
+        .subscription(Calculator::subscription)
+        .window(iced::window::Settings {
+            size: window_size,
+            min_size: Some(iced::Size::new(min_width, min_height)),
+            resizable: true,
+            decorations: true,
+            // Closing is handled in update so the window size can be saved first
+            exit_on_close_request: false,
+            ..Default::default()
+        })
+        .theme(Calculator::theme)
+        .run_with(move || (Calculator::new(window_size), Task::none()))
+}
+
+impl Calculator {
+    fn new(window_size: iced::Size) -> Self {
+        let mut ui_state = CalculatorUIState {
+            wide_window: window_size.width > window_size.height,
+            ..CalculatorUIState::default()
+        };
+
+        // User-defined palettes; a broken theme file is reported but doesn't stop the app
+        let mut theme_error = None;
+        if let Some(path) = theme::themes_path() {
+            match theme::load_custom_themes(&path) {
+                Ok(themes) => ui_state.custom_themes = themes,
+                Err(error) => theme_error = Some(error),
+            }
+        }
+
+        // Saved settings; invalid values fall back to their defaults with a notice.
+        // They choose the language, so other notices are worded after they are applied.
+        if let Some(path) = settings::settings_path() {
+            let (settings, warnings) = Settings::load(&path);
+            ui_state.apply_settings(settings);
+            if !warnings.is_empty() {
+                let locale = ui_state.settings.locale;
+                let warnings: Vec<String> = warnings
+                    .iter()
+                    .map(|warning| locale.settings_warning(warning))
+                    .collect();
+                ui_state.notice =
+                    Some(locale.format(Text::SettingsIgnored, &[&warnings.join("; ")]));
+            }
+        }
+        if let Some(error) = theme_error
+            && ui_state.notice.is_none()
+        {
+            ui_state.notice = Some(
+                ui_state
+                    .settings
+                    .locale
+                    .format(Text::ThemeFileIgnored, &[&error.to_string()]),
+            );
+        }
+
+        // Default key bindings with the user's overrides
+        let keymap = Self::load_keymap(&mut ui_state);
+        let keymap_draft = ui_state
+            .settings
+            .keymap
+            .as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+
+        Self {
+            ui_state,
+            pressed_keys: std::collections::HashMap::new(),
+            keymap,
+            window_size,
+            system_dark: Self::detect_system_dark(),
+            graph_cache: canvas::Cache::new(),
+            keymap_draft,
+        }
+    }
+
+    /// Loads the keymap file named in the settings, or the default one; a broken file is
+    /// reported and the default bindings are used
+    fn load_keymap(ui_state: &mut CalculatorUIState) -> Keymap {
+        let path = ui_state
+            .settings
+            .keymap
+            .clone()
+            .or_else(keymap::keymap_path);
+        match path {
+            Some(path) => Keymap::load(&path).unwrap_or_else(|error| {
+                ui_state.notice = Some(
+                    ui_state
+                        .settings
+                        .locale
+                        .format(Text::KeymapFileIgnored, &[&error.to_string()]),
+                );
+                Keymap::default()
+            }),
+            None => Keymap::default(),
+        }
+    }
+
+    /// Reloads the keymap if its file changed and saves the settings
+    fn settings_changed(&mut self, previous: &Settings) {
+        if self.ui_state.settings.keymap != previous.keymap {
+            self.keymap = Self::load_keymap(&mut self.ui_state);
+        }
+        if let Some(path) = settings::settings_path()
+            && let Err(error) = self.ui_state.settings.save(&path)
+        {
+            self.ui_state.notice = Some(
+                self.locale()
+                    .format(Text::SettingsNotSaved, &[&error.to_string()]),
+            );
+        }
+    }
+
+    /// Language of labels and messages
+    fn locale(&self) -> Locale {
+        self.ui_state.settings.locale
+    }
+
+    /// Asks the operating system for its light/dark preference, assuming dark if unknown
+    fn detect_system_dark() -> bool {
+        dark_light::detect() != dark_light::Mode::Light
+    }
+
+    fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::KeyPressed {
+                key,
+                modified_key,
+                modifiers,
+                numpad,
+            } => {
+                let (Some(chord_key), Some(chord_modified_key)) =
+                    (Self::keymap_key(&key), Self::keymap_key(&modified_key))
+                else {
+                    return Task::none();
+                };
+                let modifiers = keymap::Modifiers {
+                    shift: modifiers.shift(),
+                    ctrl: modifiers.control(),
+                    alt: modifiers.alt(),
+                    logo: modifiers.logo(),
+                };
+                // Focus navigation keys take precedence over the key bindings
+                if let Some(ui_message) = self.ui_state.focus_message(&chord_key, modifiers) {
+                    if ui_message == UIMessage::FocusActivate
+                        && let Some(button) = self.ui_state.focused_button()
+                    {
+                        // Highlight the pressed button until the key is released
+                        self.pressed_keys.insert(key, button.message);
+                    }
+                    return self.dispatch(ui_message);
+                }
+
+                // With a decimal comma the comma key types the decimal separator
+                let comma = self.ui_state.settings.decimal_separator == DecimalSeparator::Comma
+                    && chord_modified_key == Key::character(',');
+                match self
+                    .keymap
+                    .resolve(&chord_key, &chord_modified_key, modifiers, numpad)
+                    .or(comma.then_some(UIMessage::DecimalPressed))
+                {
+                    Some(ui_message) => {
+                        // Remember the triggered message so its button stays highlighted until release
+                        self.pressed_keys.insert(key, ui_message.clone());
+                        self.dispatch(ui_message)
+                    }
+                    None => Task::none(),
+                }
+            }
+            Message::KeyReleased(key) => {
+                self.pressed_keys.remove(&key);
+                Task::none()
+            }
+            Message::KeymapPathEdited(path) => {
+                self.keymap_draft = path;
+                Task::none()
+            }
+            Message::KeymapPathSubmitted => {
+                let path = self.keymap_draft.trim();
+                let settings = Settings {
+                    keymap: (!path.is_empty()).then(|| path.into()),
+                    ..self.ui_state.settings.clone()
+                };
+                self.dispatch(UIMessage::SettingsChanged(settings))
+            }
+            Message::WindowResized(size) => {
+                self.window_size = size;
+                self.ui_state.wide_window = size.width > size.height;
+                Task::none()
+            }
+            Message::CloseRequested(id) => {
+                // Remember the window size for the next run; failing to save isn't fatal
+                if let Some(path) = layout::window_size_path() {
+                    let size = (self.window_size.width, self.window_size.height);
+                    if let Err(error) = layout::save_window_size(&path, size) {
+                        eprintln!(
+                            "Could not save window size to {}: {}",
+                            path.display(),
+                            error
+                        );
+                    }
+                }
+                iced::window::close(id)
+            }
+            // Handle all other messages normally
+            _ => {
+                // Convert GUI message to UI state message
+                let ui_message = match message {
+                    Message::NumberPressed(digit) => UIMessage::NumberPressed(digit),
+                    Message::DecimalPressed => UIMessage::DecimalPressed,
+                    Message::OperationPressed(operation) => UIMessage::OperationPressed(operation),
+                    Message::EqualsPressed => UIMessage::EqualsPressed,
+                    Message::ClearPressed => UIMessage::ClearPressed,
+                    Message::BackspacePressed => UIMessage::BackspacePressed,
+                    Message::PercentagePressed => UIMessage::PercentagePressed,
+                    Message::SignTogglePressed => UIMessage::SignTogglePressed,
+                    Message::OpenParenPressed => UIMessage::OpenParenPressed,
+                    Message::CloseParenPressed => UIMessage::CloseParenPressed,
+                    Message::CursorLeft => UIMessage::CursorLeft,
+                    Message::CursorRight => UIMessage::CursorRight,
+                    Message::Pasted(text) => UIMessage::LoadExpression(text.unwrap_or_default()),
+                    Message::ModeTogglePressed => UIMessage::ModeTogglePressed,
+                    Message::EnterPressed => UIMessage::EnterPressed,
+                    Message::SwapPressed => UIMessage::SwapPressed,
+                    Message::DropPressed => UIMessage::DropPressed,
+                    Message::RollDownPressed => UIMessage::RollDownPressed,
+                    Message::LastXPressed => UIMessage::LastXPressed,
+                    Message::ScientificTogglePressed => UIMessage::ScientificTogglePressed,
+                    Message::SecondPressed => UIMessage::SecondPressed,
+                    Message::AngleModePressed => UIMessage::AngleModePressed,
+                    Message::ScientificKeyPressed(key) => UIMessage::ScientificKeyPressed(key),
+                    Message::ThemeSelected(theme) => UIMessage::ThemeSelected(theme),
+                    Message::GraphTogglePressed => UIMessage::GraphTogglePressed,
+                    Message::GraphInputChanged(input) => UIMessage::GraphInputChanged(input),
+                    Message::GraphZoom { factor, x, y } => UIMessage::GraphZoom { factor, x, y },
+                    Message::GraphPan { dx, dy } => UIMessage::GraphPan { dx, dy },
+                    Message::GraphResetPressed => UIMessage::GraphResetPressed,
+                    Message::SettingsTogglePressed => UIMessage::SettingsTogglePressed,
+                    Message::SettingsChanged(settings) => UIMessage::SettingsChanged(settings),
+                    Message::KeyPressed { .. }
+                    | Message::KeyReleased(_)
+                    | Message::KeymapPathEdited(_)
+                    | Message::KeymapPathSubmitted
+                    | Message::WindowResized(_)
+                    | Message::CloseRequested(_) => {
+                        unreachable!("Keyboard, window and keymap path events handled above")
+                    }
+                };
+                self.dispatch(ui_message)
+            }
+        }
+    }
+
+    /// Processes a UI message from a button or the keymap and turns the result into a task
+    fn dispatch(&mut self, ui_message: UIMessage) -> Task<Message> {
+        // Layout before the message, so toggling the panel keeps the current scale
+        let layout = self.layout();
+        let toggles_panel = ui_message == UIMessage::ScientificTogglePressed;
+        if matches!(
+            ui_message,
+            UIMessage::ThemeSelected(_) | UIMessage::NextTheme
+        ) {
+            // Pick up changes to the system appearance made while running
+            self.system_dark = Self::detect_system_dark();
+        }
+
+        // Process the message using the extracted UI state logic
+        let previous_settings = self.ui_state.settings.clone();
+        let result = self.ui_state.process_message(ui_message);
+        if self.ui_state.settings != previous_settings {
+            self.settings_changed(&previous_settings);
+        }
+        // Functions, viewport, angle mode and theme all show in the graph
+        self.graph_cache.clear();
+
+        if toggles_panel {
+            // Widen the window to make room for the panel, or shrink it back
+            let arrangement = if self.ui_state.scientific_panel {
+                Arrangement::ScientificLandscape
+            } else {
+                Arrangement::Basic
+            };
+            let (width, height) = layout.window_size_for(arrangement);
+            let size = iced::Size::new(width, height);
+            return iced::window::get_latest().and_then(move |id| iced::window::resize(id, size));
+        }
+
+        match result {
+            MessageResult::ScrollToEnd => scroll_display_to_end(),
+            // After `=` the evaluated expression line is read from its end
+            MessageResult::NoScroll if self.ui_state.last_evaluation.is_some() => {
+                scroll_display_to_end()
+            }
+            MessageResult::ScrollToCursor(fraction) => scrollable::snap_to(
+                DISPLAY_SCROLL_ID.clone(),
+                scrollable::RelativeOffset {
+                    x: fraction,
+                    y: 0.0,
+                },
+            ),
+            MessageResult::CopyToClipboard(contents) => iced::clipboard::write(contents),
+            MessageResult::ReadClipboard => iced::clipboard::read().map(Message::Pasted),
+            MessageResult::NoScroll => Task::none(),
+        }
+    }
+
+    /// Converts an iced key to a keymap key; keys the keymap can't bind give `None`
+    fn keymap_key(key: &keyboard::Key) -> Option<Key> {
+        use keyboard::key::Named;
+
+        let named = match key {
+            keyboard::Key::Character(ch) => return Some(Key::Character(ch.to_string())),
+            keyboard::Key::Named(named) => named,
+            keyboard::Key::Unidentified => return None,
+        };
+        let named = match named {
+            Named::Enter => NamedKey::Enter,
+            Named::Backspace => NamedKey::Backspace,
+            Named::Delete => NamedKey::Delete,
+            Named::Escape => NamedKey::Escape,
+            Named::Tab => NamedKey::Tab,
+            Named::Space => NamedKey::Space,
+            Named::ArrowLeft => NamedKey::ArrowLeft,
+            Named::ArrowRight => NamedKey::ArrowRight,
+            Named::ArrowUp => NamedKey::ArrowUp,
+            Named::ArrowDown => NamedKey::ArrowDown,
+            Named::Home => NamedKey::Home,
+            Named::End => NamedKey::End,
+            _ => return None,
+        };
+        Some(Key::Named(named))
+    }
+
+    fn subscription(&self) -> iced::Subscription<Message> {
+        iced::Subscription::batch([
+            Self::keyboard_subscription(),
+            iced::window::resize_events().map(|(_id, size)| Message::WindowResized(size)),
+            iced::window::close_requests().map(Message::CloseRequested),
+        ])
+    }
+
+    fn keyboard_subscription() -> iced::Subscription<Message> {
+        event::listen_with(|event, status, _window| match event {
+            // Keys typed into a text field belong to the field
+            iced::Event::Keyboard(keyboard::Event::KeyPressed { .. })
+                if status == event::Status::Captured =>
+            {
+                None
+            }
+            iced::Event::Keyboard(keyboard::Event::KeyPressed {
+                key,
+                modified_key,
+                modifiers,
+                location,
+                ..
+            }) => Some(Message::KeyPressed {
+                key,
+                modified_key,
+                modifiers,
+                numpad: location == keyboard::Location::Numpad,
+            }),
+            iced::Event::Keyboard(keyboard::Event::KeyReleased { key, .. }) => {
+                // Send KeyReleased to reset visual feedback
+                Some(Message::KeyReleased(key))
+            }
+            _ => None,
+        })
+    }
+
+    /// Check if a key triggering the given message is currently held down
+    fn is_pressed(&self, message: &UIMessage) -> bool {
+        self.pressed_keys.values().any(|pressed| pressed == message)
+    }
+
+    /// Highlighting of a basic keypad button
+    fn key_state(&self, message: &UIMessage) -> KeyState {
+        self.button_state(message, false)
+    }
+
+    /// Highlighting of a scientific panel button
+    fn panel_key_state(&self, message: &UIMessage) -> KeyState {
+        self.button_state(message, true)
+    }
+
+    fn button_state(&self, message: &UIMessage, scientific: bool) -> KeyState {
+        KeyState {
+            pressed: self.is_pressed(message),
+            focused: self.ui_state.focused_button()
+                == Some(KeypadButton {
+                    message: message.clone(),
+                    scientific,
+                }),
+        }
+    }
+
+    /// Palette of the selected theme
+    fn palette(&self) -> Palette {
+        self.ui_state.palette(self.system_dark)
+    }
+
+    /// iced theme built from the palette, so stock widgets match the keypad
+    fn theme(&self) -> Theme {
+        let palette = self.palette();
+        let base = if palette.dark {
+            iced::theme::Palette::DARK
+        } else {
+            iced::theme::Palette::LIGHT
+        };
+        Theme::custom(
+            self.ui_state.theme.to_string(),
+            iced::theme::Palette {
+                background: color(palette.background),
+                text: color(palette.text),
+                primary: color(palette.operator_key),
+                danger: color(palette.error),
+                ..base
+            },
+        )
+    }
+
+    /// Current layout for the window size and scientific panel visibility
+    fn layout(&self) -> Layout {
+        Layout::for_window(
+            self.window_size.width,
+            self.window_size.height,
+            self.ui_state.scientific_panel,
+        )
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        let layout = self.layout();
+        let palette = self.palette();
+
+        // Switch between algebraic and RPN entry
+        let mode_label = match self.ui_state.mode {
+            InputMode::Algebraic => "ALG",
+            InputMode::Rpn => "RPN",
+        };
+        let locale = self.locale();
+        let panel_label = locale.text(if self.ui_state.scientific_panel {
+            Text::Basic
+        } else {
+            Text::Scientific
+        });
+        let graph_label = locale.text(if self.ui_state.graph_view {
+            Text::Calculator
+        } else {
+            Text::Graph
+        });
+        let settings_label = locale.text(if self.ui_state.settings_view {
+            Text::Done
+        } else {
+            Text::SettingsButton
+        });
+        let mode_bar = row![
+            button(text(mode_label).size(layout.font(14.0)))
+                .on_press(Message::ModeTogglePressed)
+                .padding([4.0 * layout.scale, 12.0 * layout.scale])
+                .style(button::secondary),
+            button(text(panel_label).size(layout.font(14.0)))
+                .on_press(Message::ScientificTogglePressed)
+                .padding([4.0 * layout.scale, 12.0 * layout.scale])
+                .style(button::secondary),
+            button(text(graph_label).size(layout.font(14.0)))
+                .on_press(Message::GraphTogglePressed)
+                .padding([4.0 * layout.scale, 12.0 * layout.scale])
+                .style(button::secondary),
+            button(text(settings_label).size(layout.font(14.0)))
+                .on_press(Message::SettingsTogglePressed)
+                .padding([4.0 * layout.scale, 12.0 * layout.scale])
+                .style(button::secondary),
+            pick_list(
+                self.ui_state.available_themes(),
+                Some(self.ui_state.theme.clone()),
+                Message::ThemeSelected
+            )
+            .text_size(layout.font(14.0))
+            .padding([4.0 * layout.scale, 8.0 * layout.scale]),
+            // Angle mode indicator, always visible since typed functions use it too
+            text(self.ui_state.calculator.angle_mode.label())
+                .size(layout.font(14.0))
+                .color(dimmed(palette.text, 0.5)),
+        ]
+        .spacing(8.0 * layout.scale)
+        .align_y(iced::Alignment::Center)
+        .width(layout.content_width());
+
+        let (display, keypad) = match self.ui_state.mode {
+            InputMode::Algebraic => (
+                self.algebraic_display(&layout),
+                self.algebraic_keypad(&layout),
+            ),
+            InputMode::Rpn => (self.rpn_display(&layout), self.rpn_keypad(&layout)),
+        };
+
+        // The scientific panel sits beside the keypad in landscape and above it in portrait
+        let keypads: Element<'_, Message> = match layout.arrangement {
+            Arrangement::Basic => keypad,
+            Arrangement::ScientificLandscape => row![self.scientific_panel(&layout), keypad]
+                .spacing(layout.section_spacing())
+                .into(),
+            Arrangement::ScientificPortrait => column![self.scientific_panel(&layout), keypad]
+                .spacing(layout.spacing())
+                .into(),
+        };
+
+        // The settings and graph views take the place of the display and keypads
+        let body: Element<'_, Message> = if self.ui_state.settings_view {
+            self.settings_panel(&layout)
+        } else if self.ui_state.graph_view {
+            self.graph_panel(&layout)
+        } else {
+            column![display, keypads]
+                .spacing(layout.section_spacing())
+                .align_x(iced::Alignment::Center)
+                .into()
+        };
+
+        container(
+            column![mode_bar, body]
+                .spacing(8.0 * layout.scale)
+                .align_x(iced::Alignment::Center),
+        )
+        .center(iced::Length::Fill)
+        .padding(layout.padding())
+        .into()
+    }
+
+    /// Scientific keypad: 2nd, angle mode and parentheses above the function keys
+    fn scientific_panel(&self, layout: &Layout) -> Element<'_, Message> {
+        let palette = &self.palette();
+        let second = self.ui_state.second_function;
+
+        let top_row = row![
+            scientific_button(
+                layout,
+                palette,
+                "2nd",
+                Message::SecondPressed,
+                KeyState {
+                    pressed: second,
+                    ..self.panel_key_state(&UIMessage::SecondPressed)
+                }
+            ),
+            scientific_button(
+                layout,
+                palette,
+                self.ui_state.calculator.angle_mode.label(),
+                Message::AngleModePressed,
+                self.panel_key_state(&UIMessage::AngleModePressed)
+            ),
+            function_button(
+                layout,
+                palette,
+                "(",
+                Message::OpenParenPressed,
+                self.panel_key_state(&UIMessage::OpenParenPressed)
+            ),
+            function_button(
+                layout,
+                palette,
+                ")",
+                Message::CloseParenPressed,
+                self.panel_key_state(&UIMessage::CloseParenPressed)
+            ),
+        ]
+        .spacing(layout.spacing());
+
+        let key_rows = ScientificKey::LAYOUT.iter().map(|keys| {
+            iced::widget::Row::with_children(keys.iter().map(|&key| {
+                scientific_button(
+                    layout,
+                    palette,
+                    key.label(second),
+                    Message::ScientificKeyPressed(key),
+                    self.panel_key_state(&UIMessage::ScientificKeyPressed(key)),
+                )
+            }))
+            .spacing(layout.spacing())
+            .into()
+        });
+
+        column![
+            top_row,
+            iced::widget::Column::with_children(key_rows).spacing(layout.spacing())
+        ]
+        .spacing(layout.spacing())
+        .width(layout.keypad_width())
+        .into()
+    }
+
+    /// Graph view: function field, plot and view controls
+    fn graph_panel(&self, layout: &Layout) -> Element<'_, Message> {
+        let palette = self.palette();
+        let graph = &self.ui_state.graph;
+
+        let input = text_input(self.locale().text(Text::GraphFunctions), &graph.input)
+            .on_input(Message::GraphInputChanged)
+            .size(layout.font(20.0))
+            .padding(8.0 * layout.scale);
+        let error = text(
+            graph
+                .error
+                .as_ref()
+                .map(|(function, error)| {
+                    format!("{}: {}", function, self.locale().error_message(error))
+                })
+                .unwrap_or_default(),
+        )
+        .size(layout.font(14.0))
+        .color(color(palette.error));
+        let reset = button(text(self.locale().text(Text::ResetView)).size(layout.font(14.0)))
+            .on_press(Message::GraphResetPressed)
+            .padding([4.0 * layout.scale, 12.0 * layout.scale])
+            .style(button::secondary);
+
+        let plot = canvas(GraphPlot {
+            graph,
+            angle_mode: self.ui_state.calculator.angle_mode,
+            palette,
+            cache: &self.graph_cache,
+            font_size: layout.font(12.0),
+        })
+        .width(iced::Length::Fill)
+        .height(iced::Length::Fill);
+
+        column![input, error, plot, reset]
+            .spacing(layout.spacing())
+            .width(layout.content_width())
+            .into()
+    }
+
+    /// Settings view: one row per setting, applied and saved as soon as it changes
+    fn settings_panel(&self, layout: &Layout) -> Element<'_, Message> {
+        let palette = self.palette();
+        let settings = &self.ui_state.settings;
+        let locale = settings.locale;
+        let setting_row = |name: Text, control: Element<'static, Message>| {
+            row![
+                text(locale.text(name))
+                    .size(layout.font(16.0))
+                    .width(iced::Length::Fill),
+                control
+            ]
+            .spacing(layout.spacing())
+            .align_y(iced::Alignment::Center)
+        };
+        let control_width = 180.0 * layout.scale;
+
+        let precision = pick_list(
+            (0..=Settings::MAX_PRECISION).collect::<Vec<_>>(),
+            Some(settings.precision),
+            setting_changed(settings, |settings, precision| {
+                settings.precision = precision
+            }),
+        )
+        .text_size(layout.font(14.0))
+        .width(control_width);
+        let display_format_name = |format| {
+            locale.text(match format {
+                DisplayFormat::Auto => Text::FormatAuto,
+                DisplayFormat::Fixed => Text::FormatFixed,
+                DisplayFormat::Scientific => Text::FormatScientific,
+            })
+        };
+        let display_format = pick_list(
+            Choice::all(DisplayFormat::ALL, display_format_name),
+            Some(Choice::new(settings.display_format, display_format_name)),
+            setting_changed(settings, |settings, format: Choice<DisplayFormat>| {
+                settings.display_format = format.value
+            }),
+        )
+        .text_size(layout.font(14.0))
+        .width(control_width);
+        let angle_mode_name = |mode| {
+            locale.text(match mode {
+                AngleMode::Degrees => Text::Degrees,
+                AngleMode::Radians => Text::Radians,
+            })
+        };
+        let angle_mode = pick_list(
+            Choice::all([AngleMode::Degrees, AngleMode::Radians], angle_mode_name),
+            Some(Choice::new(settings.angle_mode, angle_mode_name)),
+            setting_changed(settings, |settings, mode: Choice<AngleMode>| {
+                settings.angle_mode = mode.value
+            }),
+        )
+        .text_size(layout.font(14.0))
+        .width(control_width);
+        let theme = pick_list(
+            self.ui_state.available_themes(),
+            Some(settings.theme.clone()),
+            setting_changed(settings, |settings, theme| settings.theme = theme),
+        )
+        .text_size(layout.font(14.0))
+        .width(control_width);
+        let separator_name = |separator| {
+            locale.text(match separator {
+                DecimalSeparator::Point => Text::SeparatorPoint,
+                DecimalSeparator::Comma => Text::SeparatorComma,
+            })
+        };
+        let decimal_separator = pick_list(
+            Choice::all(DecimalSeparator::ALL, separator_name),
+            Some(Choice::new(settings.decimal_separator, separator_name)),
+            setting_changed(settings, |settings, separator: Choice<DecimalSeparator>| {
+                settings.decimal_separator = separator.value
+            }),
+        )
+        .text_size(layout.font(14.0))
+        .width(control_width);
+        // Each language brings its usual decimal separator, which can then be changed
+        let language = pick_list(
+            Locale::ALL,
+            Some(locale),
+            setting_changed(settings, |settings, locale| {
+                settings.locale = locale;
+                settings.decimal_separator = locale.decimal_separator();
+            }),
+        )
+        .text_size(layout.font(14.0))
+        .width(control_width);
+
+        // The keymap path applies on Enter, so a half-typed path isn't loaded
+        let default_keymap = keymap::keymap_path()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        let keymap = text_input(&default_keymap, &self.keymap_draft)
+            .on_input(Message::KeymapPathEdited)
+            .on_submit(Message::KeymapPathSubmitted)
+            .size(layout.font(14.0))
+            .padding(6.0 * layout.scale);
+
+        let location = settings::settings_path()
+            .map(|path| locale.format(Text::SavedTo, &[&path.display().to_string()]))
+            .unwrap_or_else(|| locale.text(Text::NoConfigDirectory).to_string());
+        let notice = text(self.ui_state.notice.clone().unwrap_or_default())
+            .size(layout.font(14.0))
+            .color(color(palette.error));
+
+        column![
+            setting_row(Text::Language, language.into()),
+            setting_row(Text::Precision, precision.into()),
+            setting_row(Text::DisplayFormat, display_format.into()),
+            setting_row(Text::AngleMode, angle_mode.into()),
+            setting_row(Text::Theme, theme.into()),
+            setting_row(Text::DecimalSeparator, decimal_separator.into()),
+            text(locale.text(Text::KeymapFile)).size(layout.font(16.0)),
+            keymap,
+            notice,
+            text(location)
+                .size(layout.font(12.0))
+                .color(dimmed(palette.text, 0.5)),
+        ]
+        .spacing(layout.spacing())
+        .width(layout.content_width())
+        .into()
+    }
+
+    /// Algebraic display: scrollable expression with the live preview line below it
+    fn algebraic_display(&self, layout: &Layout) -> Element<'_, Message> {
+        let palette = &self.palette();
+        let content_width = layout.content_width();
+        let calculator = &self.ui_state.calculator;
+        let lines = self.ui_state.display_lines();
+
+        // The line being worked on is large; after `=` the expression shrinks and the result grows
+        let (expression_size, result_size) = if lines.evaluated {
+            (layout.font(28.0), layout.font(52.0))
+        } else {
+            (layout.font(44.0), layout.font(24.0))
+        };
+        let expression_color = if lines.evaluated {
+            dimmed(palette.text, 0.5)
+        } else {
+            color(palette.text)
+        };
+
+        // After a failed evaluation the offending characters are underlined in the error color;
+        // the raw expression is shown then, so the caret goes at the cursor position in it
+        let separator = self.ui_state.settings.decimal_separator;
+        let (segments, caret) = match calculator.error_display_parts() {
+            Some((before, offending, after)) => {
+                let position = calculator.cursor_position();
+                let inside = position < calculator.expression.chars().count();
+                (
+                    vec![
+                        (separator.localize(&before), false),
+                        (separator.localize(&offending), true),
+                        (separator.localize(&after), false),
+                    ],
+                    inside.then_some(position),
+                )
+            }
+            None => (vec![(lines.expression, false)], lines.caret),
+        };
+        let expression: Element<'_, Message> =
+            rich_text(expression_spans(segments, caret, palette))
+                .size(expression_size)
+                .color(expression_color)
+                .into();
+
+        let ghost_text = if lines.evaluated {
+            String::new()
+        } else {
+            calculator.ghost_parens()
+        };
+
+        // Expression line – horizontally scrollable with invisible scrollbar.
+        // Unclosed parentheses are rendered as dimmed ghost characters after the expression.
+        let expression_line = scrollable(row![
+            expression,
+            text(ghost_text)
+                .size(expression_size)
+                .color(dimmed(palette.text, 0.35)),
+        ])
+        .id(DISPLAY_SCROLL_ID.clone())
+        .direction(scrollable::Direction::Horizontal(
+            scrollable::Scrollbar::new().width(0).scroller_width(0),
+        ))
+        .width(content_width);
+
+        let expression_display = container(expression_line)
+            .width(content_width)
+            .height(56.0 * layout.scale)
+            .align_y(iced::alignment::Vertical::Bottom)
+            .align_x(iced::alignment::Horizontal::Right);
+
+        // Result line: the live preview while typing, the result after `=`.
+        // Notices (e.g. a rejected paste) and evaluation errors take its place until the
+        // next input. It scrolls on its own and stays anchored to its right end.
+        let message = self.ui_state.notice.clone().or_else(|| {
+            calculator
+                .error
+                .as_ref()
+                .map(|error| self.locale().error_message(&error.kind))
+        });
+        let result_text = match message {
+            Some(message) => text(message)
+                .size(layout.font(16.0))
+                .color(color(palette.error)),
+            None if lines.evaluated => text(lines.result).size(result_size),
+            None => text(lines.result)
+                .size(result_size)
+                .color(dimmed(palette.text, 0.5)),
+        };
+        let result_line = scrollable(result_text)
+            .id(RESULT_SCROLL_ID.clone())
+            .direction(scrollable::Direction::Horizontal(
+                scrollable::Scrollbar::new().width(0).scroller_width(0),
+            ))
+            .anchor_right();
+
+        let result_display = container(result_line)
+            .width(content_width)
+            .height(54.0 * layout.scale)
+            .align_y(iced::alignment::Vertical::Center)
+            .align_x(iced::alignment::Horizontal::Right);
+
+        column![expression_display, result_display].into()
+    }
+
+    /// Algebraic keypad: parentheses and cursor keys above the basic grid
+    fn algebraic_keypad(&self, layout: &Layout) -> Element<'_, Message> {
+        let palette = &self.palette();
+        column![
+            // Row 1: ( ) ◀ ▶
+            row![
+                function_button(
+                    layout,
+                    palette,
+                    "(",
+                    Message::OpenParenPressed,
+                    self.key_state(&UIMessage::OpenParenPressed)
+                ),
+                function_button(
+                    layout,
+                    palette,
+                    ")",
+                    Message::CloseParenPressed,
+                    self.key_state(&UIMessage::CloseParenPressed)
+                ),
+                function_button(
+                    layout,
+                    palette,
+                    "◀",
+                    Message::CursorLeft,
+                    self.key_state(&UIMessage::CursorLeft)
+                ),
+                function_button(
+                    layout,
+                    palette,
+                    "▶",
+                    Message::CursorRight,
+                    self.key_state(&UIMessage::CursorRight)
+                ),
+            ]
+            .spacing(layout.spacing()),
+            // Row 2: ⌫ AC % ÷
+            row![
+                function_button(
+                    layout,
+                    palette,
+                    "⌫",
+                    Message::BackspacePressed,
+                    self.key_state(&UIMessage::BackspacePressed)
+                ),
+                function_button(
+                    layout,
+                    palette,
+                    self.locale().text(Text::ClearAll),
+                    Message::ClearPressed,
+                    self.key_state(&UIMessage::ClearPressed)
+                ),
+                function_button(
+                    layout,
+                    palette,
+                    "%",
+                    Message::PercentagePressed,
+                    self.key_state(&UIMessage::PercentagePressed)
+                ),
+                operator_button(
+                    layout,
+                    palette,
+                    "÷",
+                    Message::OperationPressed(Operation::Divide),
+                    self.key_state(&UIMessage::OperationPressed(Operation::Divide))
+                ),
+            ]
+            .spacing(layout.spacing()),
+            // Rows 3-5: 7 8 9 x / 4 5 6 − / 1 2 3 +
+            self.number_rows(layout),
+            // Row 6: +/- 0 . =
+            row![
+                function_button(
+                    layout,
+                    palette,
+                    "+/-",
+                    Message::SignTogglePressed,
+                    self.key_state(&UIMessage::SignTogglePressed)
+                ),
+                number_button(
+                    layout,
+                    palette,
+                    "0",
+                    Message::NumberPressed(0),
+                    self.key_state(&UIMessage::NumberPressed(0))
+                ),
+                number_button(
+                    layout,
+                    palette,
+                    self.ui_state.settings.decimal_separator.label(),
+                    Message::DecimalPressed,
+                    self.key_state(&UIMessage::DecimalPressed)
+                ),
+                operator_button(
+                    layout,
+                    palette,
+                    "=",
+                    Message::EqualsPressed,
+                    self.key_state(&UIMessage::EqualsPressed)
+                ),
+            ]
+            .spacing(layout.spacing()),
+        ]
+        .spacing(layout.spacing())
+        .align_x(iced::Alignment::Center)
+        .width(layout.keypad_width())
+        .into()
+    }
+
+    /// RPN display: the X/Y/Z/T stack levels, X shown largest at the bottom
+    fn rpn_display(&self, layout: &Layout) -> Element<'_, Message> {
+        let palette = &self.palette();
+        let rpn = &self.ui_state.rpn;
+
+        let levels = (0..RpnCalculator::LEVEL_NAMES.len()).rev().map(|level| {
+            let size = if level == 0 { 28.0 } else { 20.0 };
+            row![
+                text(RpnCalculator::LEVEL_NAMES[level])
+                    .size(layout.font(14.0))
+                    .color(dimmed(palette.text, 0.5))
+                    .width(24.0 * layout.scale),
+                text(match &rpn.error {
+                    Some(error) if level == 0 => self.locale().error_message(error),
+                    _ => self
+                        .ui_state
+                        .settings
+                        .decimal_separator
+                        .localize(&rpn.level_display(level)),
+                })
+                .size(layout.font(size))
+                .width(iced::Length::Fill)
+                .align_x(iced::alignment::Horizontal::Right),
+            ]
+            .align_y(iced::Alignment::Center)
+            .into()
+        });
+        container(iced::widget::Column::with_children(levels).spacing(2.0 * layout.scale))
+            .width(layout.content_width())
+            .height(110.0 * layout.scale)
+            .align_y(iced::alignment::Vertical::Bottom)
+            .into()
+    }
+
+    /// RPN keypad: stack operations above the basic grid
+    fn rpn_keypad(&self, layout: &Layout) -> Element<'_, Message> {
+        let palette = &self.palette();
+        column![
+            // Row 1: ENTER x↔y R↓ LASTx
+            row![
+                stack_button(
+                    layout,
+                    palette,
+                    self.locale().text(Text::Enter),
+                    Message::EnterPressed,
+                    self.key_state(&UIMessage::EnterPressed)
+                ),
+                stack_button(
+                    layout,
+                    palette,
+                    "x↔y",
+                    Message::SwapPressed,
+                    self.key_state(&UIMessage::SwapPressed)
+                ),
+                stack_button(
+                    layout,
+                    palette,
+                    "R↓",
+                    Message::RollDownPressed,
+                    self.key_state(&UIMessage::RollDownPressed)
+                ),
+                stack_button(
+                    layout,
+                    palette,
+                    self.locale().text(Text::LastX),
+                    Message::LastXPressed,
+                    self.key_state(&UIMessage::LastXPressed)
+                ),
+            ]
+            .spacing(layout.spacing()),
+            // Row 2: ⌫ AC DROP ÷
+            row![
+                function_button(
+                    layout,
+                    palette,
+                    "⌫",
+                    Message::BackspacePressed,
+                    self.key_state(&UIMessage::BackspacePressed)
+                ),
+                function_button(
+                    layout,
+                    palette,
+                    self.locale().text(Text::ClearAll),
+                    Message::ClearPressed,
+                    self.key_state(&UIMessage::ClearPressed)
+                ),
+                stack_button(
+                    layout,
+                    palette,
+                    self.locale().text(Text::Drop),
+                    Message::DropPressed,
+                    self.key_state(&UIMessage::DropPressed)
+                ),
+                operator_button(
+                    layout,
+                    palette,
+                    "÷",
+                    Message::OperationPressed(Operation::Divide),
+                    self.key_state(&UIMessage::OperationPressed(Operation::Divide))
+                ),
+            ]
+            .spacing(layout.spacing()),
+            // Rows 3-5: 7 8 9 x / 4 5 6 − / 1 2 3 +
+            self.number_rows(layout),
+            // Row 6: +/- 0 . %
+            row![
+                function_button(
+                    layout,
+                    palette,
+                    "+/-",
+                    Message::SignTogglePressed,
+                    self.key_state(&UIMessage::SignTogglePressed)
+                ),
+                number_button(
+                    layout,
+                    palette,
+                    "0",
+                    Message::NumberPressed(0),
+                    self.key_state(&UIMessage::NumberPressed(0))
+                ),
+                number_button(
+                    layout,
+                    palette,
+                    self.ui_state.settings.decimal_separator.label(),
+                    Message::DecimalPressed,
+                    self.key_state(&UIMessage::DecimalPressed)
+                ),
+                function_button(
+                    layout,
+                    palette,
+                    "%",
+                    Message::PercentagePressed,
+                    self.key_state(&UIMessage::PercentagePressed)
+                ),
+            ]
+            .spacing(layout.spacing()),
+        ]
+        .spacing(layout.spacing())
+        .align_x(iced::Alignment::Center)
+        .width(layout.keypad_width())
+        .into()
+    }
+
+    /// Digit rows shared by both layouts: 7 8 9 x / 4 5 6 − / 1 2 3 +
+    fn number_rows(&self, layout: &Layout) -> iced::widget::Column<'_, Message> {
+        let palette = &self.palette();
+        column![
+            // Row 3: 7 8 9 x
+            row![
+                number_button(
+                    layout,
+                    palette,
+                    "7",
+                    Message::NumberPressed(7),
+                    self.key_state(&UIMessage::NumberPressed(7))
+                ),
+                number_button(
+                    layout,
+                    palette,
+                    "8",
+                    Message::NumberPressed(8),
+                    self.key_state(&UIMessage::NumberPressed(8))
+                ),
+                number_button(
+                    layout,
+                    palette,
+                    "9",
+                    Message::NumberPressed(9),
+                    self.key_state(&UIMessage::NumberPressed(9))
+                ),
+                operator_button(
+                    layout,
+                    palette,
+                    "x",
+                    Message::OperationPressed(Operation::Multiply),
+                    self.key_state(&UIMessage::OperationPressed(Operation::Multiply))
+                ),
+            ]
+            .spacing(layout.spacing()),
+            // Row 4: 4 5 6 −
+            row![
+                number_button(
+                    layout,
+                    palette,
+                    "4",
+                    Message::NumberPressed(4),
+                    self.key_state(&UIMessage::NumberPressed(4))
+                ),
+                number_button(
+                    layout,
+                    palette,
+                    "5",
+                    Message::NumberPressed(5),
+                    self.key_state(&UIMessage::NumberPressed(5))
+                ),
+                number_button(
+                    layout,
+                    palette,
+                    "6",
+                    Message::NumberPressed(6),
+                    self.key_state(&UIMessage::NumberPressed(6))
+                ),
+                operator_button(
+                    layout,
+                    palette,
+                    "−",
+                    Message::OperationPressed(Operation::Subtract),
+                    self.key_state(&UIMessage::OperationPressed(Operation::Subtract))
+                ),
+            ]
+            .spacing(layout.spacing()),
+            // Row 5: 1 2 3 +
+            row![
+                number_button(
+                    layout,
+                    palette,
+                    "1",
+                    Message::NumberPressed(1),
+                    self.key_state(&UIMessage::NumberPressed(1))
+                ),
+                number_button(
+                    layout,
+                    palette,
+                    "2",
+                    Message::NumberPressed(2),
+                    self.key_state(&UIMessage::NumberPressed(2))
+                ),
+                number_button(
+                    layout,
+                    palette,
+                    "3",
+                    Message::NumberPressed(3),
+                    self.key_state(&UIMessage::NumberPressed(3))
+                ),
+                operator_button(
+                    layout,
+                    palette,
+                    "+",
+                    Message::OperationPressed(Operation::Add),
+                    self.key_state(&UIMessage::OperationPressed(Operation::Add))
+                ),
+            ]
+            .spacing(layout.spacing()),
+        ]
+        .spacing(layout.spacing())
+    }
+}
+
+/// A pick list entry shown under its name in the current language
+#[derive(Debug, Clone, PartialEq)]
+struct Choice<T> {
+    value: T,
+    name: &'static str,
+}
+
+impl<T: Copy> Choice<T> {
+    fn new(value: T, name: impl Fn(T) -> &'static str) -> Self {
+        Self {
+            value,
+            name: name(value),
+        }
+    }
+
+    fn all(values: impl IntoIterator<Item = T>, name: impl Fn(T) -> &'static str) -> Vec<Self> {
+        values
+            .into_iter()
+            .map(|value| Self::new(value, &name))
+            .collect()
+    }
+}
+
+impl<T> std::fmt::Display for Choice<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name)
+    }
+}
+
+/// Message for a settings view control: the current settings with one value replaced
+fn setting_changed<T: 'static>(
+    settings: &Settings,
+    apply: fn(&mut Settings, T),
+) -> impl Fn(T) -> Message + 'static {
+    let settings = settings.clone();
+    move |value| {
+        let mut settings = settings.clone();
+        apply(&mut settings, value);
+        Message::SettingsChanged(settings)
+    }
+}
+
+/// Scrolls the expression line to the rightmost position (end/latest input)
+fn scroll_display_to_end() -> Task<Message> {
+    scrollable::scroll_to(
+        DISPLAY_SCROLL_ID.clone(),
+        scrollable::AbsoluteOffset {
+            x: f32::INFINITY,
+            y: 0.0,
+        },
+    )
+}
+
+/// Canvas program drawing the graph view
+struct GraphPlot<'a> {
+    graph: &'a Graph,
+    angle_mode: AngleMode,
+    palette: Palette,
+    cache: &'a canvas::Cache,
+    font_size: f32,
+}
+
+/// Interaction state of the graph canvas
+#[derive(Default)]
+struct GraphPlotState {
+    /// Last cursor position of a drag in progress, relative to the canvas
+    drag_origin: Option<iced::Point>,
+}
+
+impl canvas::Program<Message> for GraphPlot<'_> {
+    type State = GraphPlotState;
+
+    fn update(
+        &self,
+        state: &mut GraphPlotState,
+        event: canvas::Event,
+        bounds: iced::Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (canvas::event::Status, Option<Message>) {
+        let viewport = &self.graph.viewport;
+        let captured = canvas::event::Status::Captured;
+        let canvas::Event::Mouse(event) = event else {
+            return (canvas::event::Status::Ignored, None);
+        };
+
+        match event {
+            mouse::Event::WheelScrolled { delta } => {
+                let Some(position) = cursor.position_in(bounds) else {
+                    return (canvas::event::Status::Ignored, None);
+                };
+                let lines = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => y,
+                    mouse::ScrollDelta::Pixels { y, .. } => y / 40.0,
+                };
+                // Scrolling up zooms in by 10% per line, around the point under the cursor
+                let factor = 0.9f64.powf(lines as f64);
+                let (x, y) =
+                    viewport.from_screen(position.x, position.y, bounds.width, bounds.height);
+                (captured, Some(Message::GraphZoom { factor, x, y }))
+            }
+            mouse::Event::ButtonPressed(mouse::Button::Left) => match cursor.position_in(bounds) {
+                Some(position) => {
+                    state.drag_origin = Some(position);
+                    (captured, None)
+                }
+                None => (canvas::event::Status::Ignored, None),
+            },
+            mouse::Event::CursorMoved { .. } => {
+                let (Some(origin), Some(position)) =
+                    (state.drag_origin, cursor.position_from(bounds.position()))
+                else {
+                    return (canvas::event::Status::Ignored, None);
+                };
+                state.drag_origin = Some(position);
+                // Dragging moves the plane with the cursor
+                let dx = -(position.x - origin.x) as f64 / bounds.width as f64 * viewport.width();
+                let dy = (position.y - origin.y) as f64 / bounds.height as f64 * viewport.height();
+                (captured, Some(Message::GraphPan { dx, dy }))
+            }
+            mouse::Event::ButtonReleased(mouse::Button::Left) if state.drag_origin.is_some() => {
+                state.drag_origin = None;
+                (captured, None)
+            }
+            _ => (canvas::event::Status::Ignored, None),
+        }
+    }
+
+    fn draw(
+        &self,
+        _state: &GraphPlotState,
+        renderer: &iced::Renderer,
+        _theme: &Theme,
+        bounds: iced::Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let plot = self
+            .cache
+            .draw(renderer, bounds.size(), |frame| self.draw_plot(frame));
+
+        let mut trace = canvas::Frame::new(renderer, bounds.size());
+        if let Some(position) = cursor.position_in(bounds) {
+            self.draw_trace(&mut trace, position);
+        }
+        vec![plot, trace.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        state: &GraphPlotState,
+        bounds: iced::Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        if state.drag_origin.is_some() {
+            mouse::Interaction::Grabbing
+        } else if cursor.is_over(bounds) {
+            mouse::Interaction::Crosshair
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
+
+impl GraphPlot<'_> {
+    /// Background, grid, axes with labels and the curves
+    fn draw_plot(&self, frame: &mut canvas::Frame) {
+        let viewport = &self.graph.viewport;
+        let (width, height) = (frame.width(), frame.height());
+        let palette = &self.palette;
+
+        frame.fill_rectangle(iced::Point::ORIGIN, frame.size(), color(palette.number_key));
+
+        // Grid lines about every 60 pixels, with the axes drawn over them
+        let x_step = graph::grid_step(viewport.width(), (width / 60.0).max(2.0) as usize);
+        let y_step = graph::grid_step(viewport.height(), (height / 60.0).max(2.0) as usize);
+        let x_lines = graph::grid_lines(viewport.x_min, viewport.x_max, x_step);
+        let y_lines = graph::grid_lines(viewport.y_min, viewport.y_max, y_step);
+        let (origin_x, origin_y) = viewport.to_screen(0.0, 0.0, width, height);
+
+        let grid = canvas::Stroke::default()
+            .with_color(dimmed(palette.text, 0.12))
+            .with_width(1.0);
+        for &x in &x_lines {
+            let (px, _) = viewport.to_screen(x, 0.0, width, height);
+            frame.stroke(
+                &canvas::Path::line(iced::Point::new(px, 0.0), iced::Point::new(px, height)),
+                grid,
+            );
+        }
+        for &y in &y_lines {
+            let (_, py) = viewport.to_screen(0.0, y, width, height);
+            frame.stroke(
+                &canvas::Path::line(iced::Point::new(0.0, py), iced::Point::new(width, py)),
+                grid,
+            );
+        }
+
+        let axis = canvas::Stroke::default()
+            .with_color(dimmed(palette.text, 0.6))
+            .with_width(1.5);
+        frame.stroke(
+            &canvas::Path::line(
+                iced::Point::new(origin_x, 0.0),
+                iced::Point::new(origin_x, height),
+            ),
+            axis,
+        );
+        frame.stroke(
+            &canvas::Path::line(
+                iced::Point::new(0.0, origin_y),
+                iced::Point::new(width, origin_y),
+            ),
+            axis,
+        );
+
+        // Labels follow the axes, staying at the edge while an axis is out of view
+        let label_color = dimmed(palette.text, 0.7);
+        let label_x = origin_x.clamp(2.0, (width - 4.0 * self.font_size).max(2.0)) + 3.0;
+        let label_y = origin_y.clamp(0.0, (height - 1.5 * self.font_size).max(0.0)) + 3.0;
+        for &x in x_lines.iter().filter(|&&x| x != 0.0) {
+            let (px, _) = viewport.to_screen(x, 0.0, width, height);
+            frame.fill_text(canvas::Text {
+                content: graph::format_tick(x, x_step),
+                position: iced::Point::new(px + 3.0, label_y),
+                color: label_color,
+                size: self.font_size.into(),
+                ..canvas::Text::default()
+            });
+        }
+        for &y in y_lines.iter().filter(|&&y| y != 0.0) {
+            let (_, py) = viewport.to_screen(0.0, y, width, height);
+            frame.fill_text(canvas::Text {
+                content: graph::format_tick(y, y_step),
+                position: iced::Point::new(label_x, py + 2.0),
+                color: label_color,
+                size: self.font_size.into(),
+                ..canvas::Text::default()
+            });
+        }
+
+        // One sample per pixel column
+        for (index, function) in self.graph.functions.iter().enumerate() {
+            let curves = function.sample(viewport, width.max(2.0) as usize, self.angle_mode);
+            let path = canvas::Path::new(|builder| {
+                for curve in &curves {
+                    for (i, &(x, y)) in curve.iter().enumerate() {
+                        let (px, py) = viewport.to_screen(x, y, width, height);
+                        if i == 0 {
+                            builder.move_to(iced::Point::new(px, py));
+                        } else {
+                            builder.line_to(iced::Point::new(px, py));
+                        }
+                    }
+                }
+            });
+            frame.stroke(
+                &path,
+                canvas::Stroke::default()
+                    .with_color(plot_color(palette, index))
+                    .with_width(2.0),
+            );
+        }
+    }
+
+    /// Vertical line at the cursor with the value of every function there
+    fn draw_trace(&self, frame: &mut canvas::Frame, position: iced::Point) {
+        let viewport = &self.graph.viewport;
+        let (width, height) = (frame.width(), frame.height());
+        let (x, _) = viewport.from_screen(position.x, position.y, width, height);
+
+        frame.stroke(
+            &canvas::Path::line(
+                iced::Point::new(position.x, 0.0),
+                iced::Point::new(position.x, height),
+            ),
+            canvas::Stroke::default()
+                .with_color(dimmed(self.palette.text, 0.3))
+                .with_width(1.0),
+        );
+
+        for (index, function) in self.graph.functions.iter().enumerate() {
+            let value = function.evaluate(x, self.angle_mode);
+            let label = match value {
+                Some(y) => {
+                    let (px, py) = viewport.to_screen(x, y, width, height);
+                    frame.fill(
+                        &canvas::Path::circle(iced::Point::new(px, py), 4.0),
+                        plot_color(&self.palette, index),
+                    );
+                    format!(
+                        "({}, {})",
+                        rust_calculator::Calculator::format_result(x),
+                        rust_calculator::Calculator::format_result(y)
+                    )
+                }
+                None => format!(
+                    "({}, undefined)",
+                    rust_calculator::Calculator::format_result(x)
+                ),
+            };
+            frame.fill_text(canvas::Text {
+                content: label,
+                position: iced::Point::new(6.0, 6.0 + index as f32 * 1.4 * self.font_size),
+                color: plot_color(&self.palette, index),
+                size: self.font_size.into(),
+                ..canvas::Text::default()
+            });
+        }
+    }
+}
+
+/// Curve color of the function at `index`; the first uses the operator key color
+fn plot_color(palette: &Palette, index: usize) -> iced::Color {
+    const COLORS: [Rgb; 4] = [
+        Rgb(10, 132, 255),
+        Rgb(48, 209, 88),
+        Rgb(255, 55, 95),
+        Rgb(191, 90, 242),
+    ];
+    match index {
+        0 => color(palette.operator_key),
+        index => color(COLORS[(index - 1) % COLORS.len()]),
+    }
+}
+
+/// Converts a palette color to an iced color
+fn color(rgb: Rgb) -> iced::Color {
+    iced::Color::from_rgb8(rgb.0, rgb.1, rgb.2)
+}
+
+/// Spans of the expression line: the text of `segments`, those marked `true` underlined
+/// in the error color, with a caret drawn before character `caret` of the whole line.
+fn expression_spans(
+    segments: Vec<(String, bool)>,
+    caret: Option<usize>,
+    palette: &Palette,
+) -> Vec<text::Span<'static, Message>> {
+    let styled = |text: String, offending: bool| {
+        let styled = span(text);
+        if offending {
+            styled.color(color(palette.error)).underline(true)
+        } else {
+            styled
+        }
+    };
+    let mut spans = Vec::new();
+    let mut caret = caret;
+    for (text, offending) in segments {
+        let len = text.chars().count();
+        match caret {
+            Some(position) if position < len => {
+                let index = text
+                    .char_indices()
+                    .nth(position)
+                    .map_or(0, |(index, _)| index);
+                let (before, after) = text.split_at(index);
+                if !before.is_empty() {
+                    spans.push(styled(before.to_string(), offending));
+                }
+                spans.push(span("|").color(color(palette.operator_key)));
+                spans.push(styled(after.to_string(), offending));
+                caret = None;
+            }
+            _ => {
+                caret = caret.map(|position| position - len);
+                spans.push(styled(text, offending));
+            }
+        }
+    }
+    spans
+}
+
+/// Palette color with reduced opacity, for secondary text
+fn dimmed(rgb: Rgb, alpha: f32) -> iced::Color {
+    iced::Color {
+        a: alpha,
+        ..color(rgb)
+    }
+}
+
+/// Border width of a key: thicker while pressed, thin when the palette outlines every key
+fn key_border_width(palette: &Palette, pressed: bool) -> f32 {
+    if pressed {
+        2.0
+    } else if palette.outlined {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// Highlighting of a keypad button
+#[derive(Debug, Clone, Copy)]
+struct KeyState {
+    /// Held down on the keyboard, or an active mode such as 2nd
+    pressed: bool,
+    /// Has the keyboard focus
+    focused: bool,
+}
+
+/// Convenience functions for different button types following the example pattern
+/// All buttons share the same size, scaled from 70x70 with padding 16 by the layout,
+/// and take their colors from the active palette
+fn number_button<'a>(
+    layout: &Layout,
+    palette: &Palette,
+    label: &'a str,
+    on_press: Message,
+    state: KeyState,
+) -> Element<'a, Message> {
+    let background_color = if state.pressed {
+        color(palette.number_key_pressed) // Lighter color when pressed
+    } else {
+        color(palette.number_key)
+    };
+    key_button(
+        layout,
+        palette,
+        text(label).size(layout.font(24.0)),
+        on_press,
+        background_color,
+        color(palette.text),
+        state,
+    )
+}
+
+fn operator_button<'a>(
+    layout: &Layout,
+    palette: &Palette,
+    label: &'a str,
+    on_press: Message,
+    state: KeyState,
+) -> Element<'a, Message> {
+    let background_color = if state.pressed {
+        color(palette.operator_key_pressed) // Lighter operator color when pressed
+    } else {
+        color(palette.operator_key)
+    };
+    key_button(
+        layout,
+        palette,
+        text(label).size(layout.font(24.0)),
+        on_press,
+        background_color,
+        color(palette.operator_text),
+        state,
+    )
+}
+
+/// Stack operation button for the RPN keypad - function colors with a smaller label
+fn stack_button<'a>(
+    layout: &Layout,
+    palette: &Palette,
+    label: &'a str,
+    on_press: Message,
+    state: KeyState,
+) -> Element<'a, Message> {
+    key_button(
+        layout,
+        palette,
+        text(label).size(layout.font(14.0)),
+        on_press,
+        color(palette.function_key),
+        color(palette.text),
+        // Stack keys keep their flat look while held
+        KeyState {
+            pressed: false,
+            ..state
+        },
+    )
+}
+
+/// Scientific panel button - function colors with a smaller label, highlighted while active
+fn scientific_button<'a>(
+    layout: &Layout,
+    palette: &Palette,
+    label: &'a str,
+    on_press: Message,
+    state: KeyState,
+) -> Element<'a, Message> {
+    let background_color = if state.pressed {
+        color(palette.function_key_pressed) // Pressed color and a border while the layer is active
+    } else {
+        color(palette.function_key)
+    };
+    key_button(
+        layout,
+        palette,
+        text(label).size(layout.font(16.0)),
+        on_press,
+        background_color,
+        color(palette.text),
+        state,
+    )
+}
+
+fn function_button<'a>(
+    layout: &Layout,
+    palette: &Palette,
+    label: &'a str,
+    on_press: Message,
+    state: KeyState,
+) -> Element<'a, Message> {
+    let background_color = if state.pressed {
+        color(palette.function_key_pressed) // Lighter color when pressed
+    } else {
+        color(palette.function_key)
+    };
+    key_button(
+        layout,
+        palette,
+        text(label).size(layout.font(20.0)),
+        on_press,
+        background_color,
+        color(palette.text),
+        state,
+    )
+}
+
+/// Shared round key shape used by all button types
+fn key_button<'a>(
+    layout: &Layout,
+    palette: &Palette,
+    label: iced::widget::Text<'a>,
+    on_press: Message,
+    background_color: iced::Color,
+    text_color: iced::Color,
+    state: KeyState,
+) -> Element<'a, Message> {
+    let radius = 30.0 * layout.scale;
+    // The focus ring uses the text color, which contrasts with every key color
+    let (border_color, border_width) = if state.focused {
+        (color(palette.text), 3.0 * layout.scale)
+    } else {
+        (
+            color(palette.border),
+            key_border_width(palette, state.pressed),
+        )
+    };
+
+    button(
+        label
+            .align_x(iced::alignment::Horizontal::Center)
+            .align_y(iced::alignment::Vertical::Center),
+    )
+    .on_press(on_press)
+    .padding(16.0 * layout.scale)
+    .width(layout.button_size())
+    .height(layout.button_size())
+    .style(move |_theme: &Theme, _status| button::Style {
+        background: Some(iced::Background::Color(background_color)),
+        text_color,
+        border: iced::Border {
+            color: border_color,
+            width: border_width,
+            radius: radius.into(),
+        },
+        ..Default::default()
+    })
+    .into()
+}
@@ -0,0 +1,400 @@
+use crate::calculator::ExpressionError;
+use crate::cli::{EvalOptions, parse_angle_mode, parse_format, validate_precision};
+use crate::expression::MAX_INPUT_LENGTH;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use std::io;
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::runtime::Runtime;
+
+/// Most expressions one `POST /batch` request may hold.
+pub const MAX_BATCH_EXPRESSIONS: usize = 100;
+/// Most bytes of the request line and headers.
+pub const MAX_HEADER_SIZE: usize = 8 * 1024;
+/// Room in a request body for the JSON around the expressions, e.g. the options.
+const ENVELOPE_SIZE: usize = 1024;
+/// Most bytes of a `POST /evaluate` body: one expression the evaluator accepts, in JSON.
+pub const MAX_EVALUATE_BODY: usize = MAX_INPUT_LENGTH + ENVELOPE_SIZE;
+/// Most bytes of a `POST /batch` body: `MAX_BATCH_EXPRESSIONS` expressions the evaluator
+/// accepts, in JSON.
+pub const MAX_BATCH_BODY: usize = MAX_BATCH_EXPRESSIONS * (MAX_INPUT_LENGTH + 8) + ENVELOPE_SIZE;
+/// How long a client may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An HTTP response: the status code and a JSON body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    /// Status code, e.g. 200
+    pub status: u16,
+    /// JSON body
+    pub body: Value,
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Self { status: 200, body }
+    }
+
+    /// An error that isn't about an expression, e.g. a malformed request.
+    fn error(status: u16, kind: &str, message: impl Into<String>) -> Self {
+        let message: String = message.into();
+        Self {
+            status,
+            body: json!({"error": {"kind": kind, "message": message}}),
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            408 => "Request Timeout",
+            411 => "Length Required",
+            413 => "Payload Too Large",
+            422 => "Unprocessable Entity",
+            431 => "Request Header Fields Too Large",
+            _ => "Internal Server Error",
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let body = self.body.to_string();
+        format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+             Connection: close\r\n\r\n{}",
+            self.status,
+            self.reason(),
+            body.len(),
+            body
+        )
+        .into_bytes()
+    }
+}
+
+/// Evaluation options of a request; missing ones keep the server's defaults.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RequestOptions {
+    angle: Option<String>,
+    precision: Option<usize>,
+    format: Option<String>,
+}
+
+impl RequestOptions {
+    fn apply(&self, mut options: EvalOptions) -> Result<EvalOptions, String> {
+        if let Some(angle) = &self.angle {
+            options.angle_mode = parse_angle_mode(angle)?;
+        }
+        if let Some(precision) = self.precision {
+            options.number_format.precision = validate_precision(precision)?;
+        }
+        if let Some(format) = &self.format {
+            options.number_format.display_format = parse_format(format)?;
+        }
+        Ok(options)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EvaluateRequest {
+    expression: String,
+    #[serde(default)]
+    options: RequestOptions,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchRequest {
+    expressions: Vec<String>,
+    #[serde(default)]
+    options: RequestOptions,
+}
+
+/// Most bytes of the body of a request to `path`, or `None` for paths without a body.
+fn body_limit(path: &str) -> Option<usize> {
+    match path {
+        "/evaluate" => Some(MAX_EVALUATE_BODY),
+        "/batch" => Some(MAX_BATCH_BODY),
+        _ => None,
+    }
+}
+
+/// Reads a JSON request body.
+fn parse_body<T: DeserializeOwned>(body: &[u8]) -> Result<T, Response> {
+    serde_json::from_slice(body)
+        .map_err(|error| Response::error(400, "bad_request", format!("Invalid body: {}", error)))
+}
+
+/// The result of one expression: `{value, formatted}`, or `{error: {kind, message, start,
+/// end}}` with the positions of the characters at fault.
+fn evaluate(expression: &str, options: &EvalOptions) -> Result<Value, Value> {
+    let calculator = options.calculator();
+    calculator
+        .evaluate_spanned(expression)
+        .map(|value| json!({"value": value, "formatted": calculator.format_value(value)}))
+        .map_err(|error: ExpressionError| {
+            json!({"error": {
+                "kind": error.kind.name(),
+                "message": error.to_string(),
+                "start": error.span.start,
+                "end": error.span.end,
+            }})
+        })
+}
+
+/// Answers a request, given its method, path without the query and body.
+///
+/// - `GET /health` → `{"status": "ok"}`
+/// - `POST /evaluate` with `{expression, options}` → `{value, formatted}`, or status 422
+///   and `{error: {kind, message, start, end}}`
+/// - `POST /batch` with `{expressions, options}` → `{results}`, one result or error per
+///   expression
+///
+/// `options` may set `angle`, `precision` and `format` like the command-line options; the
+/// others keep the server's defaults.
+///
+/// # Examples
+///
+/// ```
+/// use rust_calculator::cli::EvalOptions;
+/// use rust_calculator::http::respond;
+/// use serde_json::json;
+///
+/// let options = EvalOptions::default();
+/// let response = respond("POST", "/evaluate", br#"{"expression":"7+8*3"}"#, &options);
+/// assert_eq!(response.status, 200);
+/// assert_eq!(response.body, json!({"value": 31.0, "formatted": "31"}));
+///
+/// let response = respond("POST", "/evaluate", br#"{"expression":"1/0"}"#, &options);
+/// assert_eq!(response.status, 422);
+/// assert_eq!(response.body["error"]["kind"], "division_by_zero");
+/// ```
+pub fn respond(method: &str, path: &str, body: &[u8], options: &EvalOptions) -> Response {
+    match (method, path) {
+        ("GET", "/health") => Response::ok(json!({"status": "ok"})),
+        ("POST", "/evaluate") => {
+            let request: EvaluateRequest = match parse_body(body) {
+                Ok(request) => request,
+                Err(response) => return response,
+            };
+            let options = match request.options.apply(*options) {
+                Ok(options) => options,
+                Err(message) => return Response::error(400, "bad_request", message),
+            };
+            match evaluate(&request.expression, &options) {
+                Ok(result) => Response::ok(result),
+                Err(error) => Response {
+                    status: 422,
+                    body: error,
+                },
+            }
+        }
+        ("POST", "/batch") => {
+            let request: BatchRequest = match parse_body(body) {
+                Ok(request) => request,
+                Err(response) => return response,
+            };
+            if request.expressions.len() > MAX_BATCH_EXPRESSIONS {
+                return Response::error(
+                    413,
+                    "payload_too_large",
+                    format!("At most {} expressions per batch", MAX_BATCH_EXPRESSIONS),
+                );
+            }
+            let options = match request.options.apply(*options) {
+                Ok(options) => options,
+                Err(message) => return Response::error(400, "bad_request", message),
+            };
+            let results: Vec<Value> = request
+                .expressions
+                .iter()
+                .map(|expression| evaluate(expression, &options).unwrap_or_else(|error| error))
+                .collect();
+            Response::ok(json!({"results": results}))
+        }
+        (_, "/health" | "/evaluate" | "/batch") => Response::error(
+            405,
+            "method_not_allowed",
+            format!("{} is not allowed for {}", method, path),
+        ),
+        _ => Response::error(404, "not_found", format!("No endpoint at {}", path)),
+    }
+}
+
+/// Reads one request from a connection and answers it. Requests whose headers or body
+/// exceed the limits are answered without reading the rest.
+async fn handle_connection(stream: TcpStream, options: &EvalOptions) -> io::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    let response =
+        match tokio::time::timeout(READ_TIMEOUT, read_request(&mut reader, options)).await {
+            Ok(Ok(response)) => response,
+            Ok(Err(error)) => return Err(error),
+            Err(_) => Response::error(408, "request_timeout", "The request took too long"),
+        };
+    writer.write_all(&response.to_bytes()).await?;
+    writer.shutdown().await?;
+
+    // Closing with unread input would reset the connection and could lose the response
+    // to a refused request, so read what the client still sends for a moment
+    let (mut unread, mut sink) = (reader.take(MAX_BATCH_BODY as u64), tokio::io::sink());
+    let drain = tokio::io::copy(&mut unread, &mut sink);
+    let _ = tokio::time::timeout(Duration::from_secs(1), drain).await;
+    Ok(())
+}
+
+async fn read_request(
+    reader: &mut BufReader<impl AsyncRead + Unpin>,
+    options: &EvalOptions,
+) -> io::Result<Response> {
+    let too_large = || {
+        Response::error(
+            431,
+            "headers_too_large",
+            "The request headers are too large",
+        )
+    };
+
+    // Request line and headers, up to the empty line
+    let mut lines = Vec::new();
+    let mut remaining = MAX_HEADER_SIZE;
+    loop {
+        let mut line = String::new();
+        let read = (&mut *reader)
+            .take(remaining as u64)
+            .read_line(&mut line)
+            .await?;
+        if !line.ends_with('\n') {
+            return Ok(if read == remaining {
+                too_large()
+            } else {
+                Response::error(400, "bad_request", "Incomplete request")
+            });
+        }
+        remaining -= read;
+        let line = line.trim_end().to_string();
+        if line.is_empty() {
+            break;
+        }
+        lines.push(line);
+    }
+
+    let mut request_line = lines
+        .first()
+        .map(|line| line.split(' '))
+        .into_iter()
+        .flatten();
+    let (Some(method), Some(target)) = (request_line.next(), request_line.next()) else {
+        return Ok(Response::error(
+            400,
+            "bad_request",
+            "Malformed request line",
+        ));
+    };
+    let path = target.split('?').next().unwrap_or(target);
+
+    let mut content_length = None;
+    for header in &lines[1..] {
+        let Some((name, value)) = header.split_once(':') else {
+            return Ok(Response::error(400, "bad_request", "Malformed header"));
+        };
+        if name.eq_ignore_ascii_case("content-length") {
+            match value.trim().parse::<usize>() {
+                Ok(length) => content_length = Some(length),
+                Err(_) => {
+                    return Ok(Response::error(
+                        400,
+                        "bad_request",
+                        "Invalid Content-Length",
+                    ));
+                }
+            }
+        }
+    }
+
+    let mut body = Vec::new();
+    if method == "POST"
+        && let Some(limit) = body_limit(path)
+    {
+        let Some(length) = content_length else {
+            return Ok(Response::error(
+                411,
+                "length_required",
+                "Content-Length is required",
+            ));
+        };
+        if length > limit {
+            return Ok(Response::error(
+                413,
+                "payload_too_large",
+                format!("The body of {} may have at most {} bytes", path, limit),
+            ));
+        }
+        body.resize(length, 0);
+        reader.read_exact(&mut body).await?;
+    }
+
+    Ok(respond(method, path, &body, options))
+}
+
+/// An HTTP evaluation server bound to an address, with its own tokio runtime.
+#[derive(Debug)]
+pub struct HttpServer {
+    runtime: Runtime,
+    listener: TcpListener,
+    options: EvalOptions,
+}
+
+impl HttpServer {
+    /// Binds to an address such as `127.0.0.1:8080`; port 0 picks a free port. Requests
+    /// are evaluated with `options` unless they ask for other ones.
+    pub fn bind(address: &str, options: EvalOptions) -> io::Result<Self> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+        let listener = runtime.block_on(TcpListener::bind(address))?;
+        Ok(Self {
+            runtime,
+            listener,
+            options,
+        })
+    }
+
+    /// The address the server listens on.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Answers requests for as long as the process runs. Every connection is served by its
+    /// own task, so requests are handled concurrently; a failed accept is logged and skipped.
+    pub fn run(self) -> io::Result<()> {
+        let Self {
+            runtime,
+            listener,
+            options,
+        } = self;
+        runtime.block_on(async move {
+            loop {
+                let stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    Err(error) => {
+                        // Running out of descriptors or an aborted handshake is transient
+                        eprintln!("rust-calculator: accept failed: {}", error);
+                        continue;
+                    }
+                };
+                tokio::spawn(async move {
+                    // A client that goes away only loses its own response
+                    let _ = handle_connection(stream, &options).await;
+                });
+            }
+        })
+    }
+}
@@ -1,18 +1,18 @@
-use crate::calculator::{Calculator, Operation};
+use crate::calculator::{Calculator, CalculatorError, Constant, Function, Operation, SyntaxError};
 
 impl Calculator {
     /// Handles number input for the calculator.
     pub fn handle_number_input(&mut self, digit: u8) {
-        if self.display == "Error"
-            || self.display.starts_with("Invalid")
-            || self.display.starts_with("Division")
-            || self.display.starts_with("Number out of range")
-        {
-            // Clear any error state and start fresh
-            self.expression = digit.to_string();
-            self.display = digit.to_string();
+        self.error = None;
+        if self.cursor_is_inside() {
+            self.insert_at_cursor(&digit.to_string());
+        } else if Self::ends_with_group(&self.expression) {
+            // A number right after a closing parenthesis, factorial or constant multiplies it
+            self.expression.push('x');
+            self.expression.push_str(&digit.to_string());
+            self.display = self.display_string();
             self.new_input = false;
-        } else if self.new_input && !self.expression.contains(|c| "+-x÷".contains(c)) {
+        } else if self.new_input && !self.expression.contains(|c| "+-x÷^()".contains(c)) {
             // If expression is just a number (result), replace it
             self.expression = digit.to_string();
             self.display = digit.to_string();
@@ -32,20 +32,51 @@ impl Calculator {
 
     /// Handles operation input for the calculator.
     pub fn handle_operation_input(&mut self, operation: Operation) {
-        if self.display == "Error" {
-            return;
-        }
+        self.error = None;
         let op_char = match operation {
             Operation::Add => "+",
             Operation::Subtract => "-",
             Operation::Multiply => "x",
             Operation::Divide => "÷",
+            Operation::Power => "^",
         };
-        // If the expression ends with an operator, replace it instead of appending
-        if let Some(last_char) = self.expression.chars().last()
-            && "+-x÷".contains(last_char)
+        // After an opening parenthesis only a unary minus can start the operand
+        if operation != Operation::Subtract
+            && self
+                .expression_before_cursor()
+                .trim_end_matches(|c| "+-x÷^".contains(c))
+                .ends_with('(')
+        {
+            return;
+        }
+        // A minus right after x, ÷ or ^ is the sign of the next operand, as in `2^-1`
+        let signs_operand = operation == Operation::Subtract;
+        if self.cursor_is_inside() {
+            // Replace an operator directly before the cursor instead of stacking operators
+            let pos = self.cursor_position();
+            let before = self.char_before_cursor();
+            if before.is_some_and(|c| "+-x÷^".contains(c))
+                && !(signs_operand && before.is_some_and(|c| "x÷^".contains(c)))
+            {
+                let start = self.byte_index(pos - 1);
+                let end = self.byte_index(pos);
+                self.expression.replace_range(start..end, "");
+                self.cursor = Some(pos - 1);
+            }
+            self.insert_at_cursor(op_char);
+            return;
+        }
+        // If the expression ends with operators, replace them instead of appending
+        let operand_len = self
+            .expression
+            .trim_end_matches(|c| "+-x÷^".contains(c))
+            .len();
+        let trailing = self.expression[operand_len..].chars().next();
+        self.expression.truncate(operand_len);
+        if let Some(operator @ ('x' | '÷' | '^')) = trailing
+            && signs_operand
         {
-            self.expression.pop();
+            self.expression.push(operator);
         }
         self.expression.push_str(op_char);
         self.display = self.display_string(); // Update display to show full expression
@@ -53,40 +84,40 @@ impl Calculator {
     }
 
     /// Handles equals input for the calculator.
+    ///
+    /// On error the expression is kept so it can be fixed: `error` tells what went wrong
+    /// and where, and the cursor is placed right after the offending characters.
     pub fn handle_equals_input(&mut self) {
-        if self.display == "Error" {
-            return;
-        }
-        match self.evaluate(&self.expression) {
+        // Close any parentheses the user left open
+        self.expression = Self::with_closed_parens(&self.expression);
+        self.cursor = None;
+        match self.evaluate_spanned(&self.expression) {
             Ok(result) => {
-                // Format nice result for display
-                self.display = if result.abs() >= 1e6 || (result.abs() < 1e-4 && result != 0.0) {
-                    format!("{:.4e}", result)
-                } else {
-                    // Remove unnecessary trailing zeros and decimal point
-                    let formatted = format!("{:.8}", result);
-                    formatted
-                        .trim_end_matches('0')
-                        .trim_end_matches('.')
-                        .to_string()
-                };
+                self.display = self.format_value(result);
                 self.expression = result.to_string(); // keep full precision
                 self.new_input = true;
+                self.error = None;
             }
             Err(error) => {
-                self.display = error.clone();
-                self.expression = "0".to_string();
+                self.display = self.display_string();
+                self.new_input = false;
+                if error.span.end < self.expression.chars().count() {
+                    self.cursor = Some(error.span.end);
+                }
+                self.error = Some(error);
             }
         }
     }
 
     /// Handles decimal point input for the calculator.
     pub fn handle_decimal_input(&mut self) {
-        if self.display == "Error" {
-            self.expression = "0.".to_string();
-            self.display = "0.".to_string();
-            self.new_input = false;
-        } else if self.new_input && !self.expression.contains(|c| "+-x÷".contains(c)) {
+        self.error = None;
+        if self.cursor_is_inside() {
+            // Only add decimal if the number around the cursor doesn't have one yet
+            if !self.number_at_cursor().contains('.') {
+                self.insert_at_cursor(".");
+            }
+        } else if self.new_input && !self.expression.contains(|c| "+-x÷^()".contains(c)) {
             // If expression is just a number (result), replace it
             self.expression = "0.".to_string();
             self.display = "0.".to_string();
@@ -106,18 +137,142 @@ impl Calculator {
         // If already has decimal, do nothing
     }
 
+    /// Handles opening parenthesis input for the calculator.
+    /// A parenthesis right after a number or a closing parenthesis is treated as multiplication.
+    pub fn handle_open_paren_input(&mut self) {
+        self.handle_prefix_input("(");
+    }
+
+    /// Handles function input (e.g. `sin`) by starting a `name(` group.
+    pub fn handle_function_input(&mut self, function: Function) {
+        self.handle_prefix_input(&format!("{}(", function.name()));
+    }
+
+    /// Handles constant input (e.g. `π`).
+    pub fn handle_constant_input(&mut self, constant: Constant) {
+        self.handle_prefix_input(constant.symbol());
+    }
+
+    /// Inserts text that starts a new operand, such as `(`, `sin(`, `10^(` or `π`.
+    ///
+    /// Replaces a lone zero or a finished result. Right after an operand
+    /// the text is joined with an implicit multiplication.
+    pub fn handle_prefix_input(&mut self, prefix: &str) {
+        self.error = None;
+        if self.cursor_is_inside() {
+            self.insert_at_cursor(prefix);
+        } else if self.expression == "0"
+            || (self.new_input && !self.expression.contains(|c| "+-x÷^()".contains(c)))
+        {
+            // Start a fresh expression instead of appending to a result
+            self.expression = prefix.to_string();
+            self.display = self.display_string();
+            self.new_input = false;
+        } else {
+            if self
+                .expression
+                .ends_with(|c: char| c.is_ascii_digit() || c == '.')
+                || Self::ends_with_group(&self.expression)
+            {
+                self.expression.push('x');
+            }
+            self.expression.push_str(prefix);
+            self.display = self.display_string();
+            self.new_input = false;
+        }
+    }
+
+    /// Handles postfix input such as `!` or `^(2)`, applied to the operand before the cursor.
+    /// The input is refused when there is no operand to apply it to.
+    pub fn handle_postfix_input(&mut self, suffix: &str) {
+        self.error = None;
+        let before = self.expression_before_cursor();
+        if !(before.ends_with(|c: char| c.is_ascii_digit() || c == '.')
+            || Self::ends_with_group(before))
+        {
+            return;
+        }
+        if self.cursor_is_inside() {
+            self.insert_at_cursor(suffix);
+        } else {
+            self.expression.push_str(suffix);
+            self.display = self.display_string();
+            self.new_input = false;
+        }
+    }
+
+    /// Returns true if the text ends with a complete operand that isn't a plain number:
+    /// a closing parenthesis, a factorial or a constant.
+    fn ends_with_group(expr: &str) -> bool {
+        expr.ends_with([')', '!', 'π']) || expr.ends_with(Constant::E.symbol())
+    }
+
+    /// Handles closing parenthesis input for the calculator.
+    /// The input is refused when there is no unmatched opening parenthesis before it,
+    /// or when the group would be closed without an operand.
+    pub fn handle_close_paren_input(&mut self) {
+        self.error = None;
+        let before = self.expression_before_cursor();
+        let depth = Self::paren_depth(before);
+        if depth == 0 || before.ends_with(|c| "+-x÷^(".contains(c)) {
+            return;
+        }
+        if self.cursor_is_inside() {
+            self.insert_at_cursor(")");
+        } else {
+            self.expression.push(')');
+            self.display = self.display_string();
+            self.new_input = false;
+        }
+    }
+
+    /// Returns how many opening parentheses in the expression are still unclosed.
+    pub fn open_paren_depth(&self) -> usize {
+        Self::paren_depth(&self.expression)
+    }
+
+    /// Returns the expression with any unclosed parentheses closed.
+    pub(crate) fn with_closed_parens(expr: &str) -> String {
+        format!("{}{}", expr, ")".repeat(Self::paren_depth(expr)))
+    }
+
+    /// Counts unclosed opening parentheses in a string.
+    pub(crate) fn paren_depth(expr: &str) -> usize {
+        expr.chars().fold(0, |depth, c| match c {
+            '(' => depth + 1,
+            ')' => depth.saturating_sub(1),
+            _ => depth,
+        })
+    }
+
     /// Handles backspace input for the calculator.
     pub fn handle_backspace_input(&mut self) {
-        if self.display == "Error" {
-            self.expression = "0".to_string();
-            self.display = "0".to_string();
+        self.error = None;
+        if self.cursor_is_inside() {
+            let pos = self.cursor_position();
+            if pos > 0 {
+                self.cursor = Some(pos - 1);
+                self.remove_at_cursor();
+            }
+        } else if let Some(function) = Function::ALL
+            .into_iter()
+            .filter(|f| self.expression.ends_with(&format!("{}(", f.name())))
+            .max_by_key(|f| f.name().len())
+        {
+            // Remove a function together with its opening parenthesis
+            let len = self.expression.len() - function.name().len() - 1;
+            self.expression.truncate(len);
+            if self.expression.is_empty() {
+                self.expression = "0".to_string();
+            }
+            self.display = self.display_string();
             self.new_input = false;
-        } else if self.expression.len() > 1 {
+        } else if self.expression.chars().count() > 1 {
             // Remove last character
             let last_char = self.expression.pop().unwrap();
 
             // Update display based on what was removed
-            if "+-x÷".contains(last_char) {
+            if "+-x÷^".contains(last_char) {
                 // Removed an operator, show the full expression
                 self.display = self.display_string();
                 self.new_input = true;
@@ -137,7 +292,7 @@ impl Calculator {
 
     /// Extracts the last number from the expression (before the last operator)
     pub fn extract_last_number(&self) -> String {
-        if let Some(last_op_pos) = self.expression.rfind(|c: char| "+-x÷".contains(c)) {
+        if let Some(last_op_pos) = self.expression.rfind(|c: char| "+-x÷^".contains(c)) {
             self.expression[last_op_pos + 1..].to_string()
         } else {
             self.expression.clone()
@@ -146,7 +301,7 @@ impl Calculator {
 
     /// Extracts the current number being entered (after the last operator)
     pub fn extract_current_number(&self) -> String {
-        if let Some(last_op_pos) = self.expression.rfind(|c: char| "+-x÷".contains(c)) {
+        if let Some(last_op_pos) = self.expression.rfind(|c: char| "+-x÷^".contains(c)) {
             self.expression[last_op_pos + 1..].to_string()
         } else {
             self.expression.clone()
@@ -155,6 +310,8 @@ impl Calculator {
 
     /// Handles percentage input for the calculator.
     pub fn handle_percentage_input(&mut self) {
+        self.error = None;
+        self.cursor = None;
         if let Ok(value) = self.display.parse::<f64>() {
             let percentage = value / 100.0;
             self.display = percentage.to_string();
@@ -170,15 +327,17 @@ impl Calculator {
 
     /// Handles sign toggle input for the calculator.
     pub fn handle_sign_toggle_input(&mut self) {
+        self.error = None;
+        self.cursor = None;
         // Determine if we're toggling an operand within an expression or the entire expression
-        let has_operators = match (
-            self.expression.contains(|c: char| "+x÷".contains(c)),
-            self.expression.contains('-'),
-            self.find_last_operator_position(&self.expression),
-        ) {
-            (true, _, _) | (false, true, Some(_)) => true,
-            _ => false,
-        };
+        let has_operators = matches!(
+            (
+                self.expression.contains(|c: char| "+x÷".contains(c)),
+                self.expression.contains('-'),
+                self.find_last_operator_position(&self.expression),
+            ),
+            (true, _, _) | (false, true, Some(_))
+        );
 
         if has_operators {
             // Has operators - we're toggling an operand within an expression
@@ -266,12 +425,12 @@ impl Calculator {
             match (paren_depth, c, i) {
                 (_, ')', _) => paren_depth += 1,
                 (_, '(', _) => paren_depth -= 1,
-                (0, c, _) if "+-x÷".contains(c) => {
+                (0, c, _) if "+-x÷^".contains(c) => {
                     // We're at the top level and found an operator
                     // Check if this is a '-' that is a sign for a negative number
                     let is_negative_sign = match (c, i) {
                         ('-', 0) => true, // '-' at the beginning of expression
-                        ('-', i) if i > 0 && "+-x÷".contains(chars[(i - 1) as usize]) => true, // '-' after another operator
+                        ('-', i) if i > 0 && "+-x÷^".contains(chars[(i - 1) as usize]) => true, // '-' after another operator
                         _ => false, // separating operator
                     };
 
@@ -290,8 +449,146 @@ impl Calculator {
 
     /// Handles clear input for the calculator.
     pub fn handle_clear_input(&mut self) {
+        self.error = None;
         self.expression = "0".to_string();
         self.display = "0".to_string();
         self.new_input = false;
+        self.cursor = None;
+    }
+
+    /// Loads external text (e.g. pasted from the clipboard) as the new expression.
+    ///
+    /// The text is normalized with [`Calculator::normalize_input`] and then checked with
+    /// [`Calculator::validate_input`]. On error the current expression is left untouched.
+    ///
+    /// # Returns
+    /// * `Ok(())` if the text was loaded
+    /// * `Err(CalculatorError)` if the text is empty or fails validation
+    pub fn load_expression(&mut self, text: &str) -> Result<(), CalculatorError> {
+        let normalized = Self::normalize_input(text);
+        if normalized.is_empty() {
+            return Err(SyntaxError::Empty.into());
+        }
+        Self::validate_input(&normalized)?;
+
+        self.expression = normalized;
+        self.display = self.display_string();
+        self.new_input = false;
+        self.cursor = None;
+        self.error = None;
+        Ok(())
+    }
+
+    /// Handles delete input, removing the character right after the cursor.
+    /// Does nothing when the cursor is at the end of the expression.
+    pub fn handle_delete_input(&mut self) {
+        self.error = None;
+        if self.cursor_is_inside() {
+            self.remove_at_cursor();
+        }
+    }
+
+    /// Moves the cursor one character to the left.
+    pub fn handle_cursor_left(&mut self) {
+        let pos = self.cursor_position();
+        if pos > 0 {
+            self.cursor = Some(pos - 1);
+        }
+    }
+
+    /// Moves the cursor one character to the right, pinning it to the end when it gets there.
+    pub fn handle_cursor_right(&mut self) {
+        let pos = self.cursor_position();
+        self.cursor = if pos + 1 < self.expression.chars().count() {
+            Some(pos + 1)
+        } else {
+            None
+        };
+    }
+
+    /// Moves the cursor to the start of the expression.
+    pub fn handle_cursor_home(&mut self) {
+        self.cursor = Some(0);
+    }
+
+    /// Moves the cursor to the end of the expression.
+    pub fn handle_cursor_end(&mut self) {
+        self.cursor = None;
+    }
+
+    /// Returns the cursor position as a character index into the expression.
+    /// A cursor pinned to the end returns the expression length.
+    pub fn cursor_position(&self) -> usize {
+        let len = self.expression.chars().count();
+        self.cursor.map_or(len, |pos| pos.min(len))
+    }
+
+    /// Returns true when the cursor sits before the last character of the expression,
+    /// so input has to be inserted rather than appended.
+    fn cursor_is_inside(&self) -> bool {
+        self.cursor_position() < self.expression.chars().count()
+    }
+
+    /// Converts a character index into a byte index of the expression.
+    fn byte_index(&self, char_index: usize) -> usize {
+        self.expression
+            .char_indices()
+            .nth(char_index)
+            .map_or(self.expression.len(), |(i, _)| i)
+    }
+
+    /// Returns the part of the expression before the cursor.
+    fn expression_before_cursor(&self) -> &str {
+        &self.expression[..self.byte_index(self.cursor_position())]
+    }
+
+    /// Returns the character directly before the cursor, if any.
+    fn char_before_cursor(&self) -> Option<char> {
+        let pos = self.cursor_position();
+        if pos == 0 {
+            return None;
+        }
+        self.expression.chars().nth(pos - 1)
+    }
+
+    /// Returns the number the cursor is currently placed in (operators are boundaries).
+    fn number_at_cursor(&self) -> &str {
+        let split = self.byte_index(self.cursor_position());
+        let (before, after) = self.expression.split_at(split);
+        let start = before
+            .rfind(|c: char| "+-x÷^()".contains(c))
+            .map_or(0, |i| {
+                i + before[i..].chars().next().map_or(1, char::len_utf8)
+            });
+        let end = after
+            .find(|c: char| "+-x÷^()".contains(c))
+            .map_or(self.expression.len(), |i| split + i);
+        &self.expression[start..end]
+    }
+
+    /// Inserts text at the cursor and moves the cursor past it.
+    fn insert_at_cursor(&mut self, text: &str) {
+        let pos = self.cursor_position();
+        let byte = self.byte_index(pos);
+        self.expression.insert_str(byte, text);
+        self.cursor = Some(pos + text.chars().count());
+        self.display = self.display_string();
+        self.new_input = false;
+    }
+
+    /// Removes the character right after the cursor.
+    fn remove_at_cursor(&mut self) {
+        let pos = self.cursor_position();
+        let start = self.byte_index(pos);
+        let end = self.byte_index(pos + 1);
+        self.expression.replace_range(start..end, "");
+        if self.expression.is_empty() {
+            self.expression = "0".to_string();
+            self.cursor = None;
+        } else if pos >= self.expression.chars().count() {
+            self.cursor = None;
+        }
+        self.display = self.display_string();
+        self.new_input = false;
     }
 }
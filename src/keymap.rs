@@ -0,0 +1,565 @@
+use crate::calculator::Operation;
+use crate::scientific::ScientificKey;
+use crate::ui::UIMessage;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Non-character keys the keymap can bind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NamedKey {
+    Enter,
+    Backspace,
+    Delete,
+    Escape,
+    Tab,
+    Space,
+    ArrowLeft,
+    ArrowRight,
+    ArrowUp,
+    ArrowDown,
+    Home,
+    End,
+}
+
+impl NamedKey {
+    const ALL: [(NamedKey, &'static str); 12] = [
+        (NamedKey::Enter, "Enter"),
+        (NamedKey::Backspace, "Backspace"),
+        (NamedKey::Delete, "Delete"),
+        (NamedKey::Escape, "Escape"),
+        (NamedKey::Tab, "Tab"),
+        (NamedKey::Space, "Space"),
+        (NamedKey::ArrowLeft, "Left"),
+        (NamedKey::ArrowRight, "Right"),
+        (NamedKey::ArrowUp, "Up"),
+        (NamedKey::ArrowDown, "Down"),
+        (NamedKey::Home, "Home"),
+        (NamedKey::End, "End"),
+    ];
+
+    /// Name used in chord strings, e.g. `Left` for the left arrow.
+    pub fn name(self) -> &'static str {
+        Self::ALL
+            .iter()
+            .find(|(key, _)| *key == self)
+            .map(|(_, name)| *name)
+            .unwrap_or_default()
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|(_, key_name)| key_name.eq_ignore_ascii_case(name))
+            .map(|(key, _)| *key)
+    }
+}
+
+/// A key, independent of any GUI toolkit.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Key {
+    /// A key producing a character, as typed with the active keyboard layout
+    Character(String),
+    /// A named, non-character key
+    Named(NamedKey),
+}
+
+impl Key {
+    /// Shorthand for a character key.
+    pub fn character(c: char) -> Self {
+        Key::Character(c.to_string())
+    }
+}
+
+/// State of the modifier keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    /// The Windows/Super key, or Cmd on macOS
+    pub logo: bool,
+}
+
+impl Modifiers {
+    /// No modifiers held.
+    pub const NONE: Modifiers = Modifiers {
+        shift: false,
+        ctrl: false,
+        alt: false,
+        logo: false,
+    };
+
+    /// The platform's shortcut modifier: Cmd on macOS, Ctrl elsewhere.
+    pub fn command() -> Self {
+        if cfg!(target_os = "macos") {
+            Modifiers {
+                logo: true,
+                ..Self::NONE
+            }
+        } else {
+            Modifiers {
+                ctrl: true,
+                ..Self::NONE
+            }
+        }
+    }
+
+    /// Only Alt held.
+    pub fn alt() -> Self {
+        Modifiers {
+            alt: true,
+            ..Self::NONE
+        }
+    }
+}
+
+/// A key together with the modifiers held and whether it came from the numeric keypad.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub key: Key,
+    pub modifiers: Modifiers,
+    pub numpad: bool,
+}
+
+impl KeyChord {
+    /// A chord without modifiers on the main keyboard.
+    pub fn new(key: Key) -> Self {
+        Self {
+            key,
+            modifiers: Modifiers::NONE,
+            numpad: false,
+        }
+    }
+
+    /// Returns the chord with the given modifiers.
+    pub fn with_modifiers(mut self, modifiers: Modifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    /// Returns the chord located on the numeric keypad.
+    pub fn on_numpad(mut self) -> Self {
+        self.numpad = true;
+        self
+    }
+
+    /// Parses a chord such as `Ctrl+Shift+Z`, `Alt+-`, `Numpad+Enter` or `+`.
+    ///
+    /// Modifiers are `Ctrl`, `Alt`, `Shift`, `Super` and `Cmd` (the platform shortcut
+    /// modifier). Named keys are matched case-insensitively; any other key must be a
+    /// single character.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::keymap::{Key, KeyChord, Modifiers, NamedKey};
+    ///
+    /// assert_eq!(
+    ///     KeyChord::parse("Alt+-"),
+    ///     Ok(KeyChord::new(Key::character('-')).with_modifiers(Modifiers::alt()))
+    /// );
+    /// assert_eq!(
+    ///     KeyChord::parse("numpad+enter"),
+    ///     Ok(KeyChord::new(Key::Named(NamedKey::Enter)).on_numpad())
+    /// );
+    /// assert!(KeyChord::parse("Hyper+Q").is_err());
+    /// ```
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        // A trailing '+' is the plus key itself, e.g. "+" or "Shift++"
+        let (prefix, key_name) = match text.strip_suffix('+') {
+            Some(rest) if rest.is_empty() || rest.ends_with('+') => {
+                (rest.strip_suffix('+').unwrap_or(rest), "+")
+            }
+            _ => match text.rsplit_once('+') {
+                Some((prefix, key_name)) => (prefix, key_name),
+                None => ("", text),
+            },
+        };
+
+        let mut chord = KeyChord::new(Self::parse_key(key_name.trim(), text)?);
+        for part in prefix
+            .split('+')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+        {
+            let modifiers = &mut chord.modifiers;
+            match part.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => modifiers.ctrl = true,
+                "alt" | "option" => modifiers.alt = true,
+                "shift" => modifiers.shift = true,
+                "super" | "logo" | "win" | "meta" => modifiers.logo = true,
+                "cmd" | "command" => {
+                    let command = Modifiers::command();
+                    modifiers.ctrl |= command.ctrl;
+                    modifiers.logo |= command.logo;
+                }
+                "numpad" => chord.numpad = true,
+                _ => return Err(format!("unknown modifier '{}' in '{}'", part, text)),
+            }
+        }
+        Ok(chord)
+    }
+
+    fn parse_key(name: &str, chord: &str) -> Result<Key, String> {
+        if let Some(named) = NamedKey::from_name(name) {
+            return Ok(Key::Named(named));
+        }
+        let mut chars = name.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(Key::character(c)),
+            (None, _) => Err(format!("missing key in '{}'", chord)),
+            _ => Err(format!("unknown key '{}' in '{}'", name, chord)),
+        }
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let modifiers = [
+            (self.modifiers.ctrl, "Ctrl+"),
+            (self.modifiers.alt, "Alt+"),
+            (self.modifiers.shift, "Shift+"),
+            (self.modifiers.logo, "Super+"),
+            (self.numpad, "Numpad+"),
+        ];
+        for (held, name) in modifiers {
+            if held {
+                f.write_str(name)?;
+            }
+        }
+        match &self.key {
+            Key::Character(c) => f.write_str(c),
+            Key::Named(named) => f.write_str(named.name()),
+        }
+    }
+}
+
+/// Names of the actions that can be bound in the keymap file.
+fn actions() -> Vec<(&'static str, UIMessage)> {
+    let mut actions: Vec<(&'static str, UIMessage)> =
+        ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"]
+            .into_iter()
+            .zip(0..)
+            .map(|(name, digit)| (name, UIMessage::NumberPressed(digit)))
+            .collect();
+    actions.extend([
+        ("decimal", UIMessage::DecimalPressed),
+        ("add", UIMessage::OperationPressed(Operation::Add)),
+        ("subtract", UIMessage::OperationPressed(Operation::Subtract)),
+        ("multiply", UIMessage::OperationPressed(Operation::Multiply)),
+        ("divide", UIMessage::OperationPressed(Operation::Divide)),
+        (
+            "power",
+            UIMessage::ScientificKeyPressed(ScientificKey::Power),
+        ),
+        ("equals", UIMessage::EqualsPressed),
+        ("clear", UIMessage::ClearPressed),
+        ("backspace", UIMessage::BackspacePressed),
+        ("delete", UIMessage::DeletePressed),
+        ("percent", UIMessage::PercentagePressed),
+        ("sign-toggle", UIMessage::SignTogglePressed),
+        ("open-paren", UIMessage::OpenParenPressed),
+        ("close-paren", UIMessage::CloseParenPressed),
+        ("cursor-left", UIMessage::CursorLeft),
+        ("cursor-right", UIMessage::CursorRight),
+        ("cursor-home", UIMessage::CursorHome),
+        ("cursor-end", UIMessage::CursorEnd),
+        ("paste", UIMessage::PastePressed),
+        ("copy", UIMessage::CopyResult),
+        ("mode-toggle", UIMessage::ModeTogglePressed),
+        ("enter", UIMessage::EnterPressed),
+        ("swap", UIMessage::SwapPressed),
+        ("drop", UIMessage::DropPressed),
+        ("roll-down", UIMessage::RollDownPressed),
+        ("last-x", UIMessage::LastXPressed),
+        ("scientific-toggle", UIMessage::ScientificTogglePressed),
+        ("graph-toggle", UIMessage::GraphTogglePressed),
+        ("settings-toggle", UIMessage::SettingsTogglePressed),
+        ("second", UIMessage::SecondPressed),
+        ("angle-mode", UIMessage::AngleModePressed),
+        ("next-theme", UIMessage::NextTheme),
+    ]);
+    let scientific_keys = [
+        ("square", ScientificKey::Square),
+        ("root", ScientificKey::Root),
+        ("factorial", ScientificKey::Factorial),
+        ("reciprocal", ScientificKey::Reciprocal),
+        ("sin", ScientificKey::Sin),
+        ("cos", ScientificKey::Cos),
+        ("tan", ScientificKey::Tan),
+        ("ln", ScientificKey::Ln),
+        ("log", ScientificKey::Log),
+        ("pi", ScientificKey::Pi),
+        ("e", ScientificKey::E),
+    ];
+    actions.extend(
+        scientific_keys
+            .into_iter()
+            .map(|(name, key)| (name, UIMessage::ScientificKeyPressed(key))),
+    );
+    actions
+}
+
+/// Looks up a bindable action by its keymap file name, e.g. `equals` or `sin`.
+pub fn action_from_name(name: &str) -> Option<UIMessage> {
+    actions()
+        .into_iter()
+        .find(|(action, _)| *action == name)
+        .map(|(_, message)| message)
+}
+
+/// Returns the keymap file name of an action, if it can be bound.
+pub fn action_name(message: &UIMessage) -> Option<&'static str> {
+    actions()
+        .into_iter()
+        .find(|(_, action)| action == message)
+        .map(|(name, _)| name)
+}
+
+/// Maps key chords to UI messages.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    bindings: HashMap<KeyChord, UIMessage>,
+}
+
+impl Keymap {
+    /// A keymap without any bindings.
+    pub fn empty() -> Self {
+        Self {
+            bindings: HashMap::new(),
+        }
+    }
+
+    /// Binds a chord, replacing any previous binding of the same chord.
+    pub fn bind(&mut self, chord: KeyChord, message: UIMessage) {
+        self.bindings.insert(chord, message);
+    }
+
+    /// Removes the binding of a chord.
+    pub fn unbind(&mut self, chord: &KeyChord) {
+        self.bindings.remove(chord);
+    }
+
+    /// Returns the message bound to a chord.
+    ///
+    /// A numeric keypad chord without its own binding falls back to the same chord
+    /// on the main keyboard.
+    pub fn lookup(&self, chord: &KeyChord) -> Option<&UIMessage> {
+        self.bindings.get(chord).or_else(|| {
+            if !chord.numpad {
+                return None;
+            }
+            let main_keyboard = KeyChord {
+                numpad: false,
+                ..chord.clone()
+            };
+            self.bindings.get(&main_keyboard)
+        })
+    }
+
+    /// Resolves a key press to a message.
+    ///
+    /// `modified_key` is the key with the keyboard layout and Shift applied (e.g. `%` for
+    /// Shift+5 on a US layout), `key` the key without them. The modified character is tried
+    /// first with Shift ignored, since Shift is already reflected in the character; the
+    /// unmodified key is the fallback, e.g. for `Alt+-` where Alt changes the character.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::keymap::{Key, Keymap, Modifiers};
+    /// use rust_calculator::UIMessage;
+    ///
+    /// let keymap = Keymap::default();
+    /// let shift = Modifiers { shift: true, ..Modifiers::NONE };
+    /// assert_eq!(
+    ///     keymap.resolve(&Key::character('5'), &Key::character('%'), shift, false),
+    ///     Some(UIMessage::PercentagePressed)
+    /// );
+    /// ```
+    pub fn resolve(
+        &self,
+        key: &Key,
+        modified_key: &Key,
+        modifiers: Modifiers,
+        numpad: bool,
+    ) -> Option<UIMessage> {
+        if let Key::Character(_) = modified_key {
+            let chord = KeyChord {
+                key: modified_key.clone(),
+                modifiers: Modifiers {
+                    shift: false,
+                    ..modifiers
+                },
+                numpad,
+            };
+            if let Some(message) = self.lookup(&chord) {
+                return Some(message.clone());
+            }
+        }
+        let chord = KeyChord {
+            key: key.clone(),
+            modifiers,
+            numpad,
+        };
+        self.lookup(&chord).cloned()
+    }
+
+    /// Returns all bindings, sorted by chord text for display.
+    pub fn bindings(&self) -> Vec<(KeyChord, UIMessage)> {
+        let mut bindings: Vec<(KeyChord, UIMessage)> = self
+            .bindings
+            .iter()
+            .map(|(chord, message)| (chord.clone(), message.clone()))
+            .collect();
+        bindings.sort_by_key(|(chord, _)| chord.to_string());
+        bindings
+    }
+
+    /// Applies overrides from keymap file text on top of this keymap.
+    ///
+    /// The file has a `[bindings]` table mapping chords to action names; the action
+    /// `none` removes a binding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::keymap::{Key, KeyChord, Keymap, NamedKey};
+    /// use rust_calculator::UIMessage;
+    ///
+    /// let mut keymap = Keymap::default();
+    /// keymap
+    ///     .apply_overrides("[bindings]\n\"Ctrl+Backspace\" = \"clear\"\n\"Escape\" = \"none\"")
+    ///     .unwrap();
+    /// let escape = KeyChord::new(Key::Named(NamedKey::Escape));
+    /// assert_eq!(keymap.lookup(&escape), None);
+    /// ```
+    pub fn apply_overrides(&mut self, text: &str) -> Result<(), String> {
+        let file: KeymapFile = toml::from_str(text).map_err(|error| error.message().to_string())?;
+
+        // Validate everything first so a bad entry leaves the keymap untouched
+        let mut overrides = Vec::new();
+        for (chord, action) in file.bindings {
+            let chord = KeyChord::parse(&chord)?;
+            let message = match action.as_str() {
+                "none" => None,
+                name => Some(
+                    action_from_name(name)
+                        .ok_or_else(|| format!("unknown action '{}' for '{}'", name, chord))?,
+                ),
+            };
+            overrides.push((chord, message));
+        }
+
+        for (chord, message) in overrides {
+            match message {
+                Some(message) => self.bind(chord, message),
+                None => self.unbind(&chord),
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads the default keymap with the overrides from a keymap file applied.
+    /// A missing file means no overrides.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let mut keymap = Self::default();
+        match std::fs::read_to_string(path) {
+            Ok(text) => keymap.apply_overrides(&text)?,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => return Err(error.to_string()),
+        }
+        Ok(keymap)
+    }
+}
+
+impl Default for Keymap {
+    /// The standard bindings: digits, operators, `=` and Enter, Backspace, Delete, Escape,
+    /// cursor keys, parentheses, numeric keypad keys and the clipboard and theme shortcuts.
+    fn default() -> Self {
+        let mut keymap = Self::empty();
+        let characters = [
+            ('.', UIMessage::DecimalPressed),
+            ('+', UIMessage::OperationPressed(Operation::Add)),
+            ('-', UIMessage::OperationPressed(Operation::Subtract)),
+            ('*', UIMessage::OperationPressed(Operation::Multiply)),
+            ('x', UIMessage::OperationPressed(Operation::Multiply)),
+            ('X', UIMessage::OperationPressed(Operation::Multiply)),
+            ('×', UIMessage::OperationPressed(Operation::Multiply)),
+            ('/', UIMessage::OperationPressed(Operation::Divide)),
+            ('÷', UIMessage::OperationPressed(Operation::Divide)),
+            ('^', UIMessage::ScientificKeyPressed(ScientificKey::Power)),
+            (
+                '!',
+                UIMessage::ScientificKeyPressed(ScientificKey::Factorial),
+            ),
+            ('%', UIMessage::PercentagePressed),
+            ('(', UIMessage::OpenParenPressed),
+            (')', UIMessage::CloseParenPressed),
+            ('=', UIMessage::EqualsPressed),
+        ];
+        for digit in 0..=9 {
+            let c = char::from(b'0' + digit);
+            keymap.bind(
+                KeyChord::new(Key::character(c)),
+                UIMessage::NumberPressed(digit),
+            );
+        }
+        for (c, message) in characters {
+            keymap.bind(KeyChord::new(Key::character(c)), message);
+        }
+
+        let named = [
+            (NamedKey::Enter, UIMessage::EqualsPressed),
+            (NamedKey::Backspace, UIMessage::BackspacePressed),
+            (NamedKey::Delete, UIMessage::DeletePressed),
+            (NamedKey::Escape, UIMessage::ClearPressed),
+            (NamedKey::ArrowLeft, UIMessage::CursorLeft),
+            (NamedKey::ArrowRight, UIMessage::CursorRight),
+            (NamedKey::Home, UIMessage::CursorHome),
+            (NamedKey::End, UIMessage::CursorEnd),
+        ];
+        for (key, message) in named {
+            keymap.bind(KeyChord::new(Key::Named(key)), message);
+        }
+
+        // Numeric keypad: digits and operators fall back to the main keyboard bindings;
+        // the keypad decimal key types ',' on layouts with a decimal comma
+        keymap.bind(
+            KeyChord::new(Key::character(',')).on_numpad(),
+            UIMessage::DecimalPressed,
+        );
+
+        keymap.bind(
+            KeyChord::new(Key::character('-')).with_modifiers(Modifiers::alt()),
+            UIMessage::SignTogglePressed,
+        );
+        let shortcuts = [
+            ('v', UIMessage::PastePressed),
+            ('c', UIMessage::CopyResult),
+            ('t', UIMessage::NextTheme),
+        ];
+        for (c, message) in shortcuts {
+            keymap.bind(
+                KeyChord::new(Key::character(c)).with_modifiers(Modifiers::command()),
+                message,
+            );
+        }
+        keymap
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeymapFile {
+    #[serde(default)]
+    bindings: BTreeMap<String, String>,
+}
+
+/// Location of the keymap file in the user config directory.
+pub fn keymap_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("rust-calculator").join("keymap.toml"))
+}
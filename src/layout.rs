@@ -0,0 +1,188 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// How the keypads are arranged in the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arrangement {
+    /// Basic keypad only
+    Basic,
+    /// Scientific panel stacked between the display and the basic keypad
+    ScientificPortrait,
+    /// Scientific panel beside the basic keypad, under a full-width display
+    ScientificLandscape,
+}
+
+/// Window-size dependent sizes for the GUI.
+///
+/// All sizes are derived from the original fixed design (70×70 buttons, 12px spacing,
+/// 348×690 window) multiplied by a scale factor that fits the design into the window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layout {
+    /// Factor applied to every size of the base design
+    pub scale: f32,
+    /// Keypad arrangement chosen for the window shape
+    pub arrangement: Arrangement,
+}
+
+impl Layout {
+    /// Button size of the unscaled design.
+    pub const BASE_BUTTON_SIZE: f32 = 70.0;
+    /// Spacing between buttons of the unscaled design.
+    pub const BASE_SPACING: f32 = 12.0;
+    /// Window size of the unscaled basic design.
+    pub const BASE_WINDOW_SIZE: (f32, f32) = (348.0, 690.0);
+    /// Smallest scale factor; the window can't shrink below the design at this scale.
+    pub const MIN_SCALE: f32 = 0.6;
+    /// Largest scale factor; beyond it the keypad just gets more margin.
+    pub const MAX_SCALE: f32 = 2.5;
+
+    // Extra room taken by the scientific panel in each arrangement: four columns plus a
+    // section gap beside the keypad, or four rows above it
+    const PANEL_WIDTH: f32 = 4.0 * 70.0 + 3.0 * 12.0 + 32.0;
+    const PANEL_HEIGHT: f32 = 4.0 * (70.0 + 12.0);
+
+    /// Picks the arrangement and scale that best fit a window of the given size.
+    ///
+    /// With the scientific panel shown, a window wider than it is tall puts the panel beside
+    /// the keypad; otherwise the panel is stacked above it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::layout::{Arrangement, Layout};
+    ///
+    /// let layout = Layout::for_window(696.0, 1380.0, false);
+    /// assert_eq!(layout.arrangement, Arrangement::Basic);
+    /// assert_eq!(layout.scale, 2.0);
+    /// assert_eq!(layout.button_size(), 140.0);
+    /// ```
+    pub fn for_window(width: f32, height: f32, scientific_panel: bool) -> Self {
+        let arrangement = match scientific_panel {
+            false => Arrangement::Basic,
+            true if width > height => Arrangement::ScientificLandscape,
+            true => Arrangement::ScientificPortrait,
+        };
+        let (base_width, base_height) = Self::base_window_size(arrangement);
+        let scale = (width / base_width)
+            .min(height / base_height)
+            .clamp(Self::MIN_SCALE, Self::MAX_SCALE);
+
+        Self { scale, arrangement }
+    }
+
+    /// Returns the unscaled window size of an arrangement.
+    pub fn base_window_size(arrangement: Arrangement) -> (f32, f32) {
+        let (width, height) = Self::BASE_WINDOW_SIZE;
+        match arrangement {
+            Arrangement::Basic => (width, height),
+            Arrangement::ScientificPortrait => (width, height + Self::PANEL_HEIGHT),
+            Arrangement::ScientificLandscape => (width + Self::PANEL_WIDTH, height),
+        }
+    }
+
+    /// Returns the smallest window size the layout supports.
+    pub fn min_window_size() -> (f32, f32) {
+        let (width, height) = Self::BASE_WINDOW_SIZE;
+        (width * Self::MIN_SCALE, height * Self::MIN_SCALE)
+    }
+
+    /// Returns the window size that fits an arrangement at the current scale,
+    /// used to grow or shrink the window when the scientific panel is toggled.
+    pub fn window_size_for(&self, arrangement: Arrangement) -> (f32, f32) {
+        let (width, height) = Self::base_window_size(arrangement);
+        (width * self.scale, height * self.scale)
+    }
+
+    /// Side length of a keypad button.
+    pub fn button_size(&self) -> f32 {
+        Self::BASE_BUTTON_SIZE * self.scale
+    }
+
+    /// Spacing between keypad buttons and rows.
+    pub fn spacing(&self) -> f32 {
+        Self::BASE_SPACING * self.scale
+    }
+
+    /// Spacing between the display and the keypad, and between the two keypads.
+    pub fn section_spacing(&self) -> f32 {
+        32.0 * self.scale
+    }
+
+    /// Outer padding of the window content.
+    pub fn padding(&self) -> f32 {
+        16.0 * self.scale
+    }
+
+    /// Width of a four-column keypad.
+    pub fn keypad_width(&self) -> f32 {
+        4.0 * self.button_size() + 3.0 * self.spacing()
+    }
+
+    /// Width of the display, which spans both keypads in the landscape arrangement.
+    pub fn content_width(&self) -> f32 {
+        match self.arrangement {
+            Arrangement::ScientificLandscape => 2.0 * self.keypad_width() + self.section_spacing(),
+            _ => self.keypad_width(),
+        }
+    }
+
+    /// Scales a font size of the base design, rounded to whole pixels.
+    pub fn font(&self, base_size: f32) -> f32 {
+        (base_size * self.scale).round()
+    }
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            arrangement: Arrangement::Basic,
+        }
+    }
+}
+
+/// Parses a window size saved as `WIDTHxHEIGHT`.
+///
+/// Returns `None` for malformed text or sizes below the supported minimum.
+///
+/// # Examples
+///
+/// ```
+/// use rust_calculator::layout::parse_window_size;
+///
+/// assert_eq!(parse_window_size("400x800\n"), Some((400.0, 800.0)));
+/// assert_eq!(parse_window_size("10x10"), None);
+/// ```
+pub fn parse_window_size(text: &str) -> Option<(f32, f32)> {
+    let (width, height) = text.trim().split_once('x')?;
+    let width: f32 = width.trim().parse().ok()?;
+    let height: f32 = height.trim().parse().ok()?;
+    let (min_width, min_height) = Layout::min_window_size();
+    if !width.is_finite() || !height.is_finite() || width < min_width || height < min_height {
+        return None;
+    }
+    Some((width, height))
+}
+
+/// Formats a window size for saving.
+pub fn format_window_size((width, height): (f32, f32)) -> String {
+    format!("{}x{}\n", width.round(), height.round())
+}
+
+/// Loads a saved window size, or `None` if the file is missing or invalid.
+pub fn load_window_size(path: &Path) -> Option<(f32, f32)> {
+    parse_window_size(&std::fs::read_to_string(path).ok()?)
+}
+
+/// Saves a window size, creating the parent directory if needed.
+pub fn save_window_size(path: &Path, size: (f32, f32)) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, format_window_size(size))
+}
+
+/// Location of the saved window size in the user config directory.
+pub fn window_size_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("rust-calculator").join("window_size"))
+}
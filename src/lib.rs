@@ -2,12 +2,78 @@
 //!
 //! A simple calculator library with expression evaluation and operator precedence.
 //! Also includes GUI state management that can be unit tested.
+//!
+//! Without the default `std` feature the crate is `#![no_std]` and needs only `alloc`:
+//! the [`expression`] evaluator, [`variables`] and the [`locale`] message catalogs remain, with
+//! floating-point math from `libm`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
+#[cfg(feature = "std")]
+pub mod batch;
+#[cfg(feature = "std")]
 pub mod calculator;
+#[cfg(feature = "std")]
+pub mod cli;
+#[cfg(feature = "std")]
 pub mod display;
+pub mod expression;
+#[cfg(feature = "std")]
+pub mod focus;
+#[cfg(feature = "std")]
+pub mod graph;
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(feature = "std")]
 pub mod input;
+#[cfg(feature = "std")]
+pub mod keymap;
+#[cfg(feature = "std")]
+pub mod layout;
+pub mod locale;
+mod math;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "std")]
+pub mod repl;
+#[cfg(feature = "std")]
+pub mod rpn;
+#[cfg(feature = "std")]
+pub mod scientific;
+#[cfg(feature = "serde")]
+pub mod serialization;
+#[cfg(feature = "std")]
+pub mod server;
+#[cfg(feature = "std")]
+pub mod settings;
+#[cfg(feature = "std")]
+pub mod theme;
+#[cfg(feature = "std")]
 pub mod ui;
+pub mod variables;
 
 // Re-export main types for convenience
-pub use calculator::{Calculator, CalculatorError, Operation};
-pub use ui::{CalculatorUIState, MessageResult, UIMessage};
+#[cfg(feature = "std")]
+pub use calculator::{Calculator, Operation};
+pub use expression::{
+    AngleMode, CalculatorError, Constant, ExpressionError, Function, SyntaxError,
+};
+#[cfg(feature = "std")]
+pub use graph::{Graph, PlotFunction, Viewport};
+#[cfg(feature = "std")]
+pub use keymap::{KeyChord, Keymap};
+#[cfg(feature = "std")]
+pub use layout::{Arrangement, Layout};
+pub use locale::Locale;
+#[cfg(feature = "std")]
+pub use rpn::RpnCalculator;
+#[cfg(feature = "std")]
+pub use scientific::{ScientificAction, ScientificKey};
+#[cfg(feature = "std")]
+pub use settings::Settings;
+#[cfg(feature = "std")]
+pub use theme::{Palette, ThemeChoice};
+#[cfg(feature = "std")]
+pub use ui::{CalculatorUIState, DisplayLines, InputMode, MessageResult, UIMessage};
@@ -0,0 +1,415 @@
+use crate::expression::{CalculatorError, SyntaxError};
+#[cfg(feature = "std")]
+use crate::settings::{DecimalSeparator, Settings, SettingsWarning};
+use alloc::string::{String, ToString};
+use core::fmt;
+
+/// Language of the GUI labels and error messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Locale {
+    #[default]
+    English,
+    German,
+}
+
+impl Locale {
+    /// All locales, in menu order.
+    pub const ALL: [Locale; 2] = [Locale::English, Locale::German];
+
+    /// Language code used in the settings file.
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::German => "de",
+        }
+    }
+
+    /// Finds the locale for a language tag such as `de`, `de-DE` or `de_AT.UTF-8`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::Locale;
+    ///
+    /// assert_eq!(Locale::from_code("de_DE.UTF-8"), Some(Locale::German));
+    /// assert_eq!(Locale::from_code("EN-gb"), Some(Locale::English));
+    /// assert_eq!(Locale::from_code("fr"), None);
+    /// ```
+    pub fn from_code(code: &str) -> Option<Locale> {
+        let language = code.split(['-', '_', '.']).next()?.to_ascii_lowercase();
+        Locale::ALL
+            .into_iter()
+            .find(|locale| locale.code() == language)
+    }
+
+    /// The decimal separator customary for the language.
+    #[cfg(feature = "std")]
+    pub fn decimal_separator(self) -> DecimalSeparator {
+        match self {
+            Locale::English => DecimalSeparator::Point,
+            Locale::German => DecimalSeparator::Comma,
+        }
+    }
+
+    /// Looks up a text in the locale's catalog; texts the catalog lacks are English.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::Locale;
+    /// use rust_calculator::locale::Text;
+    ///
+    /// assert_eq!(Locale::German.text(Text::Precision), "Genauigkeit");
+    /// // Not translated: falls back to English
+    /// assert_eq!(Locale::German.text(Text::Enter), "ENTER");
+    /// ```
+    pub fn text(self, text: Text) -> &'static str {
+        match self {
+            Locale::English => None,
+            Locale::German => german(text),
+        }
+        .unwrap_or_else(|| english(text))
+    }
+
+    /// Looks up a text and fills its `{}` placeholders with `args`, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::Locale;
+    /// use rust_calculator::locale::Text;
+    ///
+    /// assert_eq!(
+    ///     Locale::German.format(Text::PasteRejected, &["Eingabe zu lang"]),
+    ///     "Einfügen abgelehnt: Eingabe zu lang"
+    /// );
+    /// ```
+    pub fn format(self, text: Text, args: &[&str]) -> String {
+        let mut parts = self.text(text).split("{}");
+        let mut result = parts.next().unwrap_or_default().to_string();
+        for (index, part) in parts.enumerate() {
+            result.push_str(args.get(index).copied().unwrap_or_default());
+            result.push_str(part);
+        }
+        result
+    }
+
+    /// The message for an error, in this language.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::{CalculatorError, Locale};
+    ///
+    /// let error = CalculatorError::DomainError("√(-4)".to_string());
+    /// assert_eq!(Locale::English.error_message(&error), "Domain error: √(-4)");
+    /// assert_eq!(
+    ///     Locale::German.error_message(&error),
+    ///     "Außerhalb des Definitionsbereichs: √(-4)"
+    /// );
+    /// ```
+    pub fn error_message(self, error: &CalculatorError) -> String {
+        match error {
+            CalculatorError::DivisionByZero => self.format(Text::DivisionByZero, &[]),
+            CalculatorError::InvalidNumber(s) => self.format(Text::InvalidNumber, &[s]),
+            CalculatorError::InvalidExpression(error) => self.syntax_message(error),
+            CalculatorError::InputTooLong => self.format(Text::InputTooLong, &[]),
+            CalculatorError::InvalidCharacters(s) => self.format(Text::InvalidCharacters, &[s]),
+            CalculatorError::NumberOutOfRange(s) => self.format(Text::NumberOutOfRange, &[s]),
+            CalculatorError::DomainError(s) => self.format(Text::DomainError, &[s]),
+            CalculatorError::TooFewArguments => self.format(Text::TooFewArguments, &[]),
+            CalculatorError::NoLastX => self.format(Text::NoLastX, &[]),
+            CalculatorError::InvalidVariableName(s) => self.format(Text::InvalidVariableName, &[s]),
+        }
+    }
+
+    /// The message for something ignored in the settings file, in this language.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::Locale;
+    /// use rust_calculator::settings::SettingsWarning;
+    ///
+    /// let warning = SettingsWarning::UnknownSetting("volume".to_string());
+    /// assert_eq!(Locale::English.settings_warning(&warning), "unknown setting `volume` ignored");
+    /// assert_eq!(
+    ///     Locale::German.settings_warning(&warning),
+    ///     "unbekannte Einstellung `volume` ignoriert"
+    /// );
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn settings_warning(self, warning: &SettingsWarning) -> String {
+        match warning {
+            SettingsWarning::Unreadable(s) => self.format(Text::SettingsUnreadable, &[s]),
+            SettingsWarning::InvalidToml(s) => self.format(Text::SettingsInvalidToml, &[s]),
+            SettingsWarning::UnknownSetting(s) => self.format(Text::UnknownSetting, &[s]),
+            SettingsWarning::InvalidPrecision => self.format(
+                Text::InvalidPrecision,
+                &[
+                    &Settings::MAX_PRECISION.to_string(),
+                    &Settings::DEFAULT_PRECISION.to_string(),
+                ],
+            ),
+            SettingsWarning::InvalidDisplayFormat => self.format(Text::InvalidDisplayFormat, &[]),
+            SettingsWarning::InvalidAngleMode => self.format(Text::InvalidAngleMode, &[]),
+            SettingsWarning::InvalidTheme => self.format(Text::InvalidTheme, &[]),
+            SettingsWarning::InvalidKeymap => self.format(Text::InvalidKeymap, &[]),
+            SettingsWarning::InvalidDecimalSeparator => {
+                self.format(Text::InvalidDecimalSeparator, &[])
+            }
+            SettingsWarning::InvalidLocale => self.format(Text::InvalidLocale, &[]),
+        }
+    }
+
+    fn syntax_message(self, error: &SyntaxError) -> String {
+        match error {
+            SyntaxError::Empty => self.format(Text::EmptyExpression, &[]),
+            SyntaxError::MultipleDecimalPoints(s) => self.format(Text::MultipleDecimalPoints, &[s]),
+            SyntaxError::MultipleExponents(s) => self.format(Text::MultipleExponents, &[s]),
+            SyntaxError::Unexpected(s) => self.format(Text::Unexpected, &[s]),
+            SyntaxError::ConsecutiveOperators => self.format(Text::ConsecutiveOperators, &[]),
+            SyntaxError::MissingOperand => self.format(Text::MissingOperand, &[]),
+            SyntaxError::TooManyOperands => self.format(Text::TooManyOperands, &[]),
+            SyntaxError::ExpectedParenthesis(s) => self.format(Text::ExpectedParenthesis, &[s]),
+            SyntaxError::UnknownFunction(s) => self.format(Text::UnknownFunction, &[s]),
+            SyntaxError::MismatchedParentheses => self.format(Text::MismatchedParentheses, &[]),
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Each language is listed under its own name
+        match self {
+            Locale::English => write!(f, "English"),
+            Locale::German => write!(f, "Deutsch"),
+        }
+    }
+}
+
+/// Texts of the message catalogs. `{}` in a text is filled in by [`Locale::format`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Text {
+    // Mode bar and keypad
+    Basic,
+    Scientific,
+    Calculator,
+    Graph,
+    SettingsButton,
+    Done,
+    ClearAll,
+    Enter,
+    Drop,
+    LastX,
+    // Graph view
+    GraphFunctions,
+    ResetView,
+    // Settings view
+    Precision,
+    DisplayFormat,
+    AngleMode,
+    Theme,
+    DecimalSeparator,
+    Language,
+    KeymapFile,
+    SavedTo,
+    FormatAuto,
+    FormatFixed,
+    FormatScientific,
+    Degrees,
+    Radians,
+    SeparatorPoint,
+    SeparatorComma,
+    // Notices
+    PasteRejected,
+    ThemeNotFound,
+    ThemeFileIgnored,
+    KeymapFileIgnored,
+    SettingsIgnored,
+    SettingsNotSaved,
+    NoConfigDirectory,
+    // Settings file warnings
+    SettingsUnreadable,
+    SettingsInvalidToml,
+    UnknownSetting,
+    InvalidPrecision,
+    InvalidDisplayFormat,
+    InvalidAngleMode,
+    InvalidTheme,
+    InvalidKeymap,
+    InvalidDecimalSeparator,
+    InvalidLocale,
+    // Errors
+    DivisionByZero,
+    InvalidNumber,
+    InputTooLong,
+    InvalidCharacters,
+    NumberOutOfRange,
+    DomainError,
+    TooFewArguments,
+    NoLastX,
+    InvalidVariableName,
+    EmptyExpression,
+    MultipleDecimalPoints,
+    MultipleExponents,
+    Unexpected,
+    ConsecutiveOperators,
+    MissingOperand,
+    TooManyOperands,
+    ExpectedParenthesis,
+    UnknownFunction,
+    MismatchedParentheses,
+}
+
+/// The English catalog, which has every text.
+fn english(text: Text) -> &'static str {
+    match text {
+        Text::Basic => "BASIC",
+        Text::Scientific => "SCI",
+        Text::Calculator => "CALC",
+        Text::Graph => "GRAPH",
+        Text::SettingsButton => "SET",
+        Text::Done => "DONE",
+        Text::ClearAll => "AC",
+        Text::Enter => "ENTER",
+        Text::Drop => "DROP",
+        Text::LastX => "LASTx",
+        Text::GraphFunctions => "Functions of x, e.g. sin(x); x^2/4",
+        Text::ResetView => "Reset view",
+        Text::Precision => "Precision",
+        Text::DisplayFormat => "Display format",
+        Text::AngleMode => "Angle mode",
+        Text::Theme => "Theme",
+        Text::DecimalSeparator => "Decimal separator",
+        Text::Language => "Language",
+        Text::KeymapFile => "Keymap file (Enter to apply)",
+        Text::SavedTo => "Saved to {}",
+        Text::FormatAuto => "Auto",
+        Text::FormatFixed => "Fixed",
+        Text::FormatScientific => "Scientific",
+        Text::Degrees => "Degrees",
+        Text::Radians => "Radians",
+        Text::SeparatorPoint => "Point (1.5)",
+        Text::SeparatorComma => "Comma (1,5)",
+        Text::PasteRejected => "Paste rejected: {}",
+        Text::ThemeNotFound => "Theme \"{}\" not found, using {}",
+        Text::ThemeFileIgnored => "Theme file ignored: {}",
+        Text::KeymapFileIgnored => "Keymap file ignored: {}",
+        Text::SettingsIgnored => "Settings: {}",
+        Text::SettingsNotSaved => "Settings not saved: {}",
+        Text::NoConfigDirectory => "No config directory, settings are not saved",
+        Text::SettingsUnreadable => "could not be read ({}), using the defaults",
+        Text::SettingsInvalidToml => "not valid TOML ({}), using the defaults",
+        Text::UnknownSetting => "unknown setting `{}` ignored",
+        Text::InvalidPrecision => "`precision` must be a whole number from 0 to {}, using {}",
+        Text::InvalidDisplayFormat => {
+            "`display_format` must be \"auto\", \"fixed\" or \"scientific\", using \"auto\""
+        }
+        Text::InvalidAngleMode => {
+            "`angle_mode` must be \"degrees\" or \"radians\", using \"degrees\""
+        }
+        Text::InvalidTheme => "`theme` must be a theme name, using \"dark\"",
+        Text::InvalidKeymap => "`keymap` must be a file path, using the default keymap file",
+        Text::InvalidDecimalSeparator => {
+            "`decimal_separator` must be \"point\" or \"comma\", using \"point\""
+        }
+        Text::InvalidLocale => "`locale` must be \"en\" or \"de\", using \"en\"",
+        Text::DivisionByZero => "Division by zero",
+        Text::InvalidNumber => "Invalid number: {}",
+        Text::InputTooLong => "Input too long",
+        Text::InvalidCharacters => "Invalid characters: {}",
+        Text::NumberOutOfRange => "Number out of range: {}",
+        Text::DomainError => "Domain error: {}",
+        Text::TooFewArguments => "Too few arguments",
+        Text::NoLastX => "No last X",
+        Text::InvalidVariableName => "Invalid variable name: {}",
+        Text::EmptyExpression => "Empty expression",
+        Text::MultipleDecimalPoints => "Invalid number format: multiple decimal points in '{}'",
+        Text::MultipleExponents => "Invalid number format: multiple 'e' in '{}'",
+        Text::Unexpected => "Unexpected '{}'",
+        Text::ConsecutiveOperators => "Consecutive operators",
+        Text::MissingOperand => "Invalid expression: missing operand",
+        Text::TooManyOperands => "Invalid expression: too many operands",
+        Text::ExpectedParenthesis => "Expected '(' after {}",
+        Text::UnknownFunction => "Unknown function: {}",
+        Text::MismatchedParentheses => "Mismatched parentheses",
+    }
+}
+
+/// The German catalog. Key labels that are the same on German calculators are left out.
+fn german(text: Text) -> Option<&'static str> {
+    Some(match text {
+        Text::Basic => "BASIS",
+        Text::Scientific => "WISS",
+        Text::Calculator => "RECHNER",
+        Text::SettingsButton => "EINST",
+        Text::Done => "FERTIG",
+        Text::GraphFunctions => "Funktionen von x, z. B. sin(x); x^2/4",
+        Text::ResetView => "Ansicht zurücksetzen",
+        Text::Precision => "Genauigkeit",
+        Text::DisplayFormat => "Zahlenformat",
+        Text::AngleMode => "Winkelmaß",
+        Text::Theme => "Farbschema",
+        Text::DecimalSeparator => "Dezimaltrennzeichen",
+        Text::Language => "Sprache",
+        Text::KeymapFile => "Tastenbelegung (Enter zum Übernehmen)",
+        Text::SavedTo => "Gespeichert in {}",
+        Text::FormatAuto => "Automatisch",
+        Text::FormatFixed => "Fest",
+        Text::FormatScientific => "Wissenschaftlich",
+        Text::Degrees => "Grad",
+        Text::Radians => "Bogenmaß",
+        Text::SeparatorPoint => "Punkt (1.5)",
+        Text::SeparatorComma => "Komma (1,5)",
+        Text::PasteRejected => "Einfügen abgelehnt: {}",
+        Text::ThemeNotFound => "Farbschema \"{}\" nicht gefunden, verwende {}",
+        Text::ThemeFileIgnored => "Farbschema-Datei ignoriert: {}",
+        Text::KeymapFileIgnored => "Tastenbelegung ignoriert: {}",
+        Text::SettingsIgnored => "Einstellungen: {}",
+        Text::SettingsNotSaved => "Einstellungen nicht gespeichert: {}",
+        Text::NoConfigDirectory => {
+            "Kein Konfigurationsverzeichnis, Einstellungen werden nicht gespeichert"
+        }
+        Text::SettingsUnreadable => "nicht lesbar ({}), verwende die Standardwerte",
+        Text::SettingsInvalidToml => "kein gültiges TOML ({}), verwende die Standardwerte",
+        Text::UnknownSetting => "unbekannte Einstellung `{}` ignoriert",
+        Text::InvalidPrecision => "`precision` muss eine ganze Zahl von 0 bis {} sein, verwende {}",
+        Text::InvalidDisplayFormat => {
+            "`display_format` muss \"auto\", \"fixed\" oder \"scientific\" sein, verwende \"auto\""
+        }
+        Text::InvalidAngleMode => {
+            "`angle_mode` muss \"degrees\" oder \"radians\" sein, verwende \"degrees\""
+        }
+        Text::InvalidTheme => "`theme` muss ein Farbschema sein, verwende \"dark\"",
+        Text::InvalidKeymap => {
+            "`keymap` muss ein Dateipfad sein, verwende die Standard-Tastenbelegung"
+        }
+        Text::InvalidDecimalSeparator => {
+            "`decimal_separator` muss \"point\" oder \"comma\" sein, verwende \"point\""
+        }
+        Text::InvalidLocale => "`locale` muss \"en\" oder \"de\" sein, verwende \"en\"",
+        Text::DivisionByZero => "Division durch null",
+        Text::InvalidNumber => "Ungültige Zahl: {}",
+        Text::InputTooLong => "Eingabe zu lang",
+        Text::InvalidCharacters => "Ungültige Zeichen: {}",
+        Text::NumberOutOfRange => "Zahl außerhalb des Bereichs: {}",
+        Text::DomainError => "Außerhalb des Definitionsbereichs: {}",
+        Text::TooFewArguments => "Zu wenige Argumente",
+        Text::NoLastX => "Kein letztes X",
+        Text::InvalidVariableName => "Ungültiger Variablenname: {}",
+        Text::EmptyExpression => "Leerer Ausdruck",
+        Text::MultipleDecimalPoints => "Ungültiges Zahlenformat: mehrere Dezimalpunkte in '{}'",
+        Text::MultipleExponents => "Ungültiges Zahlenformat: mehrere 'e' in '{}'",
+        Text::Unexpected => "Unerwartetes '{}'",
+        Text::ConsecutiveOperators => "Zwei Operatoren hintereinander",
+        Text::MissingOperand => "Ungültiger Ausdruck: Operand fehlt",
+        Text::TooManyOperands => "Ungültiger Ausdruck: zu viele Operanden",
+        Text::ExpectedParenthesis => "'(' nach {} erwartet",
+        Text::UnknownFunction => "Unbekannte Funktion: {}",
+        Text::MismatchedParentheses => "Klammern passen nicht zusammen",
+        Text::Graph | Text::ClearAll | Text::Enter | Text::Drop | Text::LastX => return None,
+    })
+}
@@ -0,0 +1,47 @@
+/// Defines functions of one `f64` that call the `std` method of the same name, or the
+/// given `libm` function when the crate is built without `std`.
+macro_rules! unary {
+    ($($name:ident => $libm:ident),* $(,)?) => {
+        $(
+            #[cfg(feature = "std")]
+            pub(crate) fn $name(x: f64) -> f64 {
+                x.$name()
+            }
+
+            #[cfg(not(feature = "std"))]
+            pub(crate) fn $name(x: f64) -> f64 {
+                libm::$libm(x)
+            }
+        )*
+    };
+}
+
+unary! {
+    sin => sin,
+    cos => cos,
+    tan => tan,
+    asin => asin,
+    acos => acos,
+    atan => atan,
+    ln => log,
+    log10 => log10,
+    sqrt => sqrt,
+    cbrt => cbrt,
+    abs => fabs,
+    trunc => trunc,
+}
+
+#[cfg(feature = "std")]
+pub(crate) fn powf(base: f64, exponent: f64) -> f64 {
+    base.powf(exponent)
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) fn powf(base: f64, exponent: f64) -> f64 {
+    libm::pow(base, exponent)
+}
+
+/// Fractional part of `x`, with the sign of `x`.
+pub(crate) fn fract(x: f64) -> f64 {
+    x - trunc(x)
+}
@@ -0,0 +1,133 @@
+use crate::cli::parse_angle_mode;
+use crate::variables::{self, Variables};
+use crate::{AngleMode, Calculator, ExpressionError};
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+create_exception!(
+    rust_calculator,
+    CalculatorError,
+    PyValueError,
+    "An expression that can't be evaluated. `kind` names the error, e.g. \
+     `division_by_zero`, and `start`..`end` are the offending characters of the expression."
+);
+
+/// Raises a `CalculatorError` with the message, kind and position of `error`.
+fn raise(py: Python<'_>, error: &ExpressionError) -> PyErr {
+    let exception = CalculatorError::new_err(error.to_string());
+    let value = exception.value(py);
+    let described = value
+        .setattr("kind", error.kind.name())
+        .and_then(|()| value.setattr("start", error.span.start))
+        .and_then(|()| value.setattr("end", error.span.end));
+    match described {
+        Ok(()) => exception,
+        Err(error) => error,
+    }
+}
+
+fn angle_mode(angle: &str) -> PyResult<AngleMode> {
+    parse_angle_mode(angle).map_err(PyValueError::new_err)
+}
+
+/// evaluate(expression, angle="degrees")
+/// --
+///
+/// Evaluates an expression the way the calculator window does.
+#[pyfunction]
+#[pyo3(signature = (expression, angle = "degrees"))]
+fn evaluate(py: Python<'_>, expression: &str, angle: &str) -> PyResult<f64> {
+    let calculator = Calculator {
+        angle_mode: angle_mode(angle)?,
+        ..Calculator::new()
+    };
+    calculator
+        .evaluate_spanned(expression)
+        .map_err(|error| raise(py, &error))
+}
+
+/// Expression(source, angle="degrees")
+/// --
+///
+/// An expression checked once and evaluated for different values of its variables,
+/// e.g. `Expression("100 x rate").evaluate(rate=1.5)`.
+#[pyclass(name = "Expression", module = "rust_calculator", frozen)]
+struct CompiledExpression {
+    source: String,
+    variables: Vec<String>,
+    angle_mode: AngleMode,
+}
+
+#[pymethods]
+impl CompiledExpression {
+    #[new]
+    #[pyo3(signature = (source, angle = "degrees"))]
+    fn new(py: Python<'_>, source: &str, angle: &str) -> PyResult<Self> {
+        let variables = variables::names(source);
+        let mut placeholders = Variables::new();
+        for name in &variables {
+            placeholders
+                .set(name, 1.0)
+                .map_err(|kind| raise(py, &ExpressionError::new(kind, 0..0)))?;
+        }
+        placeholders
+            .check_syntax(source)
+            .map_err(|error| raise(py, &error))?;
+        Ok(Self {
+            source: source.to_string(),
+            variables,
+            angle_mode: angle_mode(angle)?,
+        })
+    }
+
+    /// The expression as written.
+    #[getter]
+    fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Names of the variables the expression uses, in order of appearance.
+    #[getter]
+    fn variables(&self) -> Vec<String> {
+        self.variables.clone()
+    }
+
+    /// evaluate(**values)
+    /// --
+    ///
+    /// Evaluates the expression with the given variable values. A variable without a
+    /// value is an `invalid_characters` error at its position.
+    #[pyo3(signature = (**values))]
+    fn evaluate(&self, py: Python<'_>, values: Option<&Bound<'_, PyDict>>) -> PyResult<f64> {
+        let mut variables = Variables::new();
+        for (name, value) in values.into_iter().flatten() {
+            let name: String = name.extract()?;
+            variables
+                .set(&name, value.extract()?)
+                .map_err(|kind| raise(py, &ExpressionError::new(kind, 0..0)))?;
+        }
+        variables
+            .evaluate(&self.source, self.angle_mode)
+            .map_err(|error| raise(py, &error))
+    }
+
+    #[pyo3(signature = (**values))]
+    fn __call__(&self, py: Python<'_>, values: Option<&Bound<'_, PyDict>>) -> PyResult<f64> {
+        self.evaluate(py, values)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Expression({:?})", self.source)
+    }
+}
+
+/// The calculator's expression evaluator, with the same semantics as the calculator window.
+#[pymodule]
+pub fn rust_calculator(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(evaluate, module)?)?;
+    module.add_class::<CompiledExpression>()?;
+    module.add("CalculatorError", module.py().get_type::<CalculatorError>())?;
+    Ok(())
+}
@@ -0,0 +1,331 @@
+use crate::calculator::{AngleMode, ExpressionError, SyntaxError};
+use crate::cli::{self, EvalOptions};
+use crate::variables::{Variables, is_variable_name};
+use rustyline::DefaultEditor;
+use rustyline::error::ReadlineError;
+use std::path::PathBuf;
+
+/// Prompt for a new entry.
+pub const PROMPT: &str = "> ";
+/// Prompt for the next line of an unfinished entry; as wide as `PROMPT`.
+pub const CONTINUATION_PROMPT: &str = ". ";
+
+/// Name of the variable holding the last result.
+pub const ANSWER: &str = "ans";
+
+/// Help text printed by `:help`.
+pub const HELP: &str = "\
+Enter an expression to evaluate it, or `name = expression` to store the result.
+`ans` is the last result. End a line with \\ or leave a parenthesis open to continue
+the entry on the next line.
+
+Commands:
+  :angle [degrees|radians]         Show or set the angle unit
+  :precision [N]                   Show or set the decimal places, 0 to 15
+  :format [auto|fixed|scientific]  Show or set the number format
+  :vars                            List the variables
+  :help                            Print this help
+  :quit                            Leave (Ctrl+D does too)";
+
+/// What the REPL has to show after a line of input.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Reply {
+    /// Nothing to show, e.g. after a blank line or while an entry continues
+    Nothing,
+    /// A result or the output of a command
+    Output(String),
+    /// An error, with the position marked under the input where it has one
+    Error(String),
+    /// The user asked to leave
+    Quit,
+}
+
+/// An interactive session: evaluation options, variables and the entry being typed.
+///
+/// Line editing is left to the terminal front end (`run`), so a session can be driven
+/// line by line.
+///
+/// # Examples
+///
+/// ```
+/// use rust_calculator::cli::EvalOptions;
+/// use rust_calculator::repl::{Repl, Reply};
+///
+/// let mut repl = Repl::new(EvalOptions::default());
+/// assert_eq!(repl.handle_line("rate = 1.5"), Reply::Output("rate = 1.5".to_string()));
+/// assert_eq!(repl.handle_line("ans x 4"), Reply::Output("6".to_string()));
+/// assert_eq!(repl.handle_line("(rate +"), Reply::Nothing);
+/// assert_eq!(repl.handle_line("0.5)"), Reply::Output("2".to_string()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Repl {
+    /// How expressions are evaluated and results formatted
+    pub options: EvalOptions,
+    variables: Variables,
+    pending: Vec<String>,
+}
+
+impl Repl {
+    /// Creates a session with `ans` set to 0.
+    pub fn new(options: EvalOptions) -> Self {
+        let mut variables = Variables::new();
+        variables.set(ANSWER, 0.0).unwrap();
+        Self {
+            options,
+            variables,
+            pending: Vec::new(),
+        }
+    }
+
+    /// The prompt for the next line.
+    pub fn prompt(&self) -> &'static str {
+        if self.pending.is_empty() {
+            PROMPT
+        } else {
+            CONTINUATION_PROMPT
+        }
+    }
+
+    /// Drops an unfinished entry, e.g. after Ctrl+C.
+    pub fn cancel(&mut self) {
+        self.pending.clear();
+    }
+
+    /// Value of a variable.
+    pub fn variable(&self, name: &str) -> Option<f64> {
+        self.variables.get(name)
+    }
+
+    /// Handles one line of input: a command, the end of an entry, or a line to be
+    /// continued.
+    pub fn handle_line(&mut self, line: &str) -> Reply {
+        if self.pending.is_empty() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                return Reply::Nothing;
+            }
+            if let Some(command) = trimmed.strip_prefix(':') {
+                return self.command(command);
+            }
+        }
+
+        // A trailing backslash, or a parenthesis left open, continues the entry
+        if let Some(line) = line.trim_end().strip_suffix('\\') {
+            self.pending.push(line.to_string());
+            return Reply::Nothing;
+        }
+        self.pending.push(line.to_string());
+        let lines = self.pending.len();
+        let entry = self.pending.join(" ");
+        if !line.trim().is_empty() && open_parentheses(&entry) > 0 {
+            return Reply::Nothing;
+        }
+        self.pending.clear();
+
+        match self.enter(&entry) {
+            Ok(reply) => reply,
+            Err(error) => Reply::Error(mark_error(&entry, lines > 1, &error)),
+        }
+    }
+
+    /// Evaluates an expression that may use the session's variables.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::cli::EvalOptions;
+    /// use rust_calculator::repl::Repl;
+    ///
+    /// let mut repl = Repl::new(EvalOptions::default());
+    /// repl.handle_line("a = 4");
+    /// assert_eq!(repl.evaluate("√(a)+a"), Ok(6.0));
+    /// assert_eq!(repl.evaluate("a÷(a-4)").unwrap_err().span, 3..6);
+    /// ```
+    pub fn evaluate(&self, expression: &str) -> Result<f64, ExpressionError> {
+        self.variables.evaluate(expression, self.options.angle_mode)
+    }
+
+    /// Evaluates an entry, storing its value in `ans` and, for `name = expression`,
+    /// in the named variable.
+    fn enter(&mut self, entry: &str) -> Result<Reply, ExpressionError> {
+        let (name, expression, offset) = match entry.split_once('=') {
+            Some((name, expression)) => {
+                let name = name.trim();
+                if !is_variable_name(name) {
+                    return Ok(Reply::Error(format!(
+                        "`{}` can't be a variable name; use lowercase letters, digits and _",
+                        name
+                    )));
+                }
+                // An empty expression would evaluate to 0; assigning that is surely a slip
+                if expression.trim().is_empty() {
+                    let end = entry.chars().count();
+                    return Err(ExpressionError::new(SyntaxError::Empty, end..end));
+                }
+                (
+                    Some(name),
+                    expression,
+                    entry.chars().count() - expression.chars().count(),
+                )
+            }
+            None => (None, entry, 0),
+        };
+
+        let value = self.evaluate(expression).map_err(|error| {
+            let span = error.span.start + offset..error.span.end + offset;
+            ExpressionError::new(error.kind, span)
+        })?;
+        let formatted = self.options.calculator().format_value(value);
+        self.variables.set(ANSWER, value).unwrap();
+        Ok(Reply::Output(match name {
+            Some(name) => {
+                self.variables.set(name, value).unwrap();
+                format!("{} = {}", name, formatted)
+            }
+            None => formatted,
+        }))
+    }
+
+    fn command(&mut self, command: &str) -> Reply {
+        let mut words = command.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let value = words.next();
+        if words.next().is_some() {
+            return Reply::Error(format!("`:{}` takes at most one value", name));
+        }
+
+        let options = &mut self.options;
+        let result = match (name, value) {
+            ("angle", Some(value)) => {
+                cli::parse_angle_mode(value).map(|angle| options.angle_mode = angle)
+            }
+            ("precision", Some(value)) => cli::parse_precision(value)
+                .map(|precision| options.number_format.precision = precision),
+            ("format", Some(value)) => {
+                cli::parse_format(value).map(|format| options.number_format.display_format = format)
+            }
+            ("angle" | "precision" | "format", None) => Ok(()),
+            ("vars", None) => return Reply::Output(self.list_variables()),
+            ("help", None) => return Reply::Output(HELP.to_string()),
+            ("quit" | "q" | "exit", None) => return Reply::Quit,
+            ("vars" | "help" | "quit" | "q" | "exit", Some(_)) => {
+                Err(format!("`:{}` takes no value", name))
+            }
+            _ => Err(format!(
+                "unknown command `:{}`; :help lists the commands",
+                name
+            )),
+        };
+
+        match result {
+            Ok(()) => Reply::Output(match name {
+                "angle" => format!("angle: {}", angle_name(options.angle_mode)),
+                "precision" => format!("precision: {}", options.number_format.precision),
+                _ => format!("format: {}", options.number_format.display_format.name()),
+            }),
+            Err(message) => Reply::Error(message),
+        }
+    }
+
+    fn list_variables(&self) -> String {
+        let calculator = self.options.calculator();
+        self.variables
+            .iter()
+            .map(|(name, value)| format!("{} = {}", name, calculator.format_value(value)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Number of parentheses opened but not yet closed.
+fn open_parentheses(text: &str) -> isize {
+    text.chars().fold(0, |open, c| match c {
+        '(' => open + 1,
+        ')' => open - 1,
+        _ => open,
+    })
+}
+
+/// Formats an error with carets under the characters it is about. The carets line up with
+/// the input after the prompt; an entry typed on several lines is repeated first.
+///
+/// # Examples
+///
+/// ```
+/// use rust_calculator::Calculator;
+/// use rust_calculator::repl::mark_error;
+///
+/// let error = Calculator::new().evaluate_spanned("2+*3").unwrap_err();
+/// assert_eq!(mark_error("2+*3", false, &error), "    ^\nUnexpected '*'");
+/// ```
+pub fn mark_error(entry: &str, multi_line: bool, error: &ExpressionError) -> String {
+    let indent = " ".repeat(PROMPT.chars().count());
+    let echo = if multi_line {
+        format!("{}{}\n", indent, entry)
+    } else {
+        String::new()
+    };
+    format!(
+        "{}{}{}{}\n{}",
+        echo,
+        indent,
+        " ".repeat(error.span.start),
+        "^".repeat(error.span.len().max(1)),
+        error
+    )
+}
+
+fn angle_name(angle_mode: AngleMode) -> &'static str {
+    match angle_mode {
+        AngleMode::Degrees => "degrees",
+        AngleMode::Radians => "radians",
+    }
+}
+
+/// Location of the REPL history file in the user config directory.
+pub fn history_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("rust-calculator").join("history.txt"))
+}
+
+/// Runs the REPL on the terminal until `:quit` or end of input. Results go to stdout and
+/// errors to stderr; the history is kept in `history_path()`.
+pub fn run(options: EvalOptions) -> rustyline::Result<()> {
+    let mut editor = DefaultEditor::new()?;
+    let history = history_path();
+    if let Some(path) = &history {
+        // There is no history yet on the first run
+        let _ = editor.load_history(path);
+    }
+
+    let mut repl = Repl::new(options);
+    loop {
+        match editor.readline(repl.prompt()) {
+            Ok(line) => {
+                if !line.trim().is_empty() {
+                    editor.add_history_entry(line.as_str())?;
+                }
+                match repl.handle_line(&line) {
+                    Reply::Nothing => {}
+                    Reply::Output(output) => println!("{}", output),
+                    Reply::Error(message) => eprintln!("{}", message),
+                    Reply::Quit => break,
+                }
+            }
+            Err(ReadlineError::Interrupted) => repl.cancel(),
+            Err(ReadlineError::Eof) => break,
+            Err(error) => return Err(error),
+        }
+    }
+
+    if let Some(path) = &history {
+        let saved = match path.parent() {
+            Some(dir) => std::fs::create_dir_all(dir).map_err(ReadlineError::from),
+            None => Ok(()),
+        }
+        .and_then(|()| editor.save_history(path));
+        if let Err(error) = saved {
+            eprintln!("History not saved to {}: {}", path.display(), error);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,279 @@
+use crate::calculator::{Calculator, CalculatorError, Constant, Operation};
+use crate::settings::NumberFormat;
+
+/// Reverse Polish Notation (RPN) calculator with an unbounded stack.
+///
+/// Numbers are typed into an entry buffer and pushed onto the stack with ENTER.
+/// Operators apply immediately to the two lowest stack levels (X and Y).
+/// Any pending entry is pushed automatically before a stack operation runs.
+#[derive(Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RpnCalculator {
+    /// Stack values; the last element is the X register, the one before it Y, and so on
+    pub stack: Vec<f64>,
+    /// The number currently being typed, not yet pushed onto the stack
+    pub entry: String,
+    /// The X value consumed by the last operation, recalled with LASTx
+    pub last_x: Option<f64>,
+    /// Error from the last operation, shown until the next input
+    pub error: Option<CalculatorError>,
+    /// Precision and notation of the stack values
+    pub number_format: NumberFormat,
+}
+
+impl RpnCalculator {
+    /// Names of the stack levels shown by the GUI, from X upwards.
+    pub const LEVEL_NAMES: [&'static str; 4] = ["X", "Y", "Z", "T"];
+
+    /// Creates a new RPN calculator with an empty stack.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Handles digit input by appending it to the entry buffer.
+    pub fn handle_number_input(&mut self, digit: u8) {
+        self.error = None;
+        if self.entry == "0" {
+            self.entry.clear();
+        }
+        self.entry.push_str(&digit.to_string());
+    }
+
+    /// Handles decimal point input; ignored if the entry already has one.
+    pub fn handle_decimal_input(&mut self) {
+        self.error = None;
+        if self.entry.is_empty() || self.entry == "-" {
+            self.entry.push_str("0.");
+        } else if !self.entry.contains('.') {
+            self.entry.push('.');
+        }
+    }
+
+    /// Handles ENTER: pushes the entry onto the stack, or duplicates X when nothing is typed.
+    pub fn handle_enter(&mut self) {
+        self.error = None;
+        if self.entry.is_empty() {
+            if let Some(&x) = self.stack.last() {
+                self.stack.push(x);
+            }
+        } else {
+            self.commit_entry();
+        }
+    }
+
+    /// Applies a binary operation to Y and X, replacing both with the result.
+    pub fn handle_operation_input(&mut self, operation: Operation) {
+        self.error = None;
+        if !self.commit_entry() {
+            return;
+        }
+        if self.stack.len() < 2 {
+            self.error = Some(CalculatorError::TooFewArguments);
+            return;
+        }
+
+        let x = self.stack[self.stack.len() - 1];
+        let y = self.stack[self.stack.len() - 2];
+        match Self::apply(operation, y, x) {
+            Ok(result) => {
+                self.stack.truncate(self.stack.len() - 2);
+                self.stack.push(result);
+                self.last_x = Some(x);
+            }
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    /// Computes `y op x` with the same error rules as the algebraic evaluator.
+    fn apply(operation: Operation, y: f64, x: f64) -> Result<f64, CalculatorError> {
+        if operation == Operation::Divide && x == 0.0 {
+            return Err(CalculatorError::DivisionByZero);
+        }
+        let result = Calculator::new().calculate(operation, y, x)?;
+        if !result.is_finite() || result.abs() > 1e100 {
+            return Err(CalculatorError::NumberOutOfRange(result.to_string()));
+        }
+        Ok(result)
+    }
+
+    /// Replaces X with `f(X)`, e.g. a scientific function.
+    pub fn handle_unary_input(&mut self, f: impl Fn(f64) -> Result<f64, CalculatorError>) {
+        self.error = None;
+        if !self.commit_entry() {
+            return;
+        }
+        let Some(&x) = self.stack.last() else {
+            self.error = Some(CalculatorError::TooFewArguments);
+            return;
+        };
+        match f(x) {
+            Ok(result) if result.is_finite() && result.abs() <= 1e100 => {
+                self.stack.pop();
+                self.stack.push(result);
+                self.last_x = Some(x);
+            }
+            Ok(result) => self.error = Some(CalculatorError::NumberOutOfRange(result.to_string())),
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    /// Pushes a constant onto the stack.
+    pub fn handle_constant_input(&mut self, constant: Constant) {
+        self.error = None;
+        if self.commit_entry() {
+            self.stack.push(constant.value());
+        }
+    }
+
+    /// Swaps the X and Y registers.
+    pub fn handle_swap(&mut self) {
+        self.error = None;
+        if !self.commit_entry() {
+            return;
+        }
+        let len = self.stack.len();
+        if len < 2 {
+            self.error = Some(CalculatorError::TooFewArguments);
+            return;
+        }
+        self.stack.swap(len - 1, len - 2);
+    }
+
+    /// Drops X from the stack, or discards the entry if a number is being typed.
+    pub fn handle_drop(&mut self) {
+        self.error = None;
+        if !self.entry.is_empty() {
+            self.entry.clear();
+        } else {
+            self.stack.pop();
+        }
+    }
+
+    /// Rolls the stack down: Y becomes X, and so on, while X moves to the top level.
+    pub fn handle_roll_down(&mut self) {
+        self.error = None;
+        if !self.commit_entry() {
+            return;
+        }
+        if self.stack.is_empty() {
+            self.error = Some(CalculatorError::TooFewArguments);
+            return;
+        }
+        self.stack.rotate_right(1);
+    }
+
+    /// Pushes the X value consumed by the last operation back onto the stack.
+    pub fn handle_last_x(&mut self) {
+        self.error = None;
+        if !self.commit_entry() {
+            return;
+        }
+        match self.last_x {
+            Some(x) => self.stack.push(x),
+            None => self.error = Some(CalculatorError::NoLastX),
+        }
+    }
+
+    /// Handles backspace by removing the last character of the entry.
+    pub fn handle_backspace_input(&mut self) {
+        self.error = None;
+        self.entry.pop();
+    }
+
+    /// Clears the stack, the entry and LASTx.
+    pub fn handle_clear_input(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Toggles the sign of the entry, or of X when nothing is being typed.
+    pub fn handle_sign_toggle_input(&mut self) {
+        self.error = None;
+        if let Some(rest) = self.entry.strip_prefix('-') {
+            self.entry = rest.to_string();
+        } else if !self.entry.is_empty() {
+            self.entry.insert(0, '-');
+        } else if let Some(x) = self.stack.last_mut() {
+            *x = -*x;
+        }
+    }
+
+    /// Replaces X with X percent of Y, keeping Y on the stack.
+    pub fn handle_percentage_input(&mut self) {
+        self.error = None;
+        if !self.commit_entry() {
+            return;
+        }
+        let len = self.stack.len();
+        if len < 2 {
+            self.error = Some(CalculatorError::TooFewArguments);
+            return;
+        }
+        let x = self.stack[len - 1];
+        self.stack[len - 1] = self.stack[len - 2] * x / 100.0;
+        self.last_x = Some(x);
+    }
+
+    /// Pushes a pending entry onto the stack.
+    /// Returns false (and sets the error) if the entry isn't a valid number.
+    fn commit_entry(&mut self) -> bool {
+        if self.entry.is_empty() {
+            return true;
+        }
+        match Calculator::safe_parse_number(&self.entry) {
+            Ok(value) => {
+                self.stack.push(value);
+                self.entry.clear();
+                true
+            }
+            Err(error) => {
+                self.error = Some(error);
+                false
+            }
+        }
+    }
+
+    /// Returns the value of the X register, including a pending entry.
+    pub fn x_value(&self) -> Option<f64> {
+        if self.entry.is_empty() {
+            self.stack.last().copied()
+        } else {
+            self.entry.parse().ok()
+        }
+    }
+
+    /// Returns the text shown for a stack level, where level 0 is X.
+    ///
+    /// While a number is being typed it is shown in X and the stack appears lifted by one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::RpnCalculator;
+    ///
+    /// let mut rpn = RpnCalculator::new();
+    /// rpn.handle_number_input(3);
+    /// rpn.handle_enter();
+    /// rpn.handle_number_input(4);
+    /// assert_eq!(rpn.level_display(0), "4");
+    /// assert_eq!(rpn.level_display(1), "3");
+    /// assert_eq!(rpn.level_display(2), "");
+    /// ```
+    pub fn level_display(&self, level: usize) -> String {
+        if level == 0 {
+            if let Some(error) = &self.error {
+                return error.to_string();
+            }
+            if !self.entry.is_empty() {
+                return self.entry.clone();
+            }
+        }
+        let offset = if self.entry.is_empty() { 0 } else { 1 };
+        match level.checked_sub(offset) {
+            Some(index) if index < self.stack.len() => self
+                .number_format
+                .format(self.stack[self.stack.len() - 1 - index]),
+            _ if level == 0 => "0".to_string(),
+            _ => String::new(),
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use crate::calculator::{Constant, Function};
+
+/// Keys of the scientific keypad panel.
+///
+/// Most keys have a second function that is used while the 2nd (shift) layer is active.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ScientificKey {
+    /// x² (2nd: x³)
+    Square,
+    /// xʸ
+    Power,
+    /// √x (2nd: ∛x)
+    Root,
+    /// x!
+    Factorial,
+    /// 1/x (2nd: |x|)
+    Reciprocal,
+    /// sin (2nd: sin⁻¹)
+    Sin,
+    /// cos (2nd: cos⁻¹)
+    Cos,
+    /// tan (2nd: tan⁻¹)
+    Tan,
+    /// ln (2nd: eˣ)
+    Ln,
+    /// log (2nd: 10ˣ)
+    Log,
+    /// π
+    Pi,
+    /// e
+    E,
+}
+
+/// What a scientific key does once the 2nd layer has been taken into account.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScientificAction {
+    /// Apply a function to the following operand
+    Function(Function),
+    /// Enter a constant
+    Constant(Constant),
+    /// Binary exponentiation, x^y
+    Power,
+    /// Raise the current operand to a fixed integer power
+    RaiseTo(i32),
+    /// Factorial of the current operand
+    Factorial,
+    /// e raised to the following operand
+    Exp,
+    /// 10 raised to the following operand
+    Exp10,
+}
+
+impl ScientificKey {
+    /// Key layout of the scientific panel, row by row.
+    pub const LAYOUT: [[ScientificKey; 4]; 3] = [
+        [
+            ScientificKey::Square,
+            ScientificKey::Power,
+            ScientificKey::Root,
+            ScientificKey::Factorial,
+        ],
+        [
+            ScientificKey::Sin,
+            ScientificKey::Cos,
+            ScientificKey::Tan,
+            ScientificKey::Reciprocal,
+        ],
+        [
+            ScientificKey::Ln,
+            ScientificKey::Log,
+            ScientificKey::Pi,
+            ScientificKey::E,
+        ],
+    ];
+
+    /// Returns the key label for the given layer.
+    pub fn label(self, second: bool) -> &'static str {
+        match (self, second) {
+            (ScientificKey::Square, false) => "x²",
+            (ScientificKey::Square, true) => "x³",
+            (ScientificKey::Power, _) => "xʸ",
+            (ScientificKey::Root, false) => "√x",
+            (ScientificKey::Root, true) => "∛x",
+            (ScientificKey::Factorial, _) => "x!",
+            (ScientificKey::Reciprocal, false) => "1/x",
+            (ScientificKey::Reciprocal, true) => "|x|",
+            (ScientificKey::Sin, false) => "sin",
+            (ScientificKey::Sin, true) => "sin⁻¹",
+            (ScientificKey::Cos, false) => "cos",
+            (ScientificKey::Cos, true) => "cos⁻¹",
+            (ScientificKey::Tan, false) => "tan",
+            (ScientificKey::Tan, true) => "tan⁻¹",
+            (ScientificKey::Ln, false) => "ln",
+            (ScientificKey::Ln, true) => "eˣ",
+            (ScientificKey::Log, false) => "log",
+            (ScientificKey::Log, true) => "10ˣ",
+            (ScientificKey::Pi, _) => "π",
+            (ScientificKey::E, _) => "e",
+        }
+    }
+
+    /// Resolves the key to an action for the given layer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::{Function, ScientificAction, ScientificKey};
+    ///
+    /// assert_eq!(
+    ///     ScientificKey::Sin.action(false),
+    ///     ScientificAction::Function(Function::Sin)
+    /// );
+    /// assert_eq!(
+    ///     ScientificKey::Sin.action(true),
+    ///     ScientificAction::Function(Function::Asin)
+    /// );
+    /// ```
+    pub fn action(self, second: bool) -> ScientificAction {
+        match (self, second) {
+            (ScientificKey::Square, false) => ScientificAction::RaiseTo(2),
+            (ScientificKey::Square, true) => ScientificAction::RaiseTo(3),
+            (ScientificKey::Power, _) => ScientificAction::Power,
+            (ScientificKey::Root, false) => ScientificAction::Function(Function::Sqrt),
+            (ScientificKey::Root, true) => ScientificAction::Function(Function::Cbrt),
+            (ScientificKey::Factorial, _) => ScientificAction::Factorial,
+            (ScientificKey::Reciprocal, false) => ScientificAction::RaiseTo(-1),
+            (ScientificKey::Reciprocal, true) => ScientificAction::Function(Function::Abs),
+            (ScientificKey::Sin, false) => ScientificAction::Function(Function::Sin),
+            (ScientificKey::Sin, true) => ScientificAction::Function(Function::Asin),
+            (ScientificKey::Cos, false) => ScientificAction::Function(Function::Cos),
+            (ScientificKey::Cos, true) => ScientificAction::Function(Function::Acos),
+            (ScientificKey::Tan, false) => ScientificAction::Function(Function::Tan),
+            (ScientificKey::Tan, true) => ScientificAction::Function(Function::Atan),
+            (ScientificKey::Ln, false) => ScientificAction::Function(Function::Ln),
+            (ScientificKey::Ln, true) => ScientificAction::Exp,
+            (ScientificKey::Log, false) => ScientificAction::Function(Function::Log),
+            (ScientificKey::Log, true) => ScientificAction::Exp10,
+            (ScientificKey::Pi, _) => ScientificAction::Constant(Constant::Pi),
+            (ScientificKey::E, _) => ScientificAction::Constant(Constant::E),
+        }
+    }
+}
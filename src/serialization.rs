@@ -0,0 +1,99 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Version of the JSON representation written by `to_json`. It is raised whenever the
+/// representation of a type changes incompatibly, so old data is rejected, not misread.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Why `from_json` couldn't read a value.
+#[derive(Debug)]
+pub enum FormatError {
+    /// Not JSON, or not a versioned value of the expected type
+    Json(serde_json::Error),
+    /// Written with another version of the representation
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::Json(error) => write!(f, "{}", error),
+            FormatError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported format version {}, expected {}",
+                version, FORMAT_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+impl From<serde_json::Error> for FormatError {
+    fn from(error: serde_json::Error) -> Self {
+        FormatError::Json(error)
+    }
+}
+
+#[derive(Serialize)]
+struct Envelope<'a, T> {
+    version: u32,
+    data: &'a T,
+}
+
+#[derive(Deserialize)]
+struct Version {
+    version: u32,
+}
+
+#[derive(Deserialize)]
+struct Data<T> {
+    data: T,
+}
+
+/// Writes a value as versioned JSON, `{"version": 1, "data": ...}`.
+///
+/// Struct fields keep their Rust names and enum variants are written in snake_case, a unit
+/// variant as a string and any other as an object with the variant as its only key.
+///
+/// # Examples
+///
+/// ```
+/// use rust_calculator::serialization::{from_json, to_json};
+/// use rust_calculator::{Operation, UIMessage};
+///
+/// let message = UIMessage::OperationPressed(Operation::Add);
+/// let json = to_json(&message).unwrap();
+/// assert_eq!(json, r#"{"version":1,"data":{"operation_pressed":"add"}}"#);
+/// assert_eq!(from_json::<UIMessage>(&json).unwrap(), message);
+/// ```
+pub fn to_json<T: Serialize>(value: &T) -> serde_json::Result<String> {
+    serde_json::to_string(&Envelope {
+        version: FORMAT_VERSION,
+        data: value,
+    })
+}
+
+/// Reads a value written by `to_json`, checking its version first.
+///
+/// # Examples
+///
+/// ```
+/// use rust_calculator::serialization::{FormatError, from_json};
+/// use rust_calculator::Operation;
+///
+/// assert_eq!(from_json::<Operation>(r#"{"version":1,"data":"power"}"#).unwrap(), Operation::Power);
+/// assert!(matches!(
+///     from_json::<Operation>(r#"{"version":2,"data":"power"}"#),
+///     Err(FormatError::UnsupportedVersion(2))
+/// ));
+/// ```
+pub fn from_json<T: DeserializeOwned>(json: &str) -> Result<T, FormatError> {
+    let Version { version } = serde_json::from_str(json)?;
+    if version != FORMAT_VERSION {
+        return Err(FormatError::UnsupportedVersion(version));
+    }
+    let Data { data } = serde_json::from_str(json)?;
+    Ok(data)
+}
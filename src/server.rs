@@ -0,0 +1,374 @@
+use crate::calculator::{Calculator, ExpressionError};
+use crate::cli::{EvalOptions, parse_angle_mode, parse_format, validate_precision};
+use crate::expression::TokenKind;
+use crate::variables::Variables;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+/// JSON-RPC error code of a request that isn't valid JSON.
+pub const PARSE_ERROR: i64 = -32700;
+/// JSON-RPC error code of JSON that isn't a request object.
+pub const INVALID_REQUEST: i64 = -32600;
+/// JSON-RPC error code of an unknown method.
+pub const METHOD_NOT_FOUND: i64 = -32601;
+/// JSON-RPC error code of missing or malformed parameters.
+pub const INVALID_PARAMS: i64 = -32602;
+/// Error code of an expression that can't be parsed or evaluated. The error's `data`
+/// holds the `kind` of error and the `start`..`end` characters at fault.
+pub const CALCULATION_ERROR: i64 = -32000;
+
+/// Session used by requests without a `session` parameter.
+pub const DEFAULT_SESSION: &str = "default";
+
+/// A JSON-RPC error response, before the request id is added.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcError {
+    /// One of the error code constants
+    pub code: i64,
+    /// What went wrong
+    pub message: String,
+    /// Details, e.g. the position of a calculation error
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    fn invalid_params(message: impl ToString) -> Self {
+        Self::new(INVALID_PARAMS, message.to_string())
+    }
+}
+
+impl From<ExpressionError> for RpcError {
+    fn from(error: ExpressionError) -> Self {
+        Self {
+            code: CALCULATION_ERROR,
+            message: error.to_string(),
+            data: Some(json!({
+                "kind": error.kind.name(),
+                "start": error.span.start,
+                "end": error.span.end,
+            })),
+        }
+    }
+}
+
+/// One editor document: a calculator with its own angle mode, result format and variables.
+#[derive(Debug, Clone)]
+struct Session {
+    calculator: Calculator,
+    variables: Variables,
+}
+
+impl Session {
+    fn new(options: &EvalOptions) -> Self {
+        Self {
+            calculator: options.calculator(),
+            variables: Variables::new(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EvaluateParams {
+    expression: String,
+    session: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ParseParams {
+    expression: String,
+    session: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SetVariableParams {
+    name: String,
+    value: f64,
+    session: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GetVariableParams {
+    name: String,
+    session: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FormatParams {
+    value: f64,
+    session: Option<String>,
+    precision: Option<usize>,
+    format: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigureParams {
+    session: Option<String>,
+    angle: Option<String>,
+    precision: Option<usize>,
+    format: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CloseSessionParams {
+    session: String,
+}
+
+/// A JSON-RPC 2.0 server that keeps independent calculator sessions keyed by id.
+///
+/// Methods, with their parameters (`session` is optional everywhere and defaults to
+/// `"default"`; sessions are created when first used):
+///
+/// - `evaluate {expression, session}` → `{value, formatted}`
+/// - `parse {expression, session}` → `{tokens: [{kind, text, start, end, value?}]}`, where
+///   the session's variables are `variable` tokens
+/// - `setVariable {name, value, session}` → `null`
+/// - `getVariable {name, session}` → the value, or `null` if it isn't set
+/// - `format {value, session, precision, format}` → the value formatted as in the session,
+///   or with the given precision and `auto`, `fixed` or `scientific` format
+/// - `configure {session, angle, precision, format}` → `null`
+/// - `closeSession {session}` → whether the session existed
+///
+/// # Examples
+///
+/// ```
+/// use rust_calculator::cli::EvalOptions;
+/// use rust_calculator::server::Server;
+///
+/// let mut server = Server::new(EvalOptions::default());
+/// let response = server.handle_message(
+///     r#"{"jsonrpc":"2.0","id":1,"method":"evaluate","params":{"expression":"7+8*3"}}"#,
+/// );
+/// assert_eq!(
+///     response.as_deref(),
+///     Some(r#"{"id":1,"jsonrpc":"2.0","result":{"formatted":"31","value":31.0}}"#)
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Server {
+    /// Angle mode and result format of new sessions
+    options: EvalOptions,
+    sessions: HashMap<String, Session>,
+}
+
+impl Server {
+    /// Creates a server whose sessions start with `options`.
+    pub fn new(options: EvalOptions) -> Self {
+        Self {
+            options,
+            sessions: HashMap::new(),
+        }
+    }
+
+    /// Handles one message, a request or a batch of them, and returns the response to
+    /// send, if any. Notifications (requests without an id) get no response.
+    pub fn handle_message(&mut self, message: &str) -> Option<String> {
+        let response = match serde_json::from_str::<Value>(message) {
+            Ok(Value::Array(requests)) if !requests.is_empty() => {
+                let responses: Vec<Value> = requests
+                    .into_iter()
+                    .filter_map(|request| self.handle(request))
+                    .collect();
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+            Ok(Value::Array(_)) => Some(error_response(
+                Value::Null,
+                RpcError::new(INVALID_REQUEST, "Empty batch"),
+            )),
+            Ok(request) => self.handle(request),
+            Err(error) => Some(error_response(
+                Value::Null,
+                RpcError::new(PARSE_ERROR, format!("Parse error: {}", error)),
+            )),
+        };
+        response.map(|response| response.to_string())
+    }
+
+    /// Handles one request object and returns its response, if it has an id.
+    pub fn handle(&mut self, request: Value) -> Option<Value> {
+        let Value::Object(mut request) = request else {
+            return Some(error_response(
+                Value::Null,
+                RpcError::new(INVALID_REQUEST, "Invalid request: not an object"),
+            ));
+        };
+        let id = request.remove("id");
+        let method = match (request.remove("jsonrpc"), request.remove("method")) {
+            (Some(version), Some(Value::String(method))) if version == "2.0" => method,
+            _ => {
+                return Some(error_response(
+                    id.unwrap_or(Value::Null),
+                    RpcError::new(
+                        INVALID_REQUEST,
+                        "Invalid request: needs \"jsonrpc\": \"2.0\" and a method name",
+                    ),
+                ));
+            }
+        };
+        let params = request.remove("params").unwrap_or(Value::Null);
+
+        let result = self.call(&method, params);
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err(error) => error_response(id, error),
+        })
+    }
+
+    fn call(&mut self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "evaluate" => {
+                let params: EvaluateParams = parse_params(params)?;
+                let session = self.session(params.session);
+                let value = session
+                    .variables
+                    .evaluate(&params.expression, session.calculator.angle_mode)?;
+                let formatted = session.calculator.format_value(value);
+                Ok(json!({"value": value, "formatted": formatted}))
+            }
+            "parse" => {
+                let params: ParseParams = parse_params(params)?;
+                let chars: Vec<char> = params.expression.chars().collect();
+                let tokens = self
+                    .session(params.session)
+                    .variables
+                    .tokens(&params.expression)?
+                    .into_iter()
+                    .map(|(kind, span)| {
+                        let mut token = json!({
+                            "kind": kind.name(),
+                            "text": chars[span.clone()].iter().collect::<String>(),
+                            "start": span.start,
+                            "end": span.end,
+                        });
+                        if let TokenKind::Number(value) | TokenKind::Variable(value) = kind {
+                            token["value"] = json!(value);
+                        }
+                        token
+                    })
+                    .collect::<Vec<_>>();
+                Ok(json!({"tokens": tokens}))
+            }
+            "setVariable" => {
+                let params: SetVariableParams = parse_params(params)?;
+                self.session(params.session)
+                    .variables
+                    .set(&params.name, params.value)
+                    .map_err(RpcError::invalid_params)?;
+                Ok(Value::Null)
+            }
+            "getVariable" => {
+                let params: GetVariableParams = parse_params(params)?;
+                Ok(json!(
+                    self.session(params.session).variables.get(&params.name)
+                ))
+            }
+            "format" => {
+                let params: FormatParams = parse_params(params)?;
+                let mut number_format = self.session(params.session).calculator.number_format;
+                if let Some(precision) = params.precision {
+                    number_format.precision =
+                        validate_precision(precision).map_err(RpcError::invalid_params)?;
+                }
+                if let Some(format) = params.format {
+                    number_format.display_format =
+                        parse_format(&format).map_err(RpcError::invalid_params)?;
+                }
+                Ok(json!(number_format.format(params.value)))
+            }
+            "configure" => {
+                let params: ConfigureParams = parse_params(params)?;
+                // Check everything before changing anything
+                let angle = params.angle.as_deref().map(parse_angle_mode).transpose();
+                let precision = params.precision.map(validate_precision).transpose();
+                let format = params.format.as_deref().map(parse_format).transpose();
+                let (angle, precision, format) = (
+                    angle.map_err(RpcError::invalid_params)?,
+                    precision.map_err(RpcError::invalid_params)?,
+                    format.map_err(RpcError::invalid_params)?,
+                );
+
+                let calculator = &mut self.session(params.session).calculator;
+                if let Some(angle) = angle {
+                    calculator.angle_mode = angle;
+                }
+                if let Some(precision) = precision {
+                    calculator.number_format.precision = precision;
+                }
+                if let Some(format) = format {
+                    calculator.number_format.display_format = format;
+                }
+                Ok(Value::Null)
+            }
+            "closeSession" => {
+                let params: CloseSessionParams = parse_params(params)?;
+                Ok(json!(self.sessions.remove(&params.session).is_some()))
+            }
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Method not found: {}", method),
+            )),
+        }
+    }
+
+    /// The session with the given id, created if it doesn't exist yet.
+    fn session(&mut self, id: Option<String>) -> &mut Session {
+        let id = id.unwrap_or_else(|| DEFAULT_SESSION.to_string());
+        let options = &self.options;
+        self.sessions
+            .entry(id)
+            .or_insert_with(|| Session::new(options))
+    }
+}
+
+/// Reads the parameters of a method; a method without parameters may omit them.
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    let params = match params {
+        Value::Null => json!({}),
+        params => params,
+    };
+    serde_json::from_value(params)
+        .map_err(|error| RpcError::invalid_params(format!("Invalid params: {}", error)))
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    let mut body = json!({"code": error.code, "message": error.message});
+    if let Some(data) = error.data {
+        body["data"] = data;
+    }
+    json!({"jsonrpc": "2.0", "id": id, "error": body})
+}
+
+/// Serves JSON-RPC over a pair of streams with one message per line, until the input
+/// ends. Blank lines are ignored and every response is flushed as soon as it's written.
+pub fn run(input: impl BufRead, mut output: impl Write, options: EvalOptions) -> io::Result<()> {
+    let mut server = Server::new(options);
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = server.handle_message(&line) {
+            writeln!(output, "{}", response)?;
+            output.flush()?;
+        }
+    }
+    Ok(())
+}
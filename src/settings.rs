@@ -0,0 +1,445 @@
+use crate::calculator::AngleMode;
+use crate::locale::Locale;
+use crate::theme::ThemeChoice;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// How results are written out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DisplayFormat {
+    /// Plain decimals, switching to scientific notation for very large and very small values
+    #[default]
+    Auto,
+    /// Always the configured number of decimal places
+    Fixed,
+    /// Always scientific notation
+    Scientific,
+}
+
+impl DisplayFormat {
+    /// All formats, in menu order.
+    pub const ALL: [DisplayFormat; 3] = [
+        DisplayFormat::Auto,
+        DisplayFormat::Fixed,
+        DisplayFormat::Scientific,
+    ];
+
+    /// Name used in the settings file.
+    pub fn name(self) -> &'static str {
+        match self {
+            DisplayFormat::Auto => "auto",
+            DisplayFormat::Fixed => "fixed",
+            DisplayFormat::Scientific => "scientific",
+        }
+    }
+}
+
+impl fmt::Display for DisplayFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DisplayFormat::Auto => write!(f, "Auto"),
+            DisplayFormat::Fixed => write!(f, "Fixed"),
+            DisplayFormat::Scientific => write!(f, "Scientific"),
+        }
+    }
+}
+
+/// Character shown between the integer and fractional digits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DecimalSeparator {
+    /// `1.5`
+    #[default]
+    Point,
+    /// `1,5`
+    Comma,
+}
+
+impl DecimalSeparator {
+    /// All separators, in menu order.
+    pub const ALL: [DecimalSeparator; 2] = [DecimalSeparator::Point, DecimalSeparator::Comma];
+
+    /// Name used in the settings file.
+    pub fn name(self) -> &'static str {
+        match self {
+            DecimalSeparator::Point => "point",
+            DecimalSeparator::Comma => "comma",
+        }
+    }
+
+    /// The separator as shown on the decimal key.
+    pub fn label(self) -> &'static str {
+        match self {
+            DecimalSeparator::Point => ".",
+            DecimalSeparator::Comma => ",",
+        }
+    }
+
+    /// Rewrites text that uses `.` as the decimal point for display.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::settings::DecimalSeparator;
+    ///
+    /// assert_eq!(DecimalSeparator::Comma.localize("12x3.5"), "12x3,5");
+    /// assert_eq!(DecimalSeparator::Point.localize("12x3.5"), "12x3.5");
+    /// ```
+    pub fn localize(self, text: &str) -> String {
+        text.replace('.', self.label())
+    }
+}
+
+impl fmt::Display for DecimalSeparator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecimalSeparator::Point => write!(f, "Point (1.5)"),
+            DecimalSeparator::Comma => write!(f, "Comma (1,5)"),
+        }
+    }
+}
+
+/// Precision and notation used for results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NumberFormat {
+    /// Decimal places: the maximum for `Auto`, the exact number otherwise
+    pub precision: usize,
+    /// Plain, fixed or scientific notation
+    pub display_format: DisplayFormat,
+}
+
+impl NumberFormat {
+    /// Formats a result.
+    ///
+    /// `Auto` shows up to `precision` decimal places with trailing zeros removed, and
+    /// scientific notation with at most 4 decimals for values of a million and more or
+    /// below 0.0001. `Fixed` always shows `precision` decimal places, except for values
+    /// too large to write out. `Scientific` always uses scientific notation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::settings::{DisplayFormat, NumberFormat};
+    ///
+    /// let auto = NumberFormat::default();
+    /// assert_eq!(auto.format(2.5), "2.5");
+    /// assert_eq!(auto.format(1234567.0), "1.2346e6");
+    ///
+    /// let fixed = NumberFormat { precision: 2, display_format: DisplayFormat::Fixed };
+    /// assert_eq!(fixed.format(2.5), "2.50");
+    ///
+    /// let scientific = NumberFormat { precision: 3, display_format: DisplayFormat::Scientific };
+    /// assert_eq!(scientific.format(2.5), "2.500e0");
+    /// ```
+    pub fn format(&self, value: f64) -> String {
+        let precision = self.precision;
+        match self.display_format {
+            DisplayFormat::Auto => {
+                if value.abs() >= 1e6 || (value.abs() < 1e-4 && value != 0.0) {
+                    format!("{:.*e}", precision.min(4), value)
+                } else {
+                    // Remove unnecessary trailing zeros and decimal point
+                    let formatted = format!("{:.*}", precision, value);
+                    if formatted.contains('.') {
+                        formatted
+                            .trim_end_matches('0')
+                            .trim_end_matches('.')
+                            .to_string()
+                    } else {
+                        formatted
+                    }
+                }
+            }
+            DisplayFormat::Fixed if value.abs() < 1e15 => format!("{:.*}", precision, value),
+            DisplayFormat::Fixed | DisplayFormat::Scientific => {
+                format!("{:.*e}", precision, value)
+            }
+        }
+    }
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            precision: Settings::DEFAULT_PRECISION,
+            display_format: DisplayFormat::default(),
+        }
+    }
+}
+
+/// Something in a settings file that was ignored. `Display` gives the English message,
+/// and [`Locale::settings_warning`] the message in another language.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SettingsWarning {
+    /// The file could not be read; the reason, from the operating system
+    Unreadable(String),
+    /// The file isn't valid TOML; the parser's message
+    InvalidToml(String),
+    /// A setting that doesn't exist, by its key
+    UnknownSetting(String),
+    /// `precision` isn't a whole number from 0 to `Settings::MAX_PRECISION`
+    InvalidPrecision,
+    /// `display_format` isn't a display format name
+    InvalidDisplayFormat,
+    /// `angle_mode` isn't `degrees` or `radians`
+    InvalidAngleMode,
+    /// `theme` isn't a theme name
+    InvalidTheme,
+    /// `keymap` isn't a path
+    InvalidKeymap,
+    /// `decimal_separator` isn't `point` or `comma`
+    InvalidDecimalSeparator,
+    /// `locale` isn't a known language
+    InvalidLocale,
+}
+
+impl fmt::Display for SettingsWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&Locale::English.settings_warning(self))
+    }
+}
+
+/// User settings, persisted to `settings.toml` in the user config directory.
+///
+/// The file holds one key per field; missing keys keep their defaults:
+///
+/// ```toml
+/// precision = 8                 # 0 to 15
+/// display_format = "auto"       # "auto", "fixed" or "scientific"
+/// angle_mode = "degrees"        # "degrees" or "radians"
+/// theme = "dark"                # "system", "light", "dark", "high-contrast" or a custom name
+/// keymap = "/path/keymap.toml"  # optional, defaults to keymap.toml in the config directory
+/// decimal_separator = "point"   # "point" or "comma"
+/// locale = "en"                 # "en" or "de"; also sets the decimal separator if it isn't given
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Settings {
+    /// Decimal places of results
+    pub precision: usize,
+    /// Notation of results
+    pub display_format: DisplayFormat,
+    /// Unit for trigonometric functions
+    pub angle_mode: AngleMode,
+    /// Selected color theme
+    pub theme: ThemeChoice,
+    /// Keymap file to load instead of the default location
+    pub keymap: Option<PathBuf>,
+    /// Character shown as the decimal point
+    pub decimal_separator: DecimalSeparator,
+    /// Language of labels and messages
+    pub locale: Locale,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            precision: Self::DEFAULT_PRECISION,
+            display_format: DisplayFormat::default(),
+            angle_mode: AngleMode::default(),
+            theme: ThemeChoice::default(),
+            keymap: None,
+            decimal_separator: DecimalSeparator::default(),
+            locale: Locale::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Decimal places used when the settings don't say otherwise.
+    pub const DEFAULT_PRECISION: usize = 8;
+    /// Largest accepted precision; `f64` has no more significant decimals to show.
+    pub const MAX_PRECISION: usize = 15;
+
+    /// Precision and notation for formatting results.
+    pub fn number_format(&self) -> NumberFormat {
+        NumberFormat {
+            precision: self.precision,
+            display_format: self.display_format,
+        }
+    }
+
+    /// Parses a settings file.
+    ///
+    /// Never fails: each missing, invalid or unknown value is replaced by its default,
+    /// and a warning saying what was ignored is returned alongside the settings. A file
+    /// that isn't valid TOML gives the defaults and a single warning.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::AngleMode;
+    /// use rust_calculator::settings::Settings;
+    ///
+    /// let (settings, warnings) = Settings::parse("angle_mode = \"radians\"\nprecision = 99");
+    /// assert_eq!(settings.angle_mode, AngleMode::Radians);
+    /// assert_eq!(settings.precision, Settings::DEFAULT_PRECISION);
+    /// assert_eq!(warnings.len(), 1);
+    /// ```
+    pub fn parse(text: &str) -> (Self, Vec<SettingsWarning>) {
+        let mut settings = Self::default();
+        let mut warnings = Vec::new();
+        let table = match text.parse::<toml::Table>() {
+            Ok(table) => table,
+            Err(error) => {
+                warnings.push(SettingsWarning::InvalidToml(error.message().to_string()));
+                return (settings, warnings);
+            }
+        };
+
+        for (key, value) in &table {
+            let parsed = match key.as_str() {
+                "precision" => value
+                    .as_integer()
+                    .and_then(|precision| usize::try_from(precision).ok())
+                    .filter(|precision| *precision <= Self::MAX_PRECISION)
+                    .map(|precision| settings.precision = precision)
+                    .ok_or(SettingsWarning::InvalidPrecision),
+                "display_format" => value
+                    .as_str()
+                    .and_then(|name| DisplayFormat::ALL.into_iter().find(|f| f.name() == name))
+                    .map(|format| settings.display_format = format)
+                    .ok_or(SettingsWarning::InvalidDisplayFormat),
+                "angle_mode" => value
+                    .as_str()
+                    .and_then(angle_mode_from_name)
+                    .map(|mode| settings.angle_mode = mode)
+                    .ok_or(SettingsWarning::InvalidAngleMode),
+                "theme" => value
+                    .as_str()
+                    .filter(|name| !name.trim().is_empty())
+                    .map(|name| settings.theme = theme_from_name(name))
+                    .ok_or(SettingsWarning::InvalidTheme),
+                "keymap" => value
+                    .as_str()
+                    .map(|path| settings.keymap = keymap_from_str(path))
+                    .ok_or(SettingsWarning::InvalidKeymap),
+                "decimal_separator" => value
+                    .as_str()
+                    .and_then(|name| {
+                        DecimalSeparator::ALL
+                            .into_iter()
+                            .find(|separator| separator.name() == name)
+                    })
+                    .map(|separator| settings.decimal_separator = separator)
+                    .ok_or(SettingsWarning::InvalidDecimalSeparator),
+                "locale" => value
+                    .as_str()
+                    .and_then(Locale::from_code)
+                    .map(|locale| settings.locale = locale)
+                    .ok_or(SettingsWarning::InvalidLocale),
+                _ => Err(SettingsWarning::UnknownSetting(key.clone())),
+            };
+            if let Err(warning) = parsed {
+                warnings.push(warning);
+            }
+        }
+        if !table.contains_key("decimal_separator") {
+            settings.decimal_separator = settings.locale.decimal_separator();
+        }
+        (settings, warnings)
+    }
+
+    /// Writes the settings in the format read by `parse`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::Locale;
+    /// use rust_calculator::settings::{DecimalSeparator, Settings};
+    ///
+    /// let settings = Settings {
+    ///     decimal_separator: DecimalSeparator::Comma,
+    ///     locale: Locale::German,
+    ///     ..Settings::default()
+    /// };
+    /// assert_eq!(Settings::parse(&settings.to_toml()), (settings, Vec::new()));
+    /// ```
+    pub fn to_toml(&self) -> String {
+        let quoted = |text: &str| toml::Value::String(text.to_string()).to_string();
+        let mut text = format!(
+            "precision = {}\ndisplay_format = {}\nangle_mode = {}\ntheme = {}\n",
+            self.precision,
+            quoted(self.display_format.name()),
+            quoted(angle_mode_name(self.angle_mode)),
+            quoted(&theme_name(&self.theme)),
+        );
+        if let Some(path) = &self.keymap {
+            text.push_str(&format!("keymap = {}\n", quoted(&path.to_string_lossy())));
+        }
+        text.push_str(&format!(
+            "decimal_separator = {}\nlocale = {}\n",
+            quoted(self.decimal_separator.name()),
+            quoted(self.locale.code())
+        ));
+        text
+    }
+
+    /// Loads the settings file; a missing file gives the defaults without warnings.
+    pub fn load(path: &Path) -> (Self, Vec<SettingsWarning>) {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => (Self::default(), Vec::new()),
+            Err(error) => (
+                Self::default(),
+                vec![SettingsWarning::Unreadable(error.to_string())],
+            ),
+        }
+    }
+
+    /// Saves the settings, creating the config directory if needed.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_toml())
+    }
+}
+
+fn angle_mode_name(mode: AngleMode) -> &'static str {
+    match mode {
+        AngleMode::Degrees => "degrees",
+        AngleMode::Radians => "radians",
+    }
+}
+
+fn angle_mode_from_name(name: &str) -> Option<AngleMode> {
+    match name {
+        "degrees" => Some(AngleMode::Degrees),
+        "radians" => Some(AngleMode::Radians),
+        _ => None,
+    }
+}
+
+fn theme_name(theme: &ThemeChoice) -> String {
+    match theme {
+        ThemeChoice::System => "system".to_string(),
+        ThemeChoice::Light => "light".to_string(),
+        ThemeChoice::Dark => "dark".to_string(),
+        ThemeChoice::HighContrast => "high-contrast".to_string(),
+        ThemeChoice::Custom(name) => name.clone(),
+    }
+}
+
+/// Built-in themes by their lowercase names; anything else names a custom theme.
+fn theme_from_name(name: &str) -> ThemeChoice {
+    ThemeChoice::BUILT_IN
+        .into_iter()
+        .find(|theme| theme_name(theme) == name)
+        .unwrap_or_else(|| ThemeChoice::Custom(name.to_string()))
+}
+
+fn keymap_from_str(path: &str) -> Option<PathBuf> {
+    let path = path.trim();
+    (!path.is_empty()).then(|| PathBuf::from(path))
+}
+
+/// Location of the settings file in the user config directory.
+pub fn settings_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("rust-calculator").join("settings.toml"))
+}
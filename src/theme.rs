@@ -0,0 +1,333 @@
+use serde::Deserialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// An sRGB color. The library keeps its own color type so it stays free of GUI dependencies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[serde(try_from = "String")]
+#[cfg_attr(feature = "serde", serde(into = "String"))]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    /// Parses a `#RRGGBB` hex color.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::theme::Rgb;
+    ///
+    /// assert_eq!(Rgb::from_hex("#ff9500"), Ok(Rgb(255, 149, 0)));
+    /// assert!(Rgb::from_hex("orange").is_err());
+    /// ```
+    pub fn from_hex(hex: &str) -> Result<Self, String> {
+        let digits = hex
+            .strip_prefix('#')
+            .filter(|digits| digits.len() == 6 && digits.chars().all(|c| c.is_ascii_hexdigit()))
+            .ok_or_else(|| format!("invalid color '{}', expected #RRGGBB", hex))?;
+        let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).unwrap_or_default();
+        Ok(Rgb(channel(0), channel(2), channel(4)))
+    }
+}
+
+impl From<Rgb> for String {
+    fn from(Rgb(red, green, blue): Rgb) -> Self {
+        format!("#{:02x}{:02x}{:02x}", red, green, blue)
+    }
+}
+
+impl TryFrom<String> for Rgb {
+    type Error = String;
+
+    fn try_from(hex: String) -> Result<Self, Self::Error> {
+        Self::from_hex(&hex)
+    }
+}
+
+/// Colors used by the GUI.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Palette {
+    /// Whether the palette is dark, which selects the base style of stock widgets
+    pub dark: bool,
+    /// Window background
+    pub background: Rgb,
+    /// Display and key label text
+    pub text: Rgb,
+    /// Digit keys
+    pub number_key: Rgb,
+    /// Digit keys while pressed
+    pub number_key_pressed: Rgb,
+    /// Operator keys
+    pub operator_key: Rgb,
+    /// Operator keys while pressed
+    pub operator_key_pressed: Rgb,
+    /// Label text on operator keys
+    pub operator_text: Rgb,
+    /// Function and scientific keys
+    pub function_key: Rgb,
+    /// Function and scientific keys while pressed
+    pub function_key_pressed: Rgb,
+    /// Key border, shown while pressed or always when `outlined`
+    pub border: Rgb,
+    /// Whether every key is drawn with a border
+    pub outlined: bool,
+    /// Notices and errors
+    pub error: Rgb,
+}
+
+impl Palette {
+    /// The original dark look of the calculator.
+    pub fn dark() -> Self {
+        Self {
+            dark: true,
+            background: Rgb(0x2B, 0x2D, 0x31),
+            text: Rgb(255, 255, 255),
+            number_key: Rgb(44, 44, 46),
+            number_key_pressed: Rgb(100, 100, 102),
+            operator_key: Rgb(255, 149, 0),
+            operator_key_pressed: Rgb(255, 180, 50),
+            operator_text: Rgb(255, 255, 255),
+            function_key: Rgb(58, 58, 60),
+            function_key_pressed: Rgb(100, 100, 102),
+            border: Rgb(255, 255, 255),
+            outlined: false,
+            error: Rgb(255, 105, 97),
+        }
+    }
+
+    /// Light keys on a light background.
+    pub fn light() -> Self {
+        Self {
+            dark: false,
+            background: Rgb(242, 242, 247),
+            text: Rgb(28, 28, 30),
+            number_key: Rgb(255, 255, 255),
+            number_key_pressed: Rgb(209, 209, 214),
+            operator_key: Rgb(255, 149, 0),
+            operator_key_pressed: Rgb(255, 180, 50),
+            operator_text: Rgb(255, 255, 255),
+            function_key: Rgb(216, 216, 220),
+            function_key_pressed: Rgb(174, 174, 178),
+            border: Rgb(28, 28, 30),
+            outlined: false,
+            error: Rgb(215, 0, 21),
+        }
+    }
+
+    /// Black background, white outlines and yellow operators for maximum contrast.
+    pub fn high_contrast() -> Self {
+        Self {
+            dark: true,
+            background: Rgb(0, 0, 0),
+            text: Rgb(255, 255, 255),
+            number_key: Rgb(0, 0, 0),
+            number_key_pressed: Rgb(70, 70, 70),
+            operator_key: Rgb(255, 214, 0),
+            operator_key_pressed: Rgb(255, 240, 140),
+            operator_text: Rgb(0, 0, 0),
+            function_key: Rgb(0, 0, 0),
+            function_key_pressed: Rgb(70, 70, 70),
+            border: Rgb(255, 255, 255),
+            outlined: true,
+            error: Rgb(255, 80, 80),
+        }
+    }
+}
+
+/// A user-defined palette loaded from the theme file.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CustomTheme {
+    /// Name shown in the theme menu
+    pub name: String,
+    /// The resolved colors
+    pub palette: Palette,
+}
+
+/// The theme selected by the user.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ThemeChoice {
+    /// Light or dark, following the operating system setting
+    System,
+    /// Built-in light palette
+    Light,
+    /// Built-in dark palette
+    #[default]
+    Dark,
+    /// Built-in high-contrast palette
+    HighContrast,
+    /// A user-defined palette, by name
+    Custom(String),
+}
+
+impl ThemeChoice {
+    /// Built-in choices, in menu order.
+    pub const BUILT_IN: [ThemeChoice; 4] = [
+        ThemeChoice::System,
+        ThemeChoice::Light,
+        ThemeChoice::Dark,
+        ThemeChoice::HighContrast,
+    ];
+
+    /// Resolves the choice to a palette.
+    ///
+    /// `System` uses the dark or light palette depending on `system_dark`; an unknown
+    /// custom name falls back to the dark palette.
+    pub fn palette(&self, custom_themes: &[CustomTheme], system_dark: bool) -> Palette {
+        match self {
+            ThemeChoice::System if system_dark => Palette::dark(),
+            ThemeChoice::System | ThemeChoice::Light => Palette::light(),
+            ThemeChoice::Dark => Palette::dark(),
+            ThemeChoice::HighContrast => Palette::high_contrast(),
+            ThemeChoice::Custom(name) => custom_themes
+                .iter()
+                .find(|theme| &theme.name == name)
+                .map(|theme| theme.palette.clone())
+                .unwrap_or_else(Palette::dark),
+        }
+    }
+}
+
+impl fmt::Display for ThemeChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThemeChoice::System => write!(f, "System"),
+            ThemeChoice::Light => write!(f, "Light"),
+            ThemeChoice::Dark => write!(f, "Dark"),
+            ThemeChoice::HighContrast => write!(f, "High Contrast"),
+            ThemeChoice::Custom(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeFile {
+    #[serde(default)]
+    theme: Vec<ThemeSpec>,
+}
+
+/// A theme entry of the theme file: a base palette with optional color overrides.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThemeSpec {
+    name: String,
+    #[serde(default)]
+    base: Option<String>,
+    background: Option<Rgb>,
+    text: Option<Rgb>,
+    number_key: Option<Rgb>,
+    number_key_pressed: Option<Rgb>,
+    operator_key: Option<Rgb>,
+    operator_key_pressed: Option<Rgb>,
+    operator_text: Option<Rgb>,
+    function_key: Option<Rgb>,
+    function_key_pressed: Option<Rgb>,
+    border: Option<Rgb>,
+    outlined: Option<bool>,
+    error: Option<Rgb>,
+}
+
+impl ThemeSpec {
+    fn into_custom_theme(self) -> Result<CustomTheme, String> {
+        let mut palette = match self.base.as_deref().unwrap_or("dark") {
+            "dark" => Palette::dark(),
+            "light" => Palette::light(),
+            "high-contrast" => Palette::high_contrast(),
+            other => {
+                return Err(format!(
+                    "theme '{}': unknown base '{}', expected dark, light or high-contrast",
+                    self.name, other
+                ));
+            }
+        };
+
+        let overrides = [
+            (&mut palette.background, self.background),
+            (&mut palette.text, self.text),
+            (&mut palette.number_key, self.number_key),
+            (&mut palette.number_key_pressed, self.number_key_pressed),
+            (&mut palette.operator_key, self.operator_key),
+            (&mut palette.operator_key_pressed, self.operator_key_pressed),
+            (&mut palette.operator_text, self.operator_text),
+            (&mut palette.function_key, self.function_key),
+            (&mut palette.function_key_pressed, self.function_key_pressed),
+            (&mut palette.border, self.border),
+            (&mut palette.error, self.error),
+        ];
+        for (color, value) in overrides {
+            if let Some(value) = value {
+                *color = value;
+            }
+        }
+        if let Some(outlined) = self.outlined {
+            palette.outlined = outlined;
+        }
+
+        Ok(CustomTheme {
+            name: self.name,
+            palette,
+        })
+    }
+}
+
+/// Parses user-defined themes from TOML text.
+///
+/// Each `[[theme]]` table needs a `name`, may pick a `base` palette (`dark`, `light` or
+/// `high-contrast`, default `dark`) and overrides any palette color with a `#RRGGBB` value.
+///
+/// # Examples
+///
+/// ```
+/// use rust_calculator::theme::{Rgb, parse_custom_themes};
+///
+/// let themes = parse_custom_themes(
+///     r##"
+///     [[theme]]
+///     name = "Solarized"
+///     background = "#002b36"
+///     operator_key = "#b58900"
+///     "##,
+/// )
+/// .unwrap();
+/// assert_eq!(themes[0].name, "Solarized");
+/// assert_eq!(themes[0].palette.operator_key, Rgb(0xb5, 0x89, 0x00));
+/// ```
+pub fn parse_custom_themes(text: &str) -> Result<Vec<CustomTheme>, String> {
+    let file: ThemeFile = toml::from_str(text).map_err(|error| error.message().to_string())?;
+
+    let mut themes: Vec<CustomTheme> = Vec::new();
+    for mut spec in file.theme {
+        spec.name = spec.name.trim().to_string();
+        let name = spec.name.as_str();
+        if name.is_empty() {
+            return Err("theme name must not be empty".to_string());
+        }
+        let taken = ThemeChoice::BUILT_IN
+            .iter()
+            .any(|choice| choice.to_string().eq_ignore_ascii_case(name))
+            || themes.iter().any(|theme| theme.name == name);
+        if taken {
+            return Err(format!("theme name '{}' is already in use", name));
+        }
+        themes.push(spec.into_custom_theme()?);
+    }
+    Ok(themes)
+}
+
+/// Loads user-defined themes from a file; a missing file means no custom themes.
+pub fn load_custom_themes(path: &Path) -> Result<Vec<CustomTheme>, String> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => parse_custom_themes(&text),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+/// Location of the theme file in the user config directory.
+pub fn themes_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("rust-calculator").join("themes.toml"))
+}
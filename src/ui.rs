@@ -1,16 +1,84 @@
-use crate::calculator::{Calculator, Operation};
+use crate::calculator::{Calculator, CalculatorError, Operation};
+use crate::expression;
+use crate::focus::FocusDirection;
+use crate::graph::{Graph, Viewport};
+use crate::locale::{Locale, Text};
+use crate::rpn::RpnCalculator;
+use crate::scientific::{ScientificAction, ScientificKey};
+use crate::settings::{DecimalSeparator, Settings};
+use crate::theme::{CustomTheme, Palette, ThemeChoice};
+
+/// Input model used to interpret key presses.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum InputMode {
+    /// Infix expressions with operator precedence, evaluated on equals
+    #[default]
+    Algebraic,
+    /// Reverse Polish Notation with a visible stack; operators apply immediately
+    Rpn,
+}
+
+/// The two lines of the algebraic display, each formatted with the display rules.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DisplayLines {
+    /// The expression being typed, or after `=` the evaluated expression followed by `=`
+    pub expression: String,
+    /// The result after `=`, otherwise the live preview; empty when there is nothing to show
+    pub result: String,
+    /// Whether `result` holds the outcome of `=` rather than a preview
+    pub evaluated: bool,
+    /// Where the caret goes in `expression`, in characters; `None` while the cursor is
+    /// pinned to the end
+    pub caret: Option<usize>,
+}
 
 /// GUI state management for the calculator application.
 /// This struct manages UI-specific state that can be unit tested.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CalculatorUIState {
     /// The calculator logic instance
     pub calculator: Calculator,
     /// Previous display text length for scroll management
     pub previous_display_len: usize,
+    /// Tentative result of the expression being built, refreshed after every message
+    pub preview: Option<String>,
+    /// The expression evaluated by the last `=`, kept until the next input
+    pub last_evaluation: Option<String>,
+    /// Short notice for the user about the last message, e.g. why a paste was rejected
+    pub notice: Option<String>,
+    /// The active input model
+    pub mode: InputMode,
+    /// The RPN calculator used while `mode` is `InputMode::Rpn`
+    pub rpn: RpnCalculator,
+    /// Whether the scientific keypad panel is shown
+    pub scientific_panel: bool,
+    /// Whether the 2nd layer of the scientific keypad is active for the next key
+    pub second_function: bool,
+    /// The selected color theme
+    pub theme: ThemeChoice,
+    /// User-defined themes available for selection
+    pub custom_themes: Vec<CustomTheme>,
+    /// Whether the graph view is shown instead of the keypad
+    pub graph_view: bool,
+    /// Functions and visible region of the graph view
+    pub graph: Graph,
+    /// The user settings; angle mode and theme follow changes made outside the settings view
+    pub settings: Settings,
+    /// Whether the settings view is shown instead of the calculator or graph
+    pub settings_view: bool,
+    /// Keypad button with the keyboard focus as (row, column) of `keypad_grid`;
+    /// `None` while focus navigation is off
+    pub focus: Option<(usize, usize)>,
+    /// Whether the window is wider than tall, which puts the scientific panel beside the keypad
+    pub wide_window: bool,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum UIMessage {
     NumberPressed(u8),
     DecimalPressed,
@@ -20,6 +88,49 @@ pub enum UIMessage {
     BackspacePressed,
     PercentagePressed,
     SignTogglePressed,
+    OpenParenPressed,
+    CloseParenPressed,
+    DeletePressed,
+    CursorLeft,
+    CursorRight,
+    CursorHome,
+    CursorEnd,
+    PastePressed,
+    LoadExpression(String),
+    CopyResult,
+    ModeTogglePressed,
+    EnterPressed,
+    SwapPressed,
+    DropPressed,
+    RollDownPressed,
+    LastXPressed,
+    ScientificTogglePressed,
+    SecondPressed,
+    AngleModePressed,
+    ScientificKeyPressed(ScientificKey),
+    ThemeSelected(ThemeChoice),
+    NextTheme,
+    GraphTogglePressed,
+    GraphInputChanged(String),
+    /// Zoom the graph by a factor around a point in graph coordinates
+    GraphZoom {
+        factor: f64,
+        x: f64,
+        y: f64,
+    },
+    /// Move the graph by an offset in graph coordinates
+    GraphPan {
+        dx: f64,
+        dy: f64,
+    },
+    GraphResetPressed,
+    SettingsTogglePressed,
+    SettingsChanged(Settings),
+    FocusNext,
+    FocusPrevious,
+    FocusMove(FocusDirection),
+    FocusActivate,
+    FocusClear,
 }
 
 /// Result of processing a UI message, indicating if scrolling should occur.
@@ -29,6 +140,13 @@ pub enum MessageResult {
     NoScroll,
     /// Scrolling to end is needed
     ScrollToEnd,
+    /// Scrolling to keep the cursor visible is needed, with the cursor
+    /// position given as a fraction of the expression length (0.0 to 1.0)
+    ScrollToCursor(f32),
+    /// The given text should be written to the clipboard
+    CopyToClipboard(String),
+    /// The clipboard should be read and its text sent back as `UIMessage::LoadExpression`
+    ReadClipboard,
 }
 
 impl CalculatorUIState {
@@ -37,13 +155,238 @@ impl CalculatorUIState {
         Self {
             calculator: Calculator::new(),
             previous_display_len: 1,
+            preview: None,
+            last_evaluation: None,
+            notice: None,
+            mode: InputMode::Algebraic,
+            rpn: RpnCalculator::new(),
+            scientific_panel: false,
+            second_function: false,
+            theme: ThemeChoice::default(),
+            custom_themes: Vec::new(),
+            graph_view: false,
+            graph: Graph::default(),
+            settings: Settings::default(),
+            settings_view: false,
+            focus: None,
+            wide_window: false,
         }
     }
 
     /// Processes a UI message and returns whether scrolling should occur.
     /// This is the extracted logic from main.rs that can be unit tested.
     pub fn process_message(&mut self, message: UIMessage) -> MessageResult {
+        self.notice = None;
+        // Pasted numbers use the configured decimal separator
+        let message = match message {
+            UIMessage::LoadExpression(text)
+                if self.settings.decimal_separator == DecimalSeparator::Comma =>
+            {
+                UIMessage::LoadExpression(text.replace(',', "."))
+            }
+            message => message,
+        };
+        let result = match (message, self.mode) {
+            (UIMessage::ModeTogglePressed, _) => {
+                self.mode = match self.mode {
+                    InputMode::Algebraic => InputMode::Rpn,
+                    InputMode::Rpn => InputMode::Algebraic,
+                };
+                MessageResult::NoScroll
+            }
+            (UIMessage::ScientificTogglePressed, _) => {
+                self.scientific_panel = !self.scientific_panel;
+                self.second_function = false;
+                MessageResult::NoScroll
+            }
+            (UIMessage::SecondPressed, _) => {
+                self.second_function = !self.second_function;
+                MessageResult::NoScroll
+            }
+            (UIMessage::AngleModePressed, _) => {
+                self.calculator.angle_mode = self.calculator.angle_mode.toggled();
+                MessageResult::NoScroll
+            }
+            (UIMessage::PastePressed, _) => MessageResult::ReadClipboard,
+            (UIMessage::ThemeSelected(theme), _) => {
+                self.theme = theme;
+                MessageResult::NoScroll
+            }
+            (UIMessage::NextTheme, _) => {
+                let themes = self.available_themes();
+                let next = themes
+                    .iter()
+                    .position(|theme| *theme == self.theme)
+                    .map_or(0, |index| (index + 1) % themes.len());
+                self.theme = themes[next].clone();
+                MessageResult::NoScroll
+            }
+            (UIMessage::GraphTogglePressed, _) => {
+                self.graph_view = !self.graph_view;
+                MessageResult::NoScroll
+            }
+            (UIMessage::GraphInputChanged(input), _) => {
+                self.graph.set_input(&input);
+                MessageResult::NoScroll
+            }
+            (UIMessage::GraphZoom { factor, x, y }, _) => {
+                self.graph.viewport.zoom(factor, x, y);
+                MessageResult::NoScroll
+            }
+            (UIMessage::GraphPan { dx, dy }, _) => {
+                self.graph.viewport.pan(dx, dy);
+                MessageResult::NoScroll
+            }
+            (UIMessage::GraphResetPressed, _) => {
+                self.graph.viewport = Viewport::default();
+                MessageResult::NoScroll
+            }
+            (UIMessage::SettingsTogglePressed, _) => {
+                self.settings_view = !self.settings_view;
+                MessageResult::NoScroll
+            }
+            (UIMessage::SettingsChanged(settings), _) => {
+                self.apply_settings(settings);
+                MessageResult::NoScroll
+            }
+            (
+                message @ (UIMessage::FocusNext
+                | UIMessage::FocusPrevious
+                | UIMessage::FocusMove(_)
+                | UIMessage::FocusActivate
+                | UIMessage::FocusClear),
+                _,
+            ) => return self.apply_focus_message(message),
+            (message, InputMode::Algebraic) => self.apply_message(message),
+            (message, InputMode::Rpn) => self.apply_rpn_message(message),
+        };
+        self.settings.angle_mode = self.calculator.angle_mode;
+        self.settings.theme = self.theme.clone();
+        self.preview = self.preview_result();
+        result
+    }
+
+    /// Applies user settings to the calculators and the theme.
+    ///
+    /// A custom theme that isn't available falls back to the default theme, with a notice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::{AngleMode, CalculatorUIState, Settings, ThemeChoice};
+    ///
+    /// let mut ui_state = CalculatorUIState::new();
+    /// ui_state.apply_settings(Settings {
+    ///     angle_mode: AngleMode::Radians,
+    ///     theme: ThemeChoice::Custom("Missing".to_string()),
+    ///     ..Settings::default()
+    /// });
+    /// assert_eq!(ui_state.calculator.angle_mode, AngleMode::Radians);
+    /// assert_eq!(ui_state.theme, ThemeChoice::default());
+    /// assert!(ui_state.notice.is_some());
+    /// ```
+    pub fn apply_settings(&mut self, mut settings: Settings) {
+        if !self.available_themes().contains(&settings.theme) {
+            self.notice = Some(settings.locale.format(
+                Text::ThemeNotFound,
+                &[
+                    &settings.theme.to_string(),
+                    &ThemeChoice::default().to_string(),
+                ],
+            ));
+            settings.theme = ThemeChoice::default();
+        }
+        self.calculator.apply_settings(&settings);
+        self.rpn.number_format = settings.number_format();
+        self.theme = settings.theme.clone();
+        self.settings = settings;
+        self.preview = self.preview_result();
+    }
+
+    /// Applies a UI message to the RPN calculator.
+    /// The RPN layout shows the whole stack, so no scrolling is ever requested.
+    fn apply_rpn_message(&mut self, message: UIMessage) -> MessageResult {
+        let rpn = &mut self.rpn;
+        match message {
+            UIMessage::NumberPressed(digit) => rpn.handle_number_input(digit),
+            UIMessage::DecimalPressed => rpn.handle_decimal_input(),
+            UIMessage::OperationPressed(operation) => rpn.handle_operation_input(operation),
+            UIMessage::EqualsPressed | UIMessage::EnterPressed => rpn.handle_enter(),
+            UIMessage::ClearPressed => rpn.handle_clear_input(),
+            UIMessage::BackspacePressed => rpn.handle_backspace_input(),
+            UIMessage::PercentagePressed => rpn.handle_percentage_input(),
+            UIMessage::SignTogglePressed => rpn.handle_sign_toggle_input(),
+            UIMessage::SwapPressed => rpn.handle_swap(),
+            UIMessage::DropPressed | UIMessage::DeletePressed => rpn.handle_drop(),
+            UIMessage::RollDownPressed => rpn.handle_roll_down(),
+            UIMessage::LastXPressed => rpn.handle_last_x(),
+            UIMessage::ScientificKeyPressed(key) => {
+                let angle_mode = self.calculator.angle_mode;
+                match key.action(std::mem::take(&mut self.second_function)) {
+                    ScientificAction::Function(function) => {
+                        rpn.handle_unary_input(|x| function.apply(x, angle_mode))
+                    }
+                    ScientificAction::Constant(constant) => rpn.handle_constant_input(constant),
+                    ScientificAction::Power => rpn.handle_operation_input(Operation::Power),
+                    ScientificAction::RaiseTo(n) => {
+                        rpn.handle_unary_input(|x| expression::power(x, n as f64))
+                    }
+                    ScientificAction::Factorial => rpn.handle_unary_input(expression::factorial),
+                    ScientificAction::Exp => rpn.handle_unary_input(|x| Ok(x.exp())),
+                    ScientificAction::Exp10 => {
+                        rpn.handle_unary_input(|x| expression::power(10.0, x))
+                    }
+                }
+            }
+            UIMessage::LoadExpression(text) => {
+                let normalized = Calculator::normalize_input(&text);
+                match Calculator::safe_parse_number(&normalized) {
+                    Ok(_) => rpn.entry = normalized,
+                    Err(error) => self.notice = Some(paste_rejected(self.settings.locale, &error)),
+                }
+            }
+            UIMessage::CopyResult => {
+                if let Some(x) = rpn.x_value() {
+                    return MessageResult::CopyToClipboard(x.to_string());
+                }
+            }
+            // Parentheses and cursor editing have no meaning in RPN
+            UIMessage::OpenParenPressed
+            | UIMessage::CloseParenPressed
+            | UIMessage::CursorLeft
+            | UIMessage::CursorRight
+            | UIMessage::CursorHome
+            | UIMessage::CursorEnd => {}
+            // Handled in process_message for both modes
+            UIMessage::ModeTogglePressed
+            | UIMessage::ScientificTogglePressed
+            | UIMessage::SecondPressed
+            | UIMessage::AngleModePressed
+            | UIMessage::PastePressed
+            | UIMessage::ThemeSelected(_)
+            | UIMessage::NextTheme
+            | UIMessage::GraphTogglePressed
+            | UIMessage::GraphInputChanged(_)
+            | UIMessage::GraphZoom { .. }
+            | UIMessage::GraphPan { .. }
+            | UIMessage::GraphResetPressed
+            | UIMessage::SettingsTogglePressed
+            | UIMessage::SettingsChanged(_)
+            | UIMessage::FocusNext
+            | UIMessage::FocusPrevious
+            | UIMessage::FocusMove(_)
+            | UIMessage::FocusActivate
+            | UIMessage::FocusClear => {}
+        }
+        MessageResult::NoScroll
+    }
+
+    /// Applies a UI message to the calculator and returns whether scrolling should occur.
+    fn apply_message(&mut self, message: UIMessage) -> MessageResult {
         let old_len = self.calculator.expression.len();
+        if message != UIMessage::CopyResult {
+            self.last_evaluation = None;
+        }
 
         match message {
             UIMessage::NumberPressed(digit) => {
@@ -56,7 +399,11 @@ impl CalculatorUIState {
                 self.calculator.handle_operation_input(operation);
             }
             UIMessage::EqualsPressed => {
+                let expression = Calculator::with_closed_parens(&self.calculator.expression);
                 self.calculator.handle_equals_input();
+                if self.calculator.error.is_none() {
+                    self.last_evaluation = Some(expression);
+                }
             }
             UIMessage::ClearPressed => {
                 self.calculator.handle_clear_input();
@@ -70,18 +417,219 @@ impl CalculatorUIState {
             UIMessage::SignTogglePressed => {
                 self.calculator.handle_sign_toggle_input();
             }
+            UIMessage::OpenParenPressed => {
+                self.calculator.handle_open_paren_input();
+            }
+            UIMessage::CloseParenPressed => {
+                self.calculator.handle_close_paren_input();
+            }
+            UIMessage::DeletePressed => {
+                self.calculator.handle_delete_input();
+            }
+            UIMessage::CursorLeft => {
+                self.calculator.handle_cursor_left();
+            }
+            UIMessage::CursorRight => {
+                self.calculator.handle_cursor_right();
+            }
+            UIMessage::CursorHome => {
+                self.calculator.handle_cursor_home();
+            }
+            UIMessage::CursorEnd => {
+                self.calculator.handle_cursor_end();
+                return MessageResult::ScrollToEnd;
+            }
+            UIMessage::LoadExpression(text) => {
+                if let Err(error) = self.calculator.load_expression(&text) {
+                    self.notice = Some(paste_rejected(self.settings.locale, &error));
+                    return MessageResult::NoScroll;
+                }
+                return MessageResult::ScrollToEnd;
+            }
+            UIMessage::CopyResult => {
+                return match self.copy_text() {
+                    Some(text) => MessageResult::CopyToClipboard(text),
+                    None => MessageResult::NoScroll,
+                };
+            }
+            UIMessage::ScientificKeyPressed(key) => {
+                let calculator = &mut self.calculator;
+                match key.action(std::mem::take(&mut self.second_function)) {
+                    ScientificAction::Function(function) => {
+                        calculator.handle_function_input(function)
+                    }
+                    ScientificAction::Constant(constant) => {
+                        calculator.handle_constant_input(constant)
+                    }
+                    ScientificAction::Power => calculator.handle_operation_input(Operation::Power),
+                    ScientificAction::RaiseTo(n) => {
+                        calculator.handle_postfix_input(&format!("^({})", n))
+                    }
+                    ScientificAction::Factorial => calculator.handle_postfix_input("!"),
+                    ScientificAction::Exp => calculator.handle_prefix_input("e^("),
+                    ScientificAction::Exp10 => calculator.handle_prefix_input("10^("),
+                }
+            }
+            // Handled in process_message for both modes
+            UIMessage::ModeTogglePressed
+            | UIMessage::ScientificTogglePressed
+            | UIMessage::SecondPressed
+            | UIMessage::AngleModePressed
+            | UIMessage::PastePressed
+            | UIMessage::ThemeSelected(_)
+            | UIMessage::NextTheme
+            | UIMessage::GraphTogglePressed
+            | UIMessage::GraphInputChanged(_)
+            | UIMessage::GraphZoom { .. }
+            | UIMessage::GraphPan { .. }
+            | UIMessage::GraphResetPressed
+            | UIMessage::SettingsTogglePressed
+            | UIMessage::SettingsChanged(_)
+            | UIMessage::FocusNext
+            | UIMessage::FocusPrevious
+            | UIMessage::FocusMove(_)
+            | UIMessage::FocusActivate
+            | UIMessage::FocusClear => {
+                return MessageResult::NoScroll;
+            }
+            // Stack operations only apply to RPN mode
+            UIMessage::EnterPressed
+            | UIMessage::SwapPressed
+            | UIMessage::DropPressed
+            | UIMessage::RollDownPressed
+            | UIMessage::LastXPressed => {
+                return MessageResult::NoScroll;
+            }
         }
 
         let new_len = self.calculator.expression.len();
 
-        // Auto-scroll only when content grows (most natural UX)
-        if new_len > old_len {
+        // Keep the caret visible while editing inside the expression,
+        // otherwise auto-scroll only when content grows (most natural UX)
+        if self.calculator.cursor.is_some() {
+            MessageResult::ScrollToCursor(self.calculator.cursor_scroll_fraction())
+        } else if new_len > old_len {
             MessageResult::ScrollToEnd
         } else {
             MessageResult::NoScroll
         }
     }
 
+    /// Computes the tentative result of the current expression without changing any state.
+    ///
+    /// Trailing operators and opening parentheses are ignored and unclosed parentheses are
+    /// closed, so the preview stays available while the expression is being typed.
+    /// Returns `None` when there is nothing worth previewing (a plain number, a finished
+    /// result or an error) or when the expression can't be evaluated yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::CalculatorUIState;
+    ///
+    /// let mut ui_state = CalculatorUIState::new();
+    /// ui_state.calculator.expression = "12x(3+".to_string();
+    /// assert_eq!(ui_state.preview_result(), Some("36".to_string()));
+    /// ```
+    pub fn preview_result(&self) -> Option<String> {
+        if self.mode == InputMode::Rpn {
+            return None;
+        }
+        let expression = self
+            .calculator
+            .expression
+            .trim_end_matches(|c| "+-x÷^(".contains(c));
+
+        // A lone number (including a previous result) previews as itself, so stay quiet
+        if expression.parse::<f64>().is_ok() {
+            return None;
+        }
+
+        self.calculator
+            .evaluate(&Calculator::with_closed_parens(expression))
+            .ok()
+            .map(|value| self.calculator.format_value(value))
+    }
+
+    /// Returns the expression line and the result line of the algebraic display.
+    ///
+    /// While typing, the expression line shows the expression, with the caret position
+    /// while the cursor is inside it, and the result line the live preview. After `=` the expression line shows what was
+    /// computed, followed by `=`, above the result.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::{CalculatorUIState, Operation, UIMessage};
+    ///
+    /// let mut ui_state = CalculatorUIState::new();
+    /// ui_state.process_message(UIMessage::NumberPressed(1));
+    /// ui_state.process_message(UIMessage::NumberPressed(2));
+    /// ui_state.process_message(UIMessage::OperationPressed(Operation::Multiply));
+    /// ui_state.process_message(UIMessage::OpenParenPressed);
+    /// ui_state.process_message(UIMessage::NumberPressed(3));
+    /// ui_state.process_message(UIMessage::OperationPressed(Operation::Add));
+    /// ui_state.process_message(UIMessage::NumberPressed(4));
+    /// assert_eq!(ui_state.display_lines().expression, "12x(3+4");
+    /// assert_eq!(ui_state.display_lines().result, "84");
+    ///
+    /// ui_state.process_message(UIMessage::EqualsPressed);
+    /// let lines = ui_state.display_lines();
+    /// assert_eq!(lines.expression, "12x(3+4)=");
+    /// assert_eq!(lines.result, "84");
+    /// assert!(lines.evaluated);
+    /// ```
+    pub fn display_lines(&self) -> DisplayLines {
+        let separator = self.settings.decimal_separator;
+        match &self.last_evaluation {
+            Some(expression) => DisplayLines {
+                expression: separator.localize(&format!(
+                    "{}=",
+                    self.calculator.format_expression(expression)
+                )),
+                result: separator.localize(&self.calculator.display),
+                evaluated: true,
+                caret: None,
+            },
+            None => {
+                let (expression, caret) = self.calculator.display_string_and_caret();
+                DisplayLines {
+                    expression: separator.localize(&expression),
+                    result: separator.localize(&self.preview.clone().unwrap_or_default()),
+                    evaluated: false,
+                    caret,
+                }
+            }
+        }
+    }
+
+    /// Returns the current value at full precision for copying to the clipboard.
+    /// Unclosed parentheses are closed first; returns `None` if the expression can't be evaluated.
+    pub fn copy_text(&self) -> Option<String> {
+        self.calculator
+            .evaluate(&Calculator::with_closed_parens(&self.calculator.expression))
+            .ok()
+            .map(|value| value.to_string())
+    }
+
+    /// Returns the built-in themes followed by the user-defined ones, in menu order.
+    pub fn available_themes(&self) -> Vec<ThemeChoice> {
+        ThemeChoice::BUILT_IN
+            .into_iter()
+            .chain(
+                self.custom_themes
+                    .iter()
+                    .map(|theme| ThemeChoice::Custom(theme.name.clone())),
+            )
+            .collect()
+    }
+
+    /// Returns the palette of the selected theme.
+    /// `system_dark` tells whether the operating system prefers a dark appearance.
+    pub fn palette(&self, system_dark: bool) -> Palette {
+        self.theme.palette(&self.custom_themes, system_dark)
+    }
+
     /// Determines if scrolling should occur based on expression length changes.
     /// This logic is extracted and can be unit tested.
     pub fn should_scroll(&self, old_expression_len: usize, new_expression_len: usize) -> bool {
@@ -94,3 +642,8 @@ impl Default for CalculatorUIState {
         Self::new()
     }
 }
+
+/// Notice for a pasted text that can't be loaded.
+fn paste_rejected(locale: Locale, error: &CalculatorError) -> String {
+    locale.format(Text::PasteRejected, &[&locale.error_message(error)])
+}
@@ -0,0 +1,263 @@
+use crate::expression::{
+    self, AngleMode, CalculatorError, Constant, ExpressionError, Function, TokenKind,
+};
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// Named values that expressions can use, e.g. `rate` in `100 x rate`.
+///
+/// # Examples
+///
+/// ```
+/// use rust_calculator::AngleMode;
+/// use rust_calculator::variables::Variables;
+///
+/// let mut variables = Variables::new();
+/// variables.set("rate", 1.5).unwrap();
+/// assert_eq!(variables.evaluate("100 x rate", AngleMode::Degrees), Ok(150.0));
+/// assert!(variables.set("sin", 1.0).is_err());
+/// assert!(variables.set("rate", f64::NAN).is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Variables {
+    values: BTreeMap<String, f64>,
+}
+
+impl Variables {
+    /// Creates an empty set of variables.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Value of a variable.
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.values.get(name).copied()
+    }
+
+    /// Sets a variable, creating it if needed. Fails for names that `is_variable_name`
+    /// rejects, and for values that aren't finite.
+    pub fn set(&mut self, name: &str, value: f64) -> Result<(), CalculatorError> {
+        if !is_variable_name(name) {
+            return Err(CalculatorError::InvalidVariableName(name.to_string()));
+        }
+        if !value.is_finite() {
+            return Err(CalculatorError::NumberOutOfRange(value.to_string()));
+        }
+        self.values.insert(name.to_string(), value);
+        Ok(())
+    }
+
+    /// The variables and their values, sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, f64)> {
+        self.values
+            .iter()
+            .map(|(name, value)| (name.as_str(), *value))
+    }
+
+    /// Evaluates an expression that may use the variables. Error positions refer to
+    /// `expression` as written, not to the values put in for the variables.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::AngleMode;
+    /// use rust_calculator::variables::Variables;
+    ///
+    /// let mut variables = Variables::new();
+    /// variables.set("a", 4.0).unwrap();
+    /// assert_eq!(variables.evaluate("√(a)+a", AngleMode::Degrees), Ok(6.0));
+    /// assert_eq!(variables.evaluate("a÷(a-4)", AngleMode::Degrees).unwrap_err().span, 3..6);
+    /// ```
+    pub fn evaluate(
+        &self,
+        expression: &str,
+        angle_mode: AngleMode,
+    ) -> Result<f64, ExpressionError> {
+        let (substituted, origins) = self.substitute(expression);
+        expression::evaluate(&substituted, angle_mode).map_err(|error| {
+            let span = original_span(expression, &origins, error.span);
+            ExpressionError::new(error.kind, span)
+        })
+    }
+
+    /// Splits an expression into its tokens like `expression::tokens`, reading the names
+    /// of variables with a value as `Variable` tokens. Positions refer to `expression` as
+    /// written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::expression::TokenKind;
+    /// use rust_calculator::variables::Variables;
+    ///
+    /// let mut variables = Variables::new();
+    /// variables.set("rate", 2.0).unwrap();
+    /// assert_eq!(
+    ///     variables.tokens("rate x 3").unwrap(),
+    ///     [
+    ///         (TokenKind::Variable(2.0), 0..4),
+    ///         (TokenKind::Operator, 5..6),
+    ///         (TokenKind::Number(3.0), 7..8),
+    ///     ]
+    /// );
+    /// ```
+    pub fn tokens(
+        &self,
+        expression: &str,
+    ) -> Result<Vec<(TokenKind, Range<usize>)>, ExpressionError> {
+        let (substituted, origins) = self.substitute(expression);
+        let chars: Vec<char> = expression.chars().collect();
+        let tokens = expression::tokens(&substituted).map_err(|error| {
+            let span = original_span(expression, &origins, error.span);
+            ExpressionError::new(error.kind, span)
+        })?;
+
+        let mut result: Vec<(TokenKind, Range<usize>)> = Vec::new();
+        for (kind, span) in tokens {
+            let span = original_span(expression, &origins, span);
+            let name: String = chars[span.clone()].iter().collect();
+            match self.get(&name) {
+                // A value is put in as `(value)`: one token stands for all of them
+                Some(_) if result.last().is_some_and(|(_, last)| *last == span) => {}
+                Some(value) => result.push((TokenKind::Variable(value), span)),
+                None => result.push((kind, span)),
+            }
+        }
+        Ok(result)
+    }
+
+    /// Checks that an expression is well formed without evaluating it, like
+    /// `expression::check_syntax`. Variables without a value are invalid characters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_calculator::variables::Variables;
+    ///
+    /// let mut variables = Variables::new();
+    /// variables.set("a", 0.0).unwrap();
+    /// assert_eq!(variables.check_syntax("1÷a"), Ok(()));
+    /// assert_eq!(variables.check_syntax("2x(a+").unwrap_err().span, 2..3);
+    /// ```
+    pub fn check_syntax(&self, expression: &str) -> Result<(), ExpressionError> {
+        let (substituted, _) = self.substitute(expression);
+        expression::check_syntax(&substituted).map_err(|kind| {
+            // Evaluation locates most syntax errors, unless it fails on a value first
+            match self.evaluate(expression, AngleMode::Degrees) {
+                Err(error) if error.kind == kind => error,
+                _ => ExpressionError::new(kind, 0..expression.chars().count()),
+            }
+        })
+    }
+
+    /// Replaces variables with their parenthesized values. Also returns, for every
+    /// character of the result, the characters of `expression` it came from.
+    fn substitute(&self, expression: &str) -> (String, Vec<Range<usize>>) {
+        let chars: Vec<char> = expression.chars().collect();
+        let mut result = String::with_capacity(expression.len());
+        let mut origins = Vec::with_capacity(chars.len());
+        let mut copied = 0;
+
+        for word in words(&chars) {
+            for (i, &c) in chars.iter().enumerate().take(word.start).skip(copied) {
+                result.push(c);
+                origins.push(i..i + 1);
+            }
+            let name: String = chars[word.clone()].iter().collect();
+            let text = match self.get(&name) {
+                Some(value) => format!("({:e})", value),
+                None => name,
+            };
+            origins.extend(core::iter::repeat_n(word.clone(), text.chars().count()));
+            result.push_str(&text);
+            copied = word.end;
+        }
+        for (i, &c) in chars.iter().enumerate().skip(copied) {
+            result.push(c);
+            origins.push(i..i + 1);
+        }
+
+        (result, origins)
+    }
+}
+
+/// Maps a character range of a substituted expression back to `expression`, given the
+/// origins `Variables::substitute` returned.
+fn original_span(expression: &str, origins: &[Range<usize>], span: Range<usize>) -> Range<usize> {
+    let end = expression.chars().count();
+    let at = |index: usize| origins.get(index).cloned().unwrap_or(end..end);
+    if span.is_empty() {
+        at(span.start)
+    } else {
+        at(span.start).start..at(span.end - 1).end
+    }
+}
+
+/// The variables an expression uses, in the order they first appear.
+///
+/// # Examples
+///
+/// ```
+/// use rust_calculator::variables::names;
+///
+/// assert_eq!(names("sin(angle)x r + r^2 + e"), ["angle", "r"]);
+/// ```
+pub fn names(expression: &str) -> Vec<String> {
+    let chars: Vec<char> = expression.chars().collect();
+    let mut names: Vec<String> = Vec::new();
+    for word in words(&chars) {
+        let name: String = chars[word].iter().collect();
+        if is_variable_name(&name) && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Character ranges of the words in an expression that could be variables.
+fn words(chars: &[char]) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        // A letter right after a number is its exponent or an error for the calculator
+        let after_number = i > 0 && (chars[i - 1].is_ascii_digit() || chars[i - 1] == '.');
+        if !after_number && is_name_start(chars[i]) {
+            let start = i;
+            while i < chars.len() && (is_name_start(chars[i]) || chars[i].is_ascii_digit()) {
+                i += 1;
+            }
+            words.push(start..i);
+        } else {
+            i += 1;
+        }
+    }
+    words
+}
+
+/// Whether `name` can hold a variable: lowercase letters, digits and underscores, starting
+/// with a letter, and not a function or constant. `x` is left out, as the calculator reads
+/// it as multiplication.
+///
+/// # Examples
+///
+/// ```
+/// use rust_calculator::variables::is_variable_name;
+///
+/// assert!(is_variable_name("rate_2"));
+/// assert!(!is_variable_name("2rate"));
+/// assert!(!is_variable_name("sin"));
+/// assert!(!is_variable_name("max"));
+/// ```
+pub fn is_variable_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name.chars().all(|c| is_name_start(c) || c.is_ascii_digit())
+        && name != Constant::E.symbol()
+        && Function::from_name(name).is_none()
+}
+
+fn is_name_start(c: char) -> bool {
+    (c.is_ascii_lowercase() && c != 'x') || c == '_'
+}
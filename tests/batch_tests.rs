@@ -0,0 +1,174 @@
+#![cfg(feature = "std")]
+
+use rust_calculator::batch::{self, BatchOptions, Entry, OutputFormat};
+use rust_calculator::cli::{Command, EvalOptions, parse_args};
+use rust_calculator::settings::DisplayFormat;
+use std::io::Write;
+use std::process::{Command as Process, Stdio};
+
+const INPUT: &str = "# prices\n7+8*3\n\n  1/0\n2^0.5\n";
+
+fn run(input: &str, options: &BatchOptions) -> (usize, String) {
+    let mut output = Vec::new();
+    let failed = batch::run([input.as_bytes()], &mut output, options).unwrap();
+    (failed, String::from_utf8(output).unwrap())
+}
+
+fn options(output: OutputFormat) -> BatchOptions {
+    BatchOptions {
+        output,
+        ..BatchOptions::default()
+    }
+}
+
+#[test]
+fn test_plain_output_keeps_blank_and_comment_lines() {
+    assert_eq!(
+        run(INPUT, &options(OutputFormat::Plain)),
+        (
+            1,
+            "# prices\n31\n\nerror: Division by zero\n1.41421356\n".to_string()
+        )
+    );
+}
+
+#[test]
+fn test_csv_output() {
+    let (_, output) = run(INPUT, &options(OutputFormat::Csv));
+    assert_eq!(
+        output,
+        "line,expression,result,error\n\
+         1,# prices,,\n\
+         2,7+8*3,31,\n\
+         3,,,\n\
+         4,1/0,,Division by zero\n\
+         5,2^0.5,1.41421356,\n"
+    );
+
+    // Fields with separators or quotes are quoted
+    let (_, output) = run("# a, \"b\"\n", &options(OutputFormat::Csv));
+    assert!(output.ends_with("1,\"# a, \"\"b\"\"\",,\n"));
+}
+
+#[test]
+fn test_comments_are_written_as_they_were() {
+    let input = "  # indented
+# trailing  
+";
+    let (_, output) = run(input, &options(OutputFormat::Plain));
+    assert_eq!(output, input);
+    let (_, output) = run(input, &options(OutputFormat::JsonLines));
+    assert_eq!(
+        output,
+        "{\"line\":1,\"comment\":\"  # indented\"}\n{\"line\":2,\"comment\":\"# trailing  \"}\n"
+    );
+}
+
+#[test]
+fn test_json_lines_output() {
+    let (failed, output) = run(INPUT, &options(OutputFormat::JsonLines));
+    assert_eq!(failed, 1);
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            r##"{"line":1,"comment":"# prices"}"##,
+            r#"{"line":2,"expression":"7+8*3","result":31.0,"formatted":"31"}"#,
+            r#"{"line":3,"blank":true}"#,
+            r#"{"line":4,"expression":"1/0","error":{"kind":"division_by_zero","message":"Division by zero","start":2,"end":3}}"#,
+            r#"{"line":5,"expression":"2^0.5","result":1.4142135623730951,"formatted":"1.41421356"}"#,
+        ]
+    );
+}
+
+#[test]
+fn test_fail_fast_stops_at_the_first_error() {
+    let fail_fast = BatchOptions {
+        fail_fast: true,
+        ..BatchOptions::default()
+    };
+    assert_eq!(
+        run(INPUT, &fail_fast),
+        (1, "# prices\n31\n\nerror: Division by zero\n".to_string())
+    );
+}
+
+#[test]
+fn test_inputs_are_numbered_across_files() {
+    let mut output = Vec::new();
+    let inputs = ["1\n2".as_bytes(), "3\n".as_bytes()];
+    batch::run(inputs, &mut output, &options(OutputFormat::Csv)).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "line,expression,result,error\n1,1,1,\n2,2,2,\n3,3,3,\n"
+    );
+}
+
+#[test]
+fn test_entries_use_the_evaluation_options() {
+    let mut eval = EvalOptions::default();
+    eval.number_format.display_format = DisplayFormat::Scientific;
+    eval.number_format.precision = 2;
+    assert_eq!(
+        Entry::evaluate("1234", &eval),
+        Entry::Value {
+            expression: "1234".to_string(),
+            value: 1234.0,
+            formatted: "1.23e3".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_parse_batch_command() {
+    let parse = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+    assert_eq!(
+        parse(&["batch", "--output=jsonl", "--fail-fast", "a.txt", "-"]),
+        Ok(Command::Batch {
+            files: vec!["a.txt".to_string(), "-".to_string()],
+            options: BatchOptions {
+                output: OutputFormat::JsonLines,
+                fail_fast: true,
+                ..BatchOptions::default()
+            },
+        })
+    );
+    assert!(parse(&["batch", "-o", "xml"]).is_err());
+    assert!(parse(&["batch", "-e", "1"]).is_err());
+    assert!(parse(&["batch", "-", "a.txt", "-"]).is_err());
+    // Batch options belong to the batch subcommand
+    assert!(parse(&["--fail-fast"]).is_err());
+    assert!(parse(&["repl", "notes.txt"]).is_err());
+}
+
+#[test]
+fn test_batch_exit_codes() {
+    let batch = |args: &[&str]| {
+        let mut child = Process::new(env!("CARGO_BIN_EXE_rust-calculator"))
+            .arg("batch")
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(INPUT.as_bytes())
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        (
+            output.status.code(),
+            String::from_utf8(output.stdout).unwrap(),
+        )
+    };
+
+    let (code, stdout) = batch(&[]);
+    assert_eq!(code, Some(0));
+    assert_eq!(stdout.lines().count(), 5);
+
+    let (code, stdout) = batch(&["--fail-fast"]);
+    assert_eq!(code, Some(1));
+    assert_eq!(stdout.lines().count(), 4);
+}
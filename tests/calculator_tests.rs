@@ -1,4 +1,6 @@
-use rust_calculator::{Calculator, CalculatorError, Operation};
+#![cfg(feature = "std")]
+
+use rust_calculator::{AngleMode, Calculator, CalculatorError, Function, Operation};
 
 #[test]
 fn test_new_calculator() {
@@ -128,7 +130,7 @@ fn test_calculate_division_by_zero() {
     let calc = Calculator::new();
     assert_eq!(
         calc.calculate(Operation::Divide, 10.0, 0.0),
-        Err("Error".to_string())
+        Err(CalculatorError::DivisionByZero)
     );
 }
 
@@ -320,7 +322,7 @@ fn test_calculate_operations() {
     // Test division by zero
     assert_eq!(
         calc.calculate(Operation::Divide, 10.0, 0.0),
-        Err("Error".to_string())
+        Err(CalculatorError::DivisionByZero)
     );
 }
 
@@ -458,7 +460,7 @@ fn test_evaluate_unary_minus_complex() {
 
     // Test unary minus at start of expression
     assert_eq!(calc.evaluate("-5"), Ok(-5.0));
-    assert_eq!(calc.evaluate("-3.14"), Ok(-3.14));
+    assert_eq!(calc.evaluate("-2.75"), Ok(-2.75));
 
     // Test unary minus in complex expressions with parentheses
     assert_eq!(calc.evaluate("(-2)+3"), Ok(1.0));
@@ -610,3 +612,133 @@ fn test_specific_unary_minus_case() {
     println!("5+(-3) = {:?}", result);
     assert_eq!(result, Ok(2.0));
 }
+
+#[test]
+fn test_normalize_input() {
+    assert_eq!(Calculator::normalize_input("12 + 3"), "12+3");
+    assert_eq!(Calculator::normalize_input("2*3/4"), "2x3÷4");
+    assert_eq!(Calculator::normalize_input("2×3X4"), "2x3x4");
+    assert_eq!(Calculator::normalize_input("−5"), "-5");
+    assert_eq!(Calculator::normalize_input("1,234,567"), "1234567");
+    assert_eq!(Calculator::normalize_input("\t42\n"), "42");
+}
+
+#[test]
+fn test_evaluate_power_is_right_associative() {
+    let calc = Calculator::new();
+    assert_eq!(calc.evaluate("2^3"), Ok(8.0));
+    assert_eq!(calc.evaluate("2^3^2"), Ok(512.0));
+    assert_eq!(calc.evaluate("2x3^2"), Ok(18.0));
+    assert_eq!(calc.evaluate("-2^2"), Ok(-4.0));
+}
+
+#[test]
+fn test_evaluate_factorial() {
+    let calc = Calculator::new();
+    assert_eq!(calc.evaluate("5!"), Ok(120.0));
+    assert_eq!(calc.evaluate("3!+1"), Ok(7.0));
+    assert_eq!(
+        calc.evaluate("2.5!"),
+        Err(CalculatorError::DomainError("2.5!".to_string()).to_string())
+    );
+    assert!(
+        calc.evaluate("171!")
+            .unwrap_err()
+            .starts_with("Number out of range")
+    );
+}
+
+#[test]
+fn test_evaluate_functions_and_constants() {
+    let calc = Calculator::new();
+    assert_eq!(calc.evaluate("√(16)"), Ok(4.0));
+    assert_eq!(calc.evaluate("∛(27)"), Ok(3.0));
+    assert_eq!(calc.evaluate("abs(-3)"), Ok(3.0));
+    assert_eq!(calc.evaluate("log(1000)"), Ok(3.0));
+    assert_eq!(calc.evaluate("ln(e)"), Ok(1.0));
+    assert_eq!(calc.evaluate("2xπ"), Ok(2.0 * std::f64::consts::PI));
+}
+
+#[test]
+fn test_trig_follows_angle_mode() {
+    let mut calc = Calculator::new();
+    assert_eq!(calc.angle_mode, AngleMode::Degrees);
+    assert_eq!(calc.evaluate("sin(90)"), Ok(1.0));
+    assert_eq!(calc.evaluate("cos(90)"), Ok(0.0));
+    assert_eq!(calc.evaluate("asin(1)"), Ok(90.0));
+
+    calc.angle_mode = AngleMode::Radians;
+    assert_eq!(calc.evaluate("cos(0)"), Ok(1.0));
+    assert!((calc.evaluate("sin(π÷2)").unwrap() - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_function_domain_errors() {
+    let calc = Calculator::new();
+    for expr in ["√(-1)", "ln(0)", "log(-5)", "asin(2)", "tan(90)"] {
+        assert!(
+            calc.evaluate(expr).unwrap_err().starts_with("Domain error"),
+            "{expr} should be a domain error"
+        );
+    }
+    assert_eq!(
+        Function::Sqrt.apply(-4.0, AngleMode::Degrees),
+        Err(CalculatorError::DomainError("√(-4)".to_string()))
+    );
+}
+
+#[test]
+fn test_validate_input_accepts_function_names_only_before_paren() {
+    assert!(Calculator::validate_input("sin(30)+cos(60)").is_ok());
+    assert!(Calculator::validate_input("2^3!").is_ok());
+    assert!(Calculator::validate_input("πx2").is_ok());
+    assert!(Calculator::validate_input("sin").is_err());
+    assert!(Calculator::validate_input("abc").is_err());
+}
+
+#[test]
+fn test_check_syntax() {
+    assert_eq!(Calculator::check_syntax("7+8x3"), Ok(()));
+    assert_eq!(Calculator::check_syntax("-(2)!"), Ok(()));
+    // Value errors only show up when evaluating
+    assert_eq!(Calculator::check_syntax("5÷0"), Ok(()));
+    assert_eq!(Calculator::check_syntax("√(-4)"), Ok(()));
+
+    assert!(Calculator::check_syntax("").is_err());
+    assert!(Calculator::check_syntax("2+").is_err());
+    assert!(Calculator::check_syntax("(2+3").is_err());
+    assert!(Calculator::check_syntax("2+3)").is_err());
+    assert!(Calculator::check_syntax("2a").is_err());
+}
+
+#[test]
+fn test_error_spans() {
+    let calc = Calculator::new();
+    let span = |expr: &str| calc.evaluate_spanned(expr).unwrap_err().span;
+
+    // Second of two operators
+    assert_eq!(span("5+x3"), 2..3);
+    assert_eq!(span("5+-"), 2..3);
+    // Unmatched parentheses
+    assert_eq!(span("(2+3"), 0..1);
+    assert_eq!(span("((2+3)"), 0..1);
+    assert_eq!(span("2+3)"), 3..4);
+    // Divisor of a division by zero, without its parentheses
+    assert_eq!(span("1+4÷(2-2)"), 5..8);
+    // Whole function call or operation outside its domain
+    assert_eq!(span("2xln(0)"), 2..7);
+    assert_eq!(span("3+(-2)!"), 3..7);
+    // Operand with no operator joining it
+    assert_eq!(span("2(3)"), 2..3);
+    // Bad characters and names
+    assert_eq!(span("2+a"), 2..3);
+    assert_eq!(span("1+2#"), 3..4);
+    assert_eq!(span("1.2.3+1"), 0..4);
+    // Overflow of the whole result
+    assert_eq!(span("1e100x1e100"), 0..11);
+
+    // The message matches evaluate
+    let error = calc.evaluate_spanned("5÷0").unwrap_err();
+    assert_eq!(error.kind, CalculatorError::DivisionByZero);
+    assert_eq!(error.to_string(), calc.evaluate("5÷0").unwrap_err());
+}
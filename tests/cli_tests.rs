@@ -0,0 +1,105 @@
+#![cfg(feature = "std")]
+
+use rust_calculator::AngleMode;
+use rust_calculator::cli::{Command, EvalOptions, parse_args};
+use rust_calculator::settings::{DisplayFormat, NumberFormat};
+use std::process::Command as Process;
+
+fn args(args: &[&str]) -> Result<Command, String> {
+    parse_args(args.iter().map(|arg| arg.to_string()))
+}
+
+fn run(args: &[&str]) -> (Option<i32>, String, String) {
+    let output = Process::new(env!("CARGO_BIN_EXE_rust-calculator"))
+        .args(args)
+        .output()
+        .unwrap();
+    (
+        output.status.code(),
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn test_parse_eval_options() {
+    assert_eq!(
+        args(&[
+            "-p",
+            "3",
+            "--format=scientific",
+            "--angle",
+            "radians",
+            "-e",
+            "2/3"
+        ]),
+        Ok(Command::Evaluate {
+            expression: "2/3".to_string(),
+            options: EvalOptions {
+                number_format: NumberFormat {
+                    precision: 3,
+                    display_format: DisplayFormat::Scientific,
+                },
+                angle_mode: AngleMode::Radians,
+            },
+        })
+    );
+    // An expression may start with a minus sign
+    assert!(matches!(
+        args(&["--eval", "-5+3"]),
+        Ok(Command::Evaluate { expression, .. }) if expression == "-5+3"
+    ));
+    assert_eq!(args(&["-h"]), Ok(Command::Help));
+}
+
+#[test]
+fn test_parse_errors() {
+    assert!(args(&["-e"]).is_err());
+    assert!(args(&["-p", "16", "-e", "1"]).is_err());
+    assert!(args(&["-f", "binary", "-e", "1"]).is_err());
+    assert!(args(&["--angle", "gradians", "-e", "1"]).is_err());
+    assert!(args(&["--frobnicate"]).is_err());
+    // Output options without an expression are a mistake, not a request for the window
+    assert!(args(&["-p", "2"]).is_err());
+}
+
+#[test]
+fn test_evaluate_formats_results() {
+    let options = EvalOptions::default();
+    assert_eq!(options.evaluate("7+8*3").unwrap(), "31");
+    assert_eq!(options.evaluate("sin(90)").unwrap(), "1");
+    assert_eq!(options.evaluate("2^40").unwrap(), "1.0995e12");
+
+    let radians = EvalOptions {
+        angle_mode: AngleMode::Radians,
+        ..options
+    };
+    assert_eq!(radians.evaluate("cos(0)").unwrap(), "1");
+    assert_eq!(radians.evaluate("sin(90)").unwrap(), "0.89399666");
+    assert_eq!(options.evaluate("2+*3").unwrap_err().span, 2..3);
+}
+
+#[test]
+fn test_one_shot_evaluation() {
+    assert_eq!(
+        run(&["-e", "7+8*3"]),
+        (Some(0), "31\n".to_string(), String::new())
+    );
+    assert_eq!(
+        run(&["-e", "1/3", "--precision", "3", "--format", "fixed"]),
+        (Some(0), "0.333\n".to_string(), String::new())
+    );
+}
+
+#[test]
+fn test_one_shot_errors() {
+    let (code, stdout, stderr) = run(&["-e", "1/0"]);
+    assert_eq!(code, Some(1));
+    assert!(stdout.is_empty());
+    assert!(stderr.contains("Division by zero"));
+
+    let (code, stdout, stderr) = run(&["--bogus"]);
+    assert_eq!(code, Some(2));
+    assert!(stdout.is_empty());
+    assert!(stderr.contains("Usage:"));
+}
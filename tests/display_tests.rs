@@ -1,3 +1,5 @@
+#![cfg(feature = "std")]
+
 use rust_calculator::Calculator;
 
 #[test]
@@ -139,3 +141,76 @@ fn test_format_large_numbers() {
     // Test medium number not formatted
     assert_eq!(calc.format_large_numbers("123456789"), "123456789");
 }
+
+#[test]
+fn test_display_string_and_caret_at_end() {
+    let mut calc = Calculator::new();
+    calc.expression = "12+3".to_string();
+    assert_eq!(calc.display_string_and_caret(), ("12+3".to_string(), None));
+}
+
+#[test]
+fn test_display_string_and_caret_inside() {
+    let mut calc = Calculator::new();
+    calc.expression = "12÷3".to_string();
+    calc.cursor = Some(2);
+    assert_eq!(
+        calc.display_string_and_caret(),
+        ("12÷3".to_string(), Some(2))
+    );
+}
+
+#[test]
+fn test_display_string_and_caret_keeps_formatting() {
+    let mut calc = Calculator::new();
+    calc.expression = "4567890123+-3".to_string();
+
+    // Before the operator, the long number collapses and the caret follows it
+    calc.cursor = Some(10);
+    assert_eq!(
+        calc.display_string_and_caret(),
+        ("4.6e9+(-3)".to_string(), Some(5))
+    );
+
+    // Inside the negative operand, after its parenthesis
+    calc.cursor = Some(12);
+    assert_eq!(
+        calc.display_string_and_caret(),
+        ("4.6e9+(-3)".to_string(), Some(8))
+    );
+
+    // The number being edited is shown as typed
+    calc.cursor = Some(3);
+    assert_eq!(
+        calc.display_string_and_caret(),
+        ("4567890123+(-3)".to_string(), Some(3))
+    );
+}
+
+#[test]
+fn test_cursor_scroll_fraction() {
+    let mut calc = Calculator::new();
+    calc.expression = "1234".to_string();
+    assert_eq!(calc.cursor_scroll_fraction(), 1.0);
+    calc.cursor = Some(1);
+    assert_eq!(calc.cursor_scroll_fraction(), 0.25);
+}
+
+#[test]
+fn test_ghost_parens() {
+    let mut calc = Calculator::new();
+    assert_eq!(calc.ghost_parens(), "");
+    calc.expression = "2x((3+4)".to_string();
+    assert_eq!(calc.ghost_parens(), ")");
+    calc.expression = "((".to_string();
+    assert_eq!(calc.ghost_parens(), "))");
+}
+
+#[test]
+fn test_format_result() {
+    assert_eq!(Calculator::format_result(5.0), "5");
+    assert_eq!(Calculator::format_result(0.5), "0.5");
+    assert_eq!(Calculator::format_result(1.0 / 3.0), "0.33333333");
+    assert_eq!(Calculator::format_result(1234567.0), "1.2346e6");
+    assert_eq!(Calculator::format_result(0.00001), "1.0000e-5");
+}
@@ -0,0 +1,105 @@
+// Runs with and without the `std` feature: `cargo test --no-default-features`
+use rust_calculator::expression::{
+    MAX_INPUT_LENGTH, check_syntax, evaluate, factorial, power, safe_parse_number,
+};
+use rust_calculator::{AngleMode, CalculatorError, Function, Locale, SyntaxError};
+
+const DEGREES: AngleMode = AngleMode::Degrees;
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-12,
+        "{} is not {}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn test_evaluate_with_precedence() {
+    assert_eq!(evaluate("7+8x3", DEGREES), Ok(31.0));
+    assert_eq!(evaluate("2^3^2", DEGREES), Ok(512.0));
+    assert_eq!(evaluate("-2^2", DEGREES), Ok(-4.0));
+    assert_eq!(evaluate("5!÷(2+3)", DEGREES), Ok(24.0));
+    assert_eq!(evaluate("  ", DEGREES), Ok(0.0));
+}
+
+#[test]
+fn test_unary_minus_after_operators() {
+    assert_eq!(evaluate("2^-1", DEGREES), Ok(0.5));
+    assert_close(evaluate("10^-3", DEGREES).unwrap(), 0.001);
+    assert_eq!(evaluate("3x-2", DEGREES), Ok(-6.0));
+    assert_eq!(evaluate("8÷-4+1", DEGREES), Ok(-1.0));
+    // The power still binds tighter than the sign
+    assert_eq!(evaluate("2x-3^2", DEGREES), Ok(-18.0));
+
+    // A sign after + or - is still two operators in a row
+    let error = evaluate("1+-2", DEGREES).unwrap_err();
+    assert_eq!(error.kind, SyntaxError::ConsecutiveOperators.into());
+    assert_eq!(error.span, 2..3);
+}
+
+#[test]
+fn test_math_functions() {
+    assert_close(evaluate("sin(30)", DEGREES).unwrap(), 0.5);
+    assert_eq!(evaluate("cos(90)", DEGREES), Ok(0.0));
+    assert_close(evaluate("sin(π÷6)", AngleMode::Radians).unwrap(), 0.5);
+    assert_close(evaluate("atan(1)", DEGREES).unwrap(), 45.0);
+    assert_close(evaluate("ln(e)", DEGREES).unwrap(), 1.0);
+    assert_close(evaluate("log(1000)", DEGREES).unwrap(), 3.0);
+    assert_close(evaluate("∛(27)+√(16)", DEGREES).unwrap(), 7.0);
+    assert_eq!(evaluate("abs(-2.5)", DEGREES), Ok(2.5));
+    assert_close(
+        evaluate("2^0.5", DEGREES).unwrap(),
+        core::f64::consts::SQRT_2,
+    );
+    assert_eq!(Function::Log.apply(100.0, DEGREES), Ok(2.0));
+}
+
+#[test]
+fn test_errors_and_spans() {
+    let error = evaluate("1+4÷(2-2)", DEGREES).unwrap_err();
+    assert_eq!(error.kind, CalculatorError::DivisionByZero);
+    assert_eq!(error.span, 5..8);
+
+    let error = evaluate("√(-4)", DEGREES).unwrap_err();
+    assert_eq!(error.kind, CalculatorError::DomainError("√(-4)".into()));
+
+    let error = evaluate("2+a", DEGREES).unwrap_err();
+    assert_eq!(error.kind, CalculatorError::InvalidCharacters("a".into()));
+    assert_eq!(error.span, 2..3);
+
+    let long = "1+".repeat(MAX_INPUT_LENGTH);
+    assert_eq!(
+        evaluate(&long, DEGREES).unwrap_err().kind,
+        CalculatorError::InputTooLong
+    );
+    assert_eq!(
+        check_syntax("(1+2"),
+        Err(SyntaxError::MismatchedParentheses.into())
+    );
+}
+
+#[test]
+fn test_number_helpers() {
+    assert_eq!(safe_parse_number("1.5e3"), Ok(1500.0));
+    assert!(safe_parse_number("1e200").is_err());
+    assert_eq!(power(2.0, 10.0), Ok(1024.0));
+    assert_eq!(power(0.0, -1.0), Err(CalculatorError::DivisionByZero));
+    assert!(power(-8.0, 0.5).is_err());
+    assert_eq!(factorial(5.0), Ok(120.0));
+    assert!(factorial(2.5).is_err());
+}
+
+#[test]
+fn test_errors_display_and_implement_error() {
+    let error = evaluate("1÷0", DEGREES).unwrap_err();
+    assert_eq!(error.to_string(), "Division by zero");
+    assert_eq!(
+        Locale::German.error_message(&error.kind),
+        "Division durch null"
+    );
+
+    let source: &dyn core::error::Error = &error.kind;
+    assert_eq!(source.to_string(), "Division by zero");
+}
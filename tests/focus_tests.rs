@@ -0,0 +1,239 @@
+#![cfg(feature = "std")]
+
+use rust_calculator::focus::{FocusDirection, KeypadButton};
+use rust_calculator::keymap::{Key, Modifiers, NamedKey};
+use rust_calculator::{CalculatorUIState, InputMode, Operation, ScientificKey, UIMessage};
+
+fn focused_message(ui_state: &CalculatorUIState) -> Option<UIMessage> {
+    ui_state.focused_button().map(|button| button.message)
+}
+
+fn shift() -> Modifiers {
+    Modifiers {
+        shift: true,
+        ..Modifiers::NONE
+    }
+}
+
+#[test]
+fn test_keypad_grid_follows_mode_and_panel() {
+    let mut ui_state = CalculatorUIState::new();
+    let grid = ui_state.keypad_grid();
+    assert_eq!(grid.len(), 6);
+    assert!(grid.iter().all(|row| row.len() == 4));
+    assert_eq!(
+        grid[5][3].as_ref().map(|button| &button.message),
+        Some(&UIMessage::EqualsPressed)
+    );
+
+    ui_state.mode = InputMode::Rpn;
+    let grid = ui_state.keypad_grid();
+    assert_eq!(
+        grid[0][0].as_ref().map(|button| &button.message),
+        Some(&UIMessage::EnterPressed)
+    );
+
+    // Stacked panel: four panel rows above the keypad
+    ui_state.mode = InputMode::Algebraic;
+    ui_state.scientific_panel = true;
+    let grid = ui_state.keypad_grid();
+    assert_eq!(grid.len(), 10);
+    assert_eq!(
+        grid[0][2],
+        Some(KeypadButton {
+            message: UIMessage::OpenParenPressed,
+            scientific: true,
+        })
+    );
+
+    // Side by side: eight columns, with no panel keys in the last two rows
+    ui_state.wide_window = true;
+    let grid = ui_state.keypad_grid();
+    assert_eq!(grid.len(), 6);
+    assert!(grid.iter().all(|row| row.len() == 8));
+    assert_eq!(grid[4][..4], [None, None, None, None]);
+    assert_eq!(
+        grid[1][4].as_ref().map(|button| &button.message),
+        Some(&UIMessage::BackspacePressed)
+    );
+}
+
+#[test]
+fn test_tab_order_wraps() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.process_message(UIMessage::FocusNext);
+    assert_eq!(ui_state.focus, Some((0, 0)));
+    assert_eq!(
+        focused_message(&ui_state),
+        Some(UIMessage::OpenParenPressed)
+    );
+
+    ui_state.process_message(UIMessage::FocusPrevious);
+    assert_eq!(ui_state.focus, Some((5, 3)));
+    ui_state.process_message(UIMessage::FocusNext);
+    assert_eq!(ui_state.focus, Some((0, 0)));
+
+    // Shift+Tab starts from the last button
+    ui_state.process_message(UIMessage::FocusClear);
+    ui_state.process_message(UIMessage::FocusPrevious);
+    assert_eq!(focused_message(&ui_state), Some(UIMessage::EqualsPressed));
+}
+
+#[test]
+fn test_arrow_moves_stop_at_edges() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.process_message(UIMessage::FocusMove(FocusDirection::Down));
+    assert_eq!(ui_state.focus, Some((0, 0)));
+
+    ui_state.process_message(UIMessage::FocusMove(FocusDirection::Up));
+    ui_state.process_message(UIMessage::FocusMove(FocusDirection::Left));
+    assert_eq!(ui_state.focus, Some((0, 0)));
+
+    ui_state.process_message(UIMessage::FocusMove(FocusDirection::Down));
+    ui_state.process_message(UIMessage::FocusMove(FocusDirection::Down));
+    ui_state.process_message(UIMessage::FocusMove(FocusDirection::Right));
+    assert_eq!(
+        focused_message(&ui_state),
+        Some(UIMessage::NumberPressed(8))
+    );
+
+    for _ in 0..5 {
+        ui_state.process_message(UIMessage::FocusMove(FocusDirection::Right));
+    }
+    assert_eq!(
+        focused_message(&ui_state),
+        Some(UIMessage::OperationPressed(Operation::Multiply))
+    );
+}
+
+#[test]
+fn test_arrow_moves_skip_empty_cells() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.scientific_panel = true;
+    ui_state.wide_window = true;
+    ui_state.focus = Some((3, 0));
+    assert_eq!(
+        focused_message(&ui_state),
+        Some(UIMessage::ScientificKeyPressed(ScientificKey::Ln))
+    );
+    // Nothing below the panel, so the focus stays
+    ui_state.process_message(UIMessage::FocusMove(FocusDirection::Down));
+    assert_eq!(ui_state.focus, Some((3, 0)));
+
+    // From the keypad's bottom row, left goes nowhere; up and left reach the panel
+    ui_state.focus = Some((5, 4));
+    ui_state.process_message(UIMessage::FocusMove(FocusDirection::Left));
+    assert_eq!(ui_state.focus, Some((5, 4)));
+    ui_state.process_message(UIMessage::FocusMove(FocusDirection::Up));
+    ui_state.process_message(UIMessage::FocusMove(FocusDirection::Up));
+    ui_state.process_message(UIMessage::FocusMove(FocusDirection::Left));
+    assert_eq!(
+        focused_message(&ui_state),
+        Some(UIMessage::ScientificKeyPressed(ScientificKey::E))
+    );
+}
+
+#[test]
+fn test_activate_presses_the_focused_button() {
+    let mut ui_state = CalculatorUIState::new();
+    // Focus 7, press it, move to + and press it, then 7 again and =
+    ui_state.focus = Some((2, 0));
+    ui_state.process_message(UIMessage::FocusActivate);
+    ui_state.focus = Some((4, 3));
+    ui_state.process_message(UIMessage::FocusActivate);
+    ui_state.focus = Some((2, 0));
+    ui_state.process_message(UIMessage::FocusActivate);
+    assert_eq!(ui_state.calculator.expression, "7+7");
+    ui_state.focus = Some((5, 3));
+    ui_state.process_message(UIMessage::FocusActivate);
+    assert_eq!(ui_state.calculator.display, "14");
+    // The focus stays on the pressed button
+    assert_eq!(ui_state.focus, Some((5, 3)));
+}
+
+#[test]
+fn test_focus_keys() {
+    let mut ui_state = CalculatorUIState::new();
+    let named = |key| Key::Named(key);
+
+    // Without focus, only Tab starts navigation and the other keys keep their bindings
+    assert_eq!(
+        ui_state.focus_message(&named(NamedKey::Tab), Modifiers::NONE),
+        Some(UIMessage::FocusNext)
+    );
+    assert_eq!(
+        ui_state.focus_message(&named(NamedKey::Tab), shift()),
+        Some(UIMessage::FocusPrevious)
+    );
+    for key in [
+        NamedKey::Enter,
+        NamedKey::Space,
+        NamedKey::ArrowUp,
+        NamedKey::Escape,
+    ] {
+        assert_eq!(ui_state.focus_message(&named(key), Modifiers::NONE), None);
+    }
+
+    ui_state.process_message(UIMessage::FocusNext);
+    let cases = [
+        (NamedKey::ArrowUp, UIMessage::FocusMove(FocusDirection::Up)),
+        (
+            NamedKey::ArrowDown,
+            UIMessage::FocusMove(FocusDirection::Down),
+        ),
+        (NamedKey::Enter, UIMessage::FocusActivate),
+        (NamedKey::Space, UIMessage::FocusActivate),
+        (NamedKey::Escape, UIMessage::FocusClear),
+    ];
+    for (key, message) in cases {
+        assert_eq!(
+            ui_state.focus_message(&named(key), Modifiers::NONE),
+            Some(message),
+            "key {key:?}"
+        );
+    }
+    // Digits and shortcuts aren't taken over
+    assert_eq!(
+        ui_state.focus_message(&Key::character('7'), Modifiers::NONE),
+        None
+    );
+    assert_eq!(
+        ui_state.focus_message(&named(NamedKey::Enter), Modifiers::command()),
+        None
+    );
+
+    ui_state.process_message(UIMessage::FocusClear);
+    assert_eq!(ui_state.focus, None);
+}
+
+#[test]
+fn test_focus_keys_ignored_in_other_views() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.process_message(UIMessage::FocusNext);
+    ui_state.process_message(UIMessage::GraphTogglePressed);
+    assert_eq!(
+        ui_state.focus_message(&Key::Named(NamedKey::Tab), Modifiers::NONE),
+        None
+    );
+    assert_eq!(
+        ui_state.focus_message(&Key::Named(NamedKey::Enter), Modifiers::NONE),
+        None
+    );
+}
+
+#[test]
+fn test_focus_survives_layout_changes() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.focus = Some((5, 3));
+    ui_state.process_message(UIMessage::ModeTogglePressed);
+    assert_eq!(
+        focused_message(&ui_state),
+        Some(UIMessage::PercentagePressed)
+    );
+
+    // A position outside the grid counts as no focus and restarts from the first button
+    ui_state.focus = Some((9, 9));
+    assert_eq!(ui_state.focused_button(), None);
+    ui_state.process_message(UIMessage::FocusNext);
+    assert_eq!(ui_state.focus, Some((0, 0)));
+}
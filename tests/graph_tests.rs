@@ -0,0 +1,187 @@
+#![cfg(feature = "std")]
+
+use rust_calculator::graph::{Graph, PlotFunction, Viewport, format_tick, grid_lines, grid_step};
+use rust_calculator::{AngleMode, SyntaxError};
+
+#[test]
+fn test_plot_function_parse() {
+    assert!(PlotFunction::parse("x^2").is_ok());
+    assert!(PlotFunction::parse("sin(x) + 2x").is_ok());
+    assert!(PlotFunction::parse("3").is_ok());
+    // Undefined values are not parse errors
+    assert!(PlotFunction::parse("1/x").is_ok());
+    assert!(PlotFunction::parse("ln(-1-x^2)").is_ok());
+
+    assert!(PlotFunction::parse("").is_err());
+    assert!(PlotFunction::parse("x+").is_err());
+    assert!(PlotFunction::parse("sin(x").is_err());
+    assert!(PlotFunction::parse("y+1").is_err());
+}
+
+#[test]
+fn test_plot_function_evaluate() {
+    let square = PlotFunction::parse("x^2").unwrap();
+    assert_eq!(square.evaluate(-3.0, AngleMode::Radians), Some(9.0));
+
+    // Implicit products with the variable
+    let f = PlotFunction::parse("2x + x(1+1) + xπ").unwrap();
+    let value = f.evaluate(1.0, AngleMode::Radians).unwrap();
+    assert!((value - (4.0 + std::f64::consts::PI)).abs() < 1e-12);
+
+    let sine = PlotFunction::parse("sin(x)").unwrap();
+    assert!((sine.evaluate(90.0, AngleMode::Degrees).unwrap() - 1.0).abs() < 1e-12);
+    assert!(sine.evaluate(90.0, AngleMode::Radians).unwrap() < 1.0);
+
+    let reciprocal = PlotFunction::parse("1/x").unwrap();
+    assert_eq!(reciprocal.evaluate(0.0, AngleMode::Radians), None);
+    assert_eq!(reciprocal.evaluate(-0.5, AngleMode::Radians), Some(-2.0));
+}
+
+#[test]
+fn test_sample_continuous_function() {
+    let f = PlotFunction::parse("x").unwrap();
+    let curves = f.sample(&Viewport::default(), 21, AngleMode::Radians);
+    assert_eq!(curves.len(), 1);
+    assert_eq!(curves[0].len(), 21);
+    assert_eq!(curves[0][0], (-10.0, -10.0));
+    assert_eq!(curves[0][20], (10.0, 10.0));
+
+    let sine = PlotFunction::parse("sin(x)").unwrap();
+    for samples in [21, 400] {
+        assert_eq!(
+            sine.sample(&Viewport::default(), samples, AngleMode::Radians)
+                .len(),
+            1
+        );
+    }
+}
+
+#[test]
+fn test_sample_steep_function_is_not_split() {
+    // Rises by far more than the viewport height between samples, but continuously
+    let f = PlotFunction::parse("x^3").unwrap();
+    let curves = f.sample(&Viewport::default(), 11, AngleMode::Radians);
+    assert_eq!(curves.len(), 1);
+}
+
+#[test]
+fn test_sample_breaks_at_undefined_points() {
+    let f = PlotFunction::parse("√(x)").unwrap();
+    let curves = f.sample(&Viewport::default(), 21, AngleMode::Radians);
+    assert_eq!(curves.len(), 1);
+    assert!(curves[0].iter().all(|&(x, _)| x >= 0.0));
+
+    let f = PlotFunction::parse("√(1-x^2)").unwrap();
+    let curves = f.sample(&Viewport::default(), 201, AngleMode::Radians);
+    assert_eq!(curves.len(), 1);
+    assert!(curves[0].iter().all(|&(x, _)| (-1.0..=1.0).contains(&x)));
+}
+
+#[test]
+fn test_sample_breaks_at_poles() {
+    let tan = PlotFunction::parse("tan(x)").unwrap();
+    let curves = tan.sample(&Viewport::default(), 500, AngleMode::Radians);
+    // Poles at ±π/2, ±3π/2, ±5π/2 split the curve into seven branches
+    assert_eq!(curves.len(), 7);
+    for curve in &curves {
+        assert!(curve.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+    }
+}
+
+#[test]
+fn test_sample_clamps_far_values() {
+    let f = PlotFunction::parse("1/x").unwrap();
+    let viewport = Viewport::default();
+    let curves = f.sample(&viewport, 1000, AngleMode::Radians);
+    let (low, high) = (
+        viewport.y_min - viewport.height(),
+        viewport.y_max + viewport.height(),
+    );
+    assert!(
+        curves
+            .iter()
+            .flatten()
+            .all(|&(_, y)| (low..=high).contains(&y))
+    );
+}
+
+#[test]
+fn test_viewport_zoom_keeps_point_fixed() {
+    let mut viewport = Viewport::default();
+    let before = viewport.to_screen(4.0, 2.0, 400.0, 300.0);
+    viewport.zoom(0.25, 4.0, 2.0);
+    assert_eq!(viewport.width(), 5.0);
+    assert_eq!(viewport.height(), 5.0);
+    assert_eq!(viewport.to_screen(4.0, 2.0, 400.0, 300.0), before);
+
+    viewport.zoom(4.0, 4.0, 2.0);
+    assert_eq!(viewport, Viewport::default());
+}
+
+#[test]
+fn test_viewport_zoom_limits() {
+    let mut viewport = Viewport::default();
+    for _ in 0..100 {
+        viewport.zoom(0.1, 0.0, 0.0);
+    }
+    assert!((viewport.width() - Viewport::MIN_SPAN).abs() < 1e-12);
+
+    for _ in 0..100 {
+        viewport.zoom(10.0, 0.0, 0.0);
+    }
+    assert!((viewport.width() - Viewport::MAX_SPAN).abs() < 1e-6);
+
+    let before = viewport;
+    viewport.zoom(f64::NAN, 0.0, 0.0);
+    viewport.zoom(-1.0, 0.0, 0.0);
+    assert_eq!(viewport, before);
+}
+
+#[test]
+fn test_viewport_pan_and_screen_conversion() {
+    let mut viewport = Viewport::default();
+    viewport.pan(5.0, -2.5);
+    assert_eq!((viewport.x_min, viewport.x_max), (-5.0, 15.0));
+    assert_eq!((viewport.y_min, viewport.y_max), (-12.5, 7.5));
+
+    let viewport = Viewport::default();
+    assert_eq!(viewport.to_screen(0.0, 0.0, 200.0, 100.0), (100.0, 50.0));
+    assert_eq!(viewport.to_screen(-10.0, 10.0, 200.0, 100.0), (0.0, 0.0));
+    assert_eq!(
+        viewport.from_screen(200.0, 100.0, 200.0, 100.0),
+        (10.0, -10.0)
+    );
+}
+
+#[test]
+fn test_grid() {
+    assert_eq!(grid_step(20.0, 10), 2.0);
+    assert_eq!(grid_step(20.0, 4), 5.0);
+    assert_eq!(grid_step(1000.0, 10), 100.0);
+    assert_eq!(grid_step(3e-6, 6), 5e-7);
+
+    assert_eq!(grid_lines(-3.5, 4.0, 2.0), vec![-2.0, 0.0, 2.0, 4.0]);
+    assert!(grid_lines(0.0, 1e9, 1e-3).is_empty());
+    assert!(grid_lines(0.0, 1.0, 0.0).is_empty());
+
+    assert_eq!(format_tick(100.0, 50.0), "100");
+    assert_eq!(format_tick(-0.00000001, 0.5), "0");
+    assert_eq!(format_tick(0.25, 0.05), "0.25");
+}
+
+#[test]
+fn test_graph_input() {
+    let mut graph = Graph::default();
+    graph.set_input("sin(x); ; x^2/4");
+    assert_eq!(graph.functions.len(), 2);
+    assert_eq!(graph.functions[1].expression(), "x^2/4");
+    assert_eq!(graph.error, None);
+
+    graph.set_input("x+; 2x");
+    assert_eq!(graph.functions.len(), 1);
+    assert_eq!(
+        graph.error,
+        Some(("x+".to_string(), SyntaxError::MissingOperand.into()))
+    );
+    assert_eq!(graph.input, "x+; 2x");
+}
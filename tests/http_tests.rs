@@ -0,0 +1,200 @@
+#![cfg(feature = "http")]
+
+use rust_calculator::cli::EvalOptions;
+use rust_calculator::expression::MAX_INPUT_LENGTH;
+use rust_calculator::http::{HttpServer, MAX_BATCH_EXPRESSIONS, MAX_EVALUATE_BODY};
+use serde_json::{Value, json};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// Starts a server on a free localhost port.
+fn start() -> SocketAddr {
+    let server = HttpServer::bind("127.0.0.1:0", EvalOptions::default()).unwrap();
+    let address = server.local_addr().unwrap();
+    thread::spawn(move || server.run());
+    address
+}
+
+/// Sends a raw request and returns the status code and JSON body of the response.
+fn send(address: SocketAddr, request: &[u8]) -> (u16, Value) {
+    let mut stream = TcpStream::connect(address).unwrap();
+    stream.write_all(request).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+    (status, serde_json::from_str(body).unwrap())
+}
+
+fn post(address: SocketAddr, path: &str, body: &Value) -> (u16, Value) {
+    let body = body.to_string();
+    let request = format!(
+        "POST {} HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\n\r\n{}",
+        path,
+        body.len(),
+        body
+    );
+    send(address, request.as_bytes())
+}
+
+#[test]
+fn test_health() {
+    let address = start();
+    assert_eq!(
+        send(address, b"GET /health HTTP/1.1\r\nHost: localhost\r\n\r\n"),
+        (200, json!({"status": "ok"}))
+    );
+}
+
+#[test]
+fn test_evaluate_with_options_and_errors() {
+    let address = start();
+    assert_eq!(
+        post(address, "/evaluate", &json!({"expression": "2x(3+4)"})),
+        (200, json!({"value": 14.0, "formatted": "14"}))
+    );
+    let request = json!({
+        "expression": "cos(π)÷3",
+        "options": {"angle": "radians", "precision": 2, "format": "fixed"},
+    });
+    assert_eq!(post(address, "/evaluate", &request).1["formatted"], "-0.33");
+
+    assert_eq!(
+        post(address, "/evaluate", &json!({"expression": "1+4÷(2-2)"})),
+        (
+            422,
+            json!({"error": {
+                "kind": "division_by_zero",
+                "message": "Division by zero",
+                "start": 5,
+                "end": 8,
+            }})
+        )
+    );
+    let (status, body) = post(
+        address,
+        "/evaluate",
+        &json!({"expression": "1", "options": {"angle": "turns"}}),
+    );
+    assert_eq!(
+        (status, &body["error"]["kind"]),
+        (400, &json!("bad_request"))
+    );
+}
+
+#[test]
+fn test_batch() {
+    let address = start();
+    let request = json!({"expressions": ["7+8*3", "√(-1)", "2^10"], "options": {"precision": 1}});
+    let (status, body) = post(address, "/batch", &request);
+    assert_eq!(status, 200);
+    assert_eq!(
+        body["results"][0],
+        json!({"value": 31.0, "formatted": "31"})
+    );
+    assert_eq!(body["results"][1]["error"]["kind"], "domain_error");
+    assert_eq!(body["results"][2]["value"], 1024.0);
+
+    let too_many = json!({"expressions": vec!["1"; MAX_BATCH_EXPRESSIONS + 1]});
+    assert_eq!(post(address, "/batch", &too_many).0, 413);
+}
+
+#[test]
+fn test_malformed_requests() {
+    let address = start();
+    assert_eq!(post(address, "/evaluate", &json!({"expr": "1"})).0, 400);
+    assert_eq!(post(address, "/solve", &json!({})).0, 404);
+    assert_eq!(send(address, b"GET /evaluate HTTP/1.1\r\n\r\n").0, 405);
+    assert_eq!(send(address, b"POST /evaluate HTTP/1.1\r\n\r\n").0, 411);
+    assert_eq!(send(address, b"nonsense\r\n\r\n").0, 400);
+}
+
+#[test]
+fn test_size_limits() {
+    let address = start();
+
+    // The longest expression the evaluator takes fits; anything longer is its error
+    let longest = "1+".repeat(MAX_INPUT_LENGTH / 2 - 1) + "1";
+    assert_eq!(
+        post(address, "/evaluate", &json!({"expression": longest})).0,
+        200
+    );
+    let (status, body) = post(address, "/evaluate", &json!({"expression": longest + "+1"}));
+    assert_eq!(
+        (status, &body["error"]["kind"]),
+        (422, &json!("input_too_long"))
+    );
+
+    // Oversized bodies are refused from their Content-Length, before they are sent
+    let request = format!(
+        "POST /evaluate HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+        MAX_EVALUATE_BODY + 1
+    );
+    let (status, body) = send(address, request.as_bytes());
+    assert_eq!(
+        (status, &body["error"]["kind"]),
+        (413, &json!("payload_too_large"))
+    );
+
+    let request = format!(
+        "GET /health HTTP/1.1\r\nX-Padding: {}\r\n\r\n",
+        "a".repeat(10_000)
+    );
+    assert_eq!(send(address, request.as_bytes()).0, 431);
+}
+
+#[test]
+fn test_concurrent_requests() {
+    let address = start();
+
+    // A client that never finishes its request doesn't hold up the others
+    let mut stalled = TcpStream::connect(address).unwrap();
+    stalled.write_all(b"POST /evaluate HTTP/1.1\r\n").unwrap();
+
+    let clients: Vec<_> = (0..16)
+        .map(|i| {
+            thread::spawn(move || {
+                post(
+                    address,
+                    "/evaluate",
+                    &json!({"expression": format!("{}x2", i)}),
+                )
+            })
+        })
+        .collect();
+    for (i, client) in clients.into_iter().enumerate() {
+        let (status, body) = client.join().unwrap();
+        assert_eq!(status, 200);
+        assert_eq!(body["value"], (i * 2) as f64);
+    }
+}
+
+#[test]
+fn test_serve_http_command() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rust-calculator"))
+        .args(["serve", "--http", "127.0.0.1:0", "--precision", "2"])
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut announcement = String::new();
+    BufReader::new(child.stderr.take().unwrap())
+        .read_line(&mut announcement)
+        .unwrap();
+    let address = announcement
+        .trim()
+        .strip_prefix("rust-calculator: listening on http://")
+        .unwrap()
+        .parse()
+        .unwrap();
+
+    let response = post(address, "/evaluate", &json!({"expression": "2÷3"}));
+    child.kill().unwrap();
+    child.wait().unwrap();
+    assert_eq!(
+        response,
+        (200, json!({"value": 2.0 / 3.0, "formatted": "0.67"}))
+    );
+}
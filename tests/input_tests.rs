@@ -1,4 +1,6 @@
-use rust_calculator::{Calculator, Operation};
+#![cfg(feature = "std")]
+
+use rust_calculator::{Calculator, CalculatorError, Constant, Function, Operation};
 
 #[test]
 fn test_handle_number_input_basic() {
@@ -29,10 +31,14 @@ fn test_handle_percentage_with_unparseable_display() {
 #[test]
 fn test_handle_number_input_after_error() {
     let mut calc = Calculator::new();
-    calc.display = "Error".to_string();
+    calc.expression = "5x".to_string();
+    calc.handle_equals_input();
+    assert!(calc.error.is_some());
+    // The kept expression is completed and the error goes away
     calc.handle_number_input(7);
-    assert_eq!(calc.expression, "7");
-    assert_eq!(calc.display, "7");
+    assert_eq!(calc.expression, "5x7");
+    assert_eq!(calc.display, "5x7");
+    assert_eq!(calc.error, None);
     assert!(!calc.new_input);
 }
 
@@ -73,9 +79,11 @@ fn test_handle_operation_input_replaces_last_operator() {
 #[test]
 fn test_handle_operation_input_after_error() {
     let mut calc = Calculator::new();
-    calc.display = "Error".to_string();
+    calc.expression = "2÷0".to_string();
+    calc.handle_equals_input();
     calc.handle_operation_input(Operation::Add);
-    // Should do nothing when display is Error
+    assert_eq!(calc.expression, "2÷0+");
+    assert_eq!(calc.error, None);
 }
 
 #[test]
@@ -95,16 +103,80 @@ fn test_handle_equals_input_with_error() {
     let mut calc = Calculator::new();
     calc.expression = "10/0".to_string();
     calc.handle_equals_input();
-    assert_eq!(calc.display, "Division by zero");
-    assert_eq!(calc.expression, "0");
+    // The expression is kept with the divisor marked, so it can be fixed
+    assert_eq!(calc.expression, "10/0");
+    let error = calc.error.clone().unwrap();
+    assert_eq!(error.kind, CalculatorError::DivisionByZero);
+    assert_eq!(error.span, 3..4);
+}
+
+#[test]
+fn test_error_is_fixed_by_editing() {
+    let mut calc = Calculator::new();
+    calc.expression = "5+x3".to_string();
+    calc.handle_equals_input();
+    assert_eq!(calc.expression, "5+x3");
+    assert_eq!(calc.error.as_ref().unwrap().span, 2..3);
+    // The cursor sits right after the offending operator
+    assert_eq!(calc.cursor, Some(3));
+
+    calc.handle_backspace_input();
+    assert_eq!(calc.error, None);
+    assert_eq!(calc.expression, "5+3");
+
+    calc.handle_equals_input();
+    assert_eq!(calc.display, "8");
+    assert_eq!(calc.error, None);
+}
+
+#[test]
+fn test_error_at_end_keeps_cursor_at_end() {
+    let mut calc = Calculator::new();
+    calc.expression = "2^(0-1)!".to_string();
+    calc.handle_equals_input();
+    assert_eq!(calc.error.as_ref().unwrap().span, 3..8);
+    assert_eq!(calc.cursor, None);
+
+    // Appending input continues the kept expression
+    calc.handle_operation_input(Operation::Add);
+    assert_eq!(calc.error, None);
+    assert_eq!(calc.expression, "2^(0-1)!+");
+}
+
+#[test]
+fn test_error_display_parts() {
+    let mut calc = Calculator::new();
+    assert_eq!(calc.error_display_parts(), None);
+
+    calc.expression = "√(-4)+1".to_string();
+    calc.handle_equals_input();
+    assert_eq!(
+        calc.error_display_parts(),
+        Some(("".to_string(), "√(-4)".to_string(), "+1".to_string()))
+    );
+
+    // Moving the cursor keeps the highlight on the same characters
+    calc.handle_cursor_home();
+    calc.handle_cursor_right();
+    calc.handle_cursor_right();
+    assert_eq!(
+        calc.error_display_parts(),
+        Some(("".to_string(), "√(-4)".to_string(), "+1".to_string()))
+    );
 }
 
 #[test]
 fn test_handle_equals_input_after_error() {
     let mut calc = Calculator::new();
-    calc.display = "Error".to_string();
+    calc.expression = "2÷0".to_string();
     calc.handle_equals_input();
-    // Should do nothing when display is Error
+    calc.handle_equals_input();
+    // Evaluating the unchanged expression again gives the same error
+    assert_eq!(calc.expression, "2÷0");
+    assert_eq!(
+        calc.error.map(|error| error.kind),
+        Some(CalculatorError::DivisionByZero)
+    );
 }
 
 #[test]
@@ -137,11 +209,11 @@ fn test_handle_decimal_input_after_operation() {
 #[test]
 fn test_handle_decimal_input_after_error() {
     let mut calc = Calculator::new();
-    calc.display = "Error".to_string();
+    calc.expression = "5x".to_string();
+    calc.handle_equals_input();
     calc.handle_decimal_input();
-    assert_eq!(calc.expression, "0.");
-    assert_eq!(calc.display, "0.");
-    assert!(!calc.new_input);
+    assert_eq!(calc.expression, "5x.");
+    assert_eq!(calc.error, None);
 }
 
 #[test]
@@ -178,11 +250,12 @@ fn test_handle_backspace_to_single_digit() {
 #[test]
 fn test_handle_backspace_after_error() {
     let mut calc = Calculator::new();
-    calc.display = "Error".to_string();
+    calc.expression = "5x".to_string();
+    calc.handle_equals_input();
     calc.handle_backspace_input();
-    assert_eq!(calc.expression, "0");
-    assert_eq!(calc.display, "0");
-    assert!(!calc.new_input);
+    assert_eq!(calc.expression, "5");
+    assert_eq!(calc.display, "5");
+    assert_eq!(calc.error, None);
 }
 
 #[test]
@@ -462,3 +535,365 @@ fn test_extract_current_number() {
     calc.expression = "123.45".to_string();
     assert_eq!(calc.extract_current_number(), "123.45");
 }
+
+#[test]
+fn test_cursor_defaults_to_end() {
+    let mut calc = Calculator::new();
+    calc.expression = "12+3".to_string();
+    assert_eq!(calc.cursor, None);
+    assert_eq!(calc.cursor_position(), 4);
+}
+
+#[test]
+fn test_cursor_movement() {
+    let mut calc = Calculator::new();
+    calc.expression = "12+3".to_string();
+
+    calc.handle_cursor_left();
+    assert_eq!(calc.cursor, Some(3));
+    calc.handle_cursor_home();
+    assert_eq!(calc.cursor, Some(0));
+    calc.handle_cursor_left();
+    assert_eq!(calc.cursor, Some(0)); // Already at start
+    calc.handle_cursor_right();
+    assert_eq!(calc.cursor, Some(1));
+    calc.handle_cursor_end();
+    assert_eq!(calc.cursor, None);
+
+    // Moving right onto the last position pins the cursor to the end again
+    calc.cursor = Some(3);
+    calc.handle_cursor_right();
+    assert_eq!(calc.cursor, None);
+}
+
+#[test]
+fn test_number_input_at_cursor() {
+    let mut calc = Calculator::new();
+    calc.expression = "12+3".to_string();
+    calc.cursor = Some(1);
+    calc.handle_number_input(5);
+    assert_eq!(calc.expression, "152+3");
+    assert_eq!(calc.cursor, Some(2));
+    assert_eq!(calc.display, "152+3");
+}
+
+#[test]
+fn test_operation_input_at_cursor_replaces_adjacent_operator() {
+    let mut calc = Calculator::new();
+    calc.expression = "12+3".to_string();
+    calc.cursor = Some(3);
+    calc.handle_operation_input(Operation::Multiply);
+    assert_eq!(calc.expression, "12x3");
+    assert_eq!(calc.cursor, Some(3));
+
+    calc.cursor = Some(1);
+    calc.handle_operation_input(Operation::Divide);
+    assert_eq!(calc.expression, "1÷2x3");
+    assert_eq!(calc.cursor, Some(2));
+}
+
+#[test]
+fn test_decimal_input_at_cursor() {
+    let mut calc = Calculator::new();
+    calc.expression = "12+34".to_string();
+    calc.cursor = Some(1);
+    calc.handle_decimal_input();
+    assert_eq!(calc.expression, "1.2+34");
+
+    // The number around the cursor already has a decimal point
+    calc.handle_decimal_input();
+    assert_eq!(calc.expression, "1.2+34");
+}
+
+#[test]
+fn test_backspace_at_cursor() {
+    let mut calc = Calculator::new();
+    calc.expression = "1÷2x3".to_string();
+    calc.cursor = Some(2);
+    calc.handle_backspace_input();
+    assert_eq!(calc.expression, "12x3");
+    assert_eq!(calc.cursor, Some(1));
+
+    calc.cursor = Some(0);
+    calc.handle_backspace_input();
+    assert_eq!(calc.expression, "12x3"); // Nothing before the cursor
+}
+
+#[test]
+fn test_delete_at_cursor() {
+    let mut calc = Calculator::new();
+    calc.expression = "12x3".to_string();
+    calc.cursor = Some(2);
+    calc.handle_delete_input();
+    assert_eq!(calc.expression, "123");
+    assert_eq!(calc.cursor, Some(2));
+
+    // Deleting the last character pins the cursor to the end
+    calc.handle_delete_input();
+    assert_eq!(calc.expression, "12");
+    assert_eq!(calc.cursor, None);
+
+    // At the end there is nothing to delete
+    calc.handle_delete_input();
+    assert_eq!(calc.expression, "12");
+}
+
+#[test]
+fn test_delete_last_character_resets_to_zero() {
+    let mut calc = Calculator::new();
+    calc.expression = "7".to_string();
+    calc.cursor = Some(0);
+    calc.handle_delete_input();
+    assert_eq!(calc.expression, "0");
+    assert_eq!(calc.cursor, None);
+}
+
+#[test]
+fn test_equals_and_clear_reset_cursor() {
+    let mut calc = Calculator::new();
+    calc.expression = "2+3".to_string();
+    calc.cursor = Some(1);
+    calc.handle_equals_input();
+    assert_eq!(calc.cursor, None);
+
+    calc.cursor = Some(0);
+    calc.handle_clear_input();
+    assert_eq!(calc.cursor, None);
+}
+
+#[test]
+fn test_open_paren_replaces_initial_zero() {
+    let mut calc = Calculator::new();
+    calc.handle_open_paren_input();
+    assert_eq!(calc.expression, "(");
+    assert_eq!(calc.open_paren_depth(), 1);
+
+    calc.handle_number_input(2);
+    assert_eq!(calc.expression, "(2");
+}
+
+#[test]
+fn test_open_paren_after_number_multiplies() {
+    let mut calc = Calculator::new();
+    calc.handle_number_input(2);
+    calc.handle_open_paren_input();
+    assert_eq!(calc.expression, "2x(");
+}
+
+#[test]
+fn test_open_paren_after_operator() {
+    let mut calc = Calculator::new();
+    calc.handle_number_input(2);
+    calc.handle_operation_input(Operation::Add);
+    calc.handle_open_paren_input();
+    calc.handle_open_paren_input();
+    assert_eq!(calc.expression, "2+((");
+    assert_eq!(calc.open_paren_depth(), 2);
+}
+
+#[test]
+fn test_open_paren_after_result_starts_fresh() {
+    let mut calc = Calculator::new();
+    calc.expression = "2+3".to_string();
+    calc.handle_equals_input();
+    calc.handle_open_paren_input();
+    assert_eq!(calc.expression, "(");
+}
+
+#[test]
+fn test_close_paren_without_matching_open_is_refused() {
+    let mut calc = Calculator::new();
+    calc.handle_number_input(5);
+    calc.handle_close_paren_input();
+    assert_eq!(calc.expression, "5");
+}
+
+#[test]
+fn test_close_paren_without_operand_is_refused() {
+    let mut calc = Calculator::new();
+    calc.expression = "2x(".to_string();
+    calc.handle_close_paren_input();
+    assert_eq!(calc.expression, "2x(");
+
+    calc.expression = "2x(3+".to_string();
+    calc.handle_close_paren_input();
+    assert_eq!(calc.expression, "2x(3+");
+}
+
+#[test]
+fn test_close_paren_tracks_depth() {
+    let mut calc = Calculator::new();
+    calc.expression = "((3+4".to_string();
+    calc.handle_close_paren_input();
+    assert_eq!(calc.expression, "((3+4)");
+    assert_eq!(calc.open_paren_depth(), 1);
+    calc.handle_close_paren_input();
+    assert_eq!(calc.open_paren_depth(), 0);
+    calc.handle_close_paren_input();
+    assert_eq!(calc.expression, "((3+4))");
+}
+
+#[test]
+fn test_number_after_close_paren_multiplies() {
+    let mut calc = Calculator::new();
+    calc.expression = "(3+4)".to_string();
+    calc.handle_number_input(2);
+    assert_eq!(calc.expression, "(3+4)x2");
+}
+
+#[test]
+fn test_operation_after_open_paren_only_allows_minus() {
+    let mut calc = Calculator::new();
+    calc.expression = "2x(".to_string();
+    calc.handle_operation_input(Operation::Add);
+    assert_eq!(calc.expression, "2x(");
+    calc.handle_operation_input(Operation::Subtract);
+    assert_eq!(calc.expression, "2x(-");
+    calc.handle_operation_input(Operation::Multiply);
+    assert_eq!(calc.expression, "2x(-");
+}
+
+#[test]
+fn test_equals_closes_open_parens() {
+    let mut calc = Calculator::new();
+    calc.handle_number_input(2);
+    calc.handle_open_paren_input();
+    calc.handle_number_input(3);
+    calc.handle_operation_input(Operation::Add);
+    calc.handle_number_input(4);
+    assert_eq!(calc.expression, "2x(3+4");
+    calc.handle_equals_input();
+    assert_eq!(calc.display, "14");
+}
+
+#[test]
+fn test_load_expression() {
+    let mut calc = Calculator::new();
+    assert_eq!(calc.load_expression(" 12 * (3 + 4) "), Ok(()));
+    assert_eq!(calc.expression, "12x(3+4)");
+    assert_eq!(calc.display, "12x(3+4)");
+    assert!(!calc.new_input);
+    calc.handle_equals_input();
+    assert_eq!(calc.display, "84");
+}
+
+#[test]
+fn test_load_expression_rejects_invalid_text() {
+    let mut calc = Calculator::new();
+    calc.expression = "5+5".to_string();
+    assert_eq!(
+        calc.load_expression("rm -rf"),
+        Err(CalculatorError::InvalidCharacters("rmrf".to_string()))
+    );
+    assert_eq!(calc.expression, "5+5"); // Unchanged on rejection
+
+    assert!(calc.load_expression("   ").is_err());
+    assert!(
+        calc.load_expression(&"1".repeat(Calculator::MAX_INPUT_LENGTH + 1))
+            .is_err()
+    );
+    assert_eq!(calc.expression, "5+5");
+}
+
+#[test]
+fn test_handle_function_input_replaces_zero_and_multiplies_implicitly() {
+    let mut calc = Calculator::new();
+    calc.handle_function_input(Function::Sin);
+    assert_eq!(calc.expression, "sin(");
+
+    let mut calc = Calculator::new();
+    calc.handle_number_input(2);
+    calc.handle_function_input(Function::Sqrt);
+    calc.handle_number_input(9);
+    assert_eq!(calc.expression, "2x√(9");
+    calc.handle_equals_input();
+    assert_eq!(calc.display, "6");
+}
+
+#[test]
+fn test_handle_constant_input() {
+    let mut calc = Calculator::new();
+    calc.handle_number_input(2);
+    calc.handle_constant_input(Constant::Pi);
+    assert_eq!(calc.expression, "2xπ");
+
+    // A digit after a constant also multiplies
+    calc.handle_number_input(3);
+    assert_eq!(calc.expression, "2xπx3");
+}
+
+#[test]
+fn test_negative_exponent() {
+    let mut calc = Calculator::new();
+    calc.handle_number_input(2);
+    calc.handle_operation_input(Operation::Power);
+    calc.handle_operation_input(Operation::Subtract);
+    assert_eq!(calc.expression, "2^-");
+    // Pressing minus again keeps a single sign, another operator replaces both
+    calc.handle_operation_input(Operation::Subtract);
+    assert_eq!(calc.expression, "2^-");
+    calc.handle_operation_input(Operation::Multiply);
+    assert_eq!(calc.expression, "2x");
+    calc.handle_operation_input(Operation::Subtract);
+    calc.handle_number_input(3);
+    assert_eq!(calc.expression, "2x-3");
+    calc.handle_equals_input();
+    assert_eq!(calc.error, None);
+    assert_eq!(calc.display, "-6");
+
+    // After + or - a minus still replaces the operator
+    calc.handle_operation_input(Operation::Add);
+    calc.handle_operation_input(Operation::Subtract);
+    assert_eq!(calc.expression, "-6-");
+}
+
+#[test]
+fn test_handle_backspace_after_constant() {
+    let mut calc = Calculator::new();
+    calc.handle_constant_input(Constant::Pi);
+    assert_eq!(calc.expression, "π");
+    calc.handle_backspace_input();
+    assert_eq!(calc.expression, "0");
+    assert_eq!(calc.display, "0");
+
+    // Multi-byte characters are removed one at a time
+    calc.handle_number_input(2);
+    calc.handle_constant_input(Constant::Pi);
+    calc.handle_backspace_input();
+    assert_eq!(calc.expression, "2x");
+}
+
+#[test]
+fn test_handle_postfix_input_requires_operand() {
+    let mut calc = Calculator::new();
+    calc.handle_number_input(5);
+    calc.handle_operation_input(Operation::Add);
+    calc.handle_postfix_input("!");
+    assert_eq!(calc.expression, "5+");
+
+    calc.handle_number_input(3);
+    calc.handle_postfix_input("!");
+    assert_eq!(calc.expression, "5+3!");
+    calc.handle_equals_input();
+    assert_eq!(calc.display, "11");
+}
+
+#[test]
+fn test_prefix_input_starts_fresh_after_result() {
+    let mut calc = Calculator::new();
+    calc.handle_number_input(4);
+    calc.handle_equals_input();
+    calc.handle_prefix_input("10^(");
+    assert_eq!(calc.expression, "10^(");
+}
+
+#[test]
+fn test_backspace_removes_whole_function_name() {
+    let mut calc = Calculator::new();
+    calc.handle_number_input(1);
+    calc.handle_operation_input(Operation::Add);
+    calc.handle_function_input(Function::Asin);
+    assert_eq!(calc.expression, "1+asin(");
+    calc.handle_backspace_input();
+    assert_eq!(calc.expression, "1+");
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "std")]
+
+use rust_calculator::layout::{
+    format_window_size, load_window_size, parse_window_size, save_window_size,
+};
+use rust_calculator::{Arrangement, Layout};
+
+#[test]
+fn test_base_window_matches_original_design() {
+    let layout = Layout::for_window(348.0, 690.0, false);
+    assert_eq!(layout, Layout::default());
+    assert_eq!(layout.button_size(), 70.0);
+    assert_eq!(layout.spacing(), 12.0);
+    assert_eq!(layout.content_width(), 316.0);
+    assert_eq!(layout.font(52.0), 52.0);
+}
+
+#[test]
+fn test_scale_follows_the_tighter_dimension() {
+    // Wide window: height limits the scale
+    let layout = Layout::for_window(1000.0, 1035.0, false);
+    assert_eq!(layout.scale, 1.5);
+    assert_eq!(layout.button_size(), 105.0);
+    assert_eq!(layout.font(24.0), 36.0);
+
+    // Tall window: width limits the scale
+    let layout = Layout::for_window(522.0, 2000.0, false);
+    assert_eq!(layout.scale, 1.5);
+}
+
+#[test]
+fn test_scale_is_clamped() {
+    assert_eq!(
+        Layout::for_window(100.0, 100.0, false).scale,
+        Layout::MIN_SCALE
+    );
+    assert_eq!(
+        Layout::for_window(10000.0, 10000.0, false).scale,
+        Layout::MAX_SCALE
+    );
+}
+
+#[test]
+fn test_scientific_arrangement_depends_on_orientation() {
+    let landscape = Layout::for_window(696.0, 690.0, true);
+    assert_eq!(landscape.arrangement, Arrangement::ScientificLandscape);
+    assert_eq!(landscape.scale, 1.0);
+    // Display spans both keypads
+    assert_eq!(landscape.content_width(), 2.0 * 316.0 + 32.0);
+
+    let portrait = Layout::for_window(348.0, 1018.0, true);
+    assert_eq!(portrait.arrangement, Arrangement::ScientificPortrait);
+    assert_eq!(portrait.scale, 1.0);
+    assert_eq!(portrait.content_width(), 316.0);
+}
+
+#[test]
+fn test_window_size_for_keeps_scale() {
+    let layout = Layout::for_window(522.0, 1035.0, false);
+    assert_eq!(
+        layout.window_size_for(Arrangement::ScientificLandscape),
+        (1044.0, 1035.0)
+    );
+    assert_eq!(layout.window_size_for(Arrangement::Basic), (522.0, 1035.0));
+}
+
+#[test]
+fn test_parse_window_size() {
+    assert_eq!(parse_window_size("348x690"), Some((348.0, 690.0)));
+    assert_eq!(parse_window_size(" 500 x 900 \n"), Some((500.0, 900.0)));
+    assert_eq!(parse_window_size(""), None);
+    assert_eq!(parse_window_size("500"), None);
+    assert_eq!(parse_window_size("wide x tall"), None);
+    assert_eq!(parse_window_size("inf x 900"), None);
+    // Below the minimum size
+    assert_eq!(parse_window_size("100x900"), None);
+}
+
+#[test]
+fn test_format_window_size_round_trips() {
+    let text = format_window_size((512.4, 1000.6));
+    assert_eq!(text, "512x1001\n");
+    assert_eq!(parse_window_size(&text), Some((512.0, 1001.0)));
+}
+
+#[test]
+fn test_save_and_load_window_size() {
+    let dir = std::env::temp_dir().join(format!("rust-calculator-layout-{}", std::process::id()));
+    let path = dir.join("nested").join("window_size");
+
+    assert_eq!(load_window_size(&path), None);
+    save_window_size(&path, (400.0, 800.0)).unwrap();
+    assert_eq!(load_window_size(&path), Some((400.0, 800.0)));
+
+    std::fs::write(&path, "garbage").unwrap();
+    assert_eq!(load_window_size(&path), None);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,153 @@
+#![cfg(feature = "std")]
+
+use rust_calculator::locale::Text;
+use rust_calculator::settings::{DecimalSeparator, SettingsWarning};
+use rust_calculator::{
+    Calculator, CalculatorError, CalculatorUIState, InputMode, Locale, Settings, SyntaxError,
+    UIMessage,
+};
+
+fn german_ui() -> CalculatorUIState {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.apply_settings(Settings {
+        locale: Locale::German,
+        decimal_separator: Locale::German.decimal_separator(),
+        ..Settings::default()
+    });
+    ui_state
+}
+
+#[test]
+fn test_locale_codes() {
+    for locale in Locale::ALL {
+        assert_eq!(Locale::from_code(locale.code()), Some(locale));
+    }
+    assert_eq!(Locale::from_code("de_CH"), Some(Locale::German));
+    assert_eq!(Locale::from_code(""), None);
+    assert_eq!(Locale::default(), Locale::English);
+    assert_eq!(Locale::German.decimal_separator(), DecimalSeparator::Comma);
+}
+
+#[test]
+fn test_catalog_lookup_with_english_fallback() {
+    assert_eq!(Locale::English.text(Text::SettingsButton), "SET");
+    assert_eq!(Locale::German.text(Text::SettingsButton), "EINST");
+    assert_eq!(Locale::German.text(Text::Degrees), "Grad");
+    // Labels that German calculators share with English ones aren't in the German catalog
+    assert_eq!(Locale::German.text(Text::ClearAll), "AC");
+    assert_eq!(Locale::German.text(Text::LastX), "LASTx");
+}
+
+#[test]
+fn test_format_fills_placeholders_in_order() {
+    assert_eq!(
+        Locale::English.format(Text::ThemeNotFound, &["Solarized", "Dark"]),
+        "Theme \"Solarized\" not found, using Dark"
+    );
+    assert_eq!(
+        Locale::German.format(Text::ThemeNotFound, &["Solarized", "Dark"]),
+        "Farbschema \"Solarized\" nicht gefunden, verwende Dark"
+    );
+    // Missing arguments are left empty
+    assert_eq!(Locale::English.format(Text::SavedTo, &[]), "Saved to ");
+}
+
+#[test]
+fn test_english_messages_are_the_display_text() {
+    let errors = [
+        CalculatorError::DivisionByZero,
+        CalculatorError::InputTooLong,
+        CalculatorError::NumberOutOfRange("1e200".to_string()),
+        CalculatorError::TooFewArguments,
+        SyntaxError::MismatchedParentheses.into(),
+        SyntaxError::UnknownFunction("foo".to_string()).into(),
+    ];
+    for error in errors {
+        assert_eq!(Locale::English.error_message(&error), error.to_string());
+    }
+    assert_eq!(
+        CalculatorError::from(SyntaxError::ExpectedParenthesis("sin".to_string())).to_string(),
+        "Expected '(' after sin"
+    );
+}
+
+#[test]
+fn test_german_error_messages() {
+    let calc = Calculator::new();
+    let message =
+        |expr: &str| Locale::German.error_message(&calc.evaluate_spanned(expr).unwrap_err().kind);
+    assert_eq!(message("1÷0"), "Division durch null");
+    assert_eq!(message("(2+3"), "Klammern passen nicht zusammen");
+    assert_eq!(message("5+*3"), "Unerwartetes '*'");
+    assert_eq!(message("2+)"), "Ungültiger Ausdruck: Operand fehlt");
+    assert_eq!(message("√(-4)"), "Außerhalb des Definitionsbereichs: √(-4)");
+}
+
+#[test]
+fn test_evaluation_errors_are_typed() {
+    let calc = Calculator::new();
+    let kind = |expr: &str| calc.evaluate_spanned(expr).unwrap_err().kind;
+    assert_eq!(
+        kind("5+*3"),
+        SyntaxError::Unexpected("*".to_string()).into()
+    );
+    assert_eq!(kind("2+"), SyntaxError::MissingOperand.into());
+    assert_eq!(
+        kind("1.2.3+1"),
+        SyntaxError::MultipleDecimalPoints("1.2.".to_string()).into()
+    );
+    assert_eq!(
+        Calculator::check_syntax("(2+3"),
+        Err(SyntaxError::MismatchedParentheses.into())
+    );
+}
+
+#[test]
+fn test_german_ui_keeps_working() {
+    let mut ui_state = german_ui();
+    ui_state.calculator.expression = "7÷2".to_string();
+    ui_state.process_message(UIMessage::EqualsPressed);
+    assert_eq!(ui_state.display_lines().result, "3,5");
+    assert_eq!(ui_state.last_evaluation, Some("7÷2".to_string()));
+
+    // A failed evaluation keeps the expression; the error is typed, whatever the language
+    ui_state.calculator.expression = "7÷0".to_string();
+    ui_state.process_message(UIMessage::EqualsPressed);
+    assert_eq!(ui_state.last_evaluation, None);
+    assert_eq!(
+        ui_state.calculator.error.as_ref().map(|error| &error.kind),
+        Some(&CalculatorError::DivisionByZero)
+    );
+}
+
+#[test]
+fn test_german_notices() {
+    let mut ui_state = german_ui();
+    ui_state.process_message(UIMessage::LoadExpression("hallo".to_string()));
+    assert_eq!(
+        ui_state.notice,
+        Some("Einfügen abgelehnt: Ungültige Zeichen: hallo".to_string())
+    );
+
+    ui_state.process_message(UIMessage::ModeTogglePressed);
+    assert_eq!(ui_state.mode, InputMode::Rpn);
+    ui_state.process_message(UIMessage::LoadExpression("1,2,3".to_string()));
+    assert_eq!(
+        ui_state.notice,
+        Some("Einfügen abgelehnt: Ungültige Zahl: 1.2.3".to_string())
+    );
+}
+
+#[test]
+fn test_german_settings_warnings() {
+    let (_, warnings) = Settings::parse("precision = 99\nlocale = \"de\"");
+    assert_eq!(warnings, [SettingsWarning::InvalidPrecision]);
+    assert_eq!(
+        Locale::German.settings_warning(&warnings[0]),
+        "`precision` muss eine ganze Zahl von 0 bis 15 sein, verwende 8"
+    );
+    assert_eq!(
+        Locale::German.settings_warning(&SettingsWarning::InvalidToml("expected `=`".to_string())),
+        "kein gültiges TOML (expected `=`), verwende die Standardwerte"
+    );
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "python")]
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rust_calculator::python::rust_calculator as module;
+
+/// Runs the Python-side tests in `python/tests` against the module, registered as a
+/// built-in module of an embedded interpreter.
+#[test]
+fn test_python_suite() {
+    pyo3::append_to_inittab!(module);
+    Python::initialize();
+    Python::attach(|py| {
+        let locals = PyDict::new(py);
+        locals
+            .set_item(
+                "directory",
+                concat!(env!("CARGO_MANIFEST_DIR"), "/python/tests"),
+            )
+            .unwrap();
+        py.run(
+            c"import unittest\n\
+              suite = unittest.defaultTestLoader.discover(directory)\n\
+              result = unittest.TextTestRunner(verbosity=2).run(suite)\n\
+              passed = result.wasSuccessful() and result.testsRun > 0",
+            None,
+            Some(&locals),
+        )
+        .unwrap();
+        let passed: bool = locals
+            .get_item("passed")
+            .unwrap()
+            .unwrap()
+            .extract()
+            .unwrap();
+        assert!(passed, "Python tests failed");
+    });
+}
@@ -0,0 +1,184 @@
+#![cfg(feature = "std")]
+
+use rust_calculator::cli::{Command, EvalOptions, parse_args};
+use rust_calculator::repl::{Repl, Reply};
+use rust_calculator::variables::is_variable_name;
+use rust_calculator::{AngleMode, CalculatorError};
+use std::io::Write;
+use std::process::{Command as Process, Stdio};
+
+fn output(text: &str) -> Reply {
+    Reply::Output(text.to_string())
+}
+
+#[test]
+fn test_ans_and_variables() {
+    let mut repl = Repl::new(EvalOptions::default());
+    assert_eq!(repl.variable("ans"), Some(0.0));
+    assert_eq!(repl.handle_line("7+8*3"), output("31"));
+    assert_eq!(repl.handle_line("ans-1"), output("30"));
+    assert_eq!(repl.handle_line("width = 2.5"), output("width = 2.5"));
+    assert_eq!(repl.handle_line("height=ans x 2"), output("height = 5"));
+    assert_eq!(repl.handle_line("width x height"), output("12.5"));
+    assert_eq!(repl.variable("ans"), Some(12.5));
+    assert_eq!(
+        repl.handle_line(":vars"),
+        output("ans = 12.5\nheight = 5\nwidth = 2.5")
+    );
+
+    // A failed entry leaves `ans` alone
+    assert!(matches!(repl.handle_line("1/0"), Reply::Error(_)));
+    assert_eq!(repl.variable("ans"), Some(12.5));
+}
+
+#[test]
+fn test_variable_names() {
+    assert!(is_variable_name("a"));
+    assert!(is_variable_name("total_2"));
+    assert!(!is_variable_name("_a"));
+    assert!(!is_variable_name("Rate"));
+    assert!(!is_variable_name("e"));
+    assert!(!is_variable_name("log"));
+    // `x` is multiplication
+    assert!(!is_variable_name("box"));
+
+    let mut repl = Repl::new(EvalOptions::default());
+    assert!(matches!(repl.handle_line("cos = 1"), Reply::Error(_)));
+    assert_eq!(repl.variable("cos"), None);
+    // Unknown names are errors of the calculator, pointed at the name
+    let error = repl.evaluate("2+foo").unwrap_err();
+    assert_eq!(error.span, 2..5);
+}
+
+#[test]
+fn test_multi_line_entries() {
+    let mut repl = Repl::new(EvalOptions::default());
+    assert_eq!(repl.prompt(), "> ");
+    assert_eq!(repl.handle_line("1+\\"), Reply::Nothing);
+    assert_eq!(repl.prompt(), ". ");
+    assert_eq!(repl.handle_line("2"), output("3"));
+    assert_eq!(repl.prompt(), "> ");
+
+    // Open parentheses continue until they are closed
+    assert_eq!(repl.handle_line("2x(3+"), Reply::Nothing);
+    assert_eq!(repl.handle_line("(4"), Reply::Nothing);
+    assert_eq!(repl.handle_line("))"), output("14"));
+
+    // A blank line ends the entry even with parentheses open
+    assert_eq!(repl.handle_line("(1"), Reply::Nothing);
+    assert!(matches!(repl.handle_line(""), Reply::Error(_)));
+    assert_eq!(repl.prompt(), "> ");
+
+    repl.handle_line("(1");
+    repl.cancel();
+    assert_eq!(repl.handle_line("5"), output("5"));
+}
+
+#[test]
+fn test_errors_are_marked_under_the_input() {
+    let mut repl = Repl::new(EvalOptions::default());
+    assert_eq!(
+        repl.handle_line("2+*3"),
+        Reply::Error("    ^\nUnexpected '*'".to_string())
+    );
+    // Positions refer to what was typed, not to the substituted values
+    repl.handle_line("a = 2");
+    assert_eq!(
+        repl.handle_line("a÷(a-a)"),
+        Reply::Error("     ^^^\nDivision by zero".to_string())
+    );
+    assert_eq!(
+        repl.handle_line("b = 1+)"),
+        Reply::Error("        ^\nInvalid expression: missing operand".to_string())
+    );
+    // An assignment needs a value; the variable is left alone
+    assert_eq!(
+        repl.handle_line("a ="),
+        Reply::Error("     ^\nEmpty expression".to_string())
+    );
+    assert_eq!(repl.evaluate("a"), Ok(2.0));
+    // An entry over several lines is repeated above the marker
+    repl.handle_line("1÷\\");
+    assert_eq!(
+        repl.handle_line("0"),
+        Reply::Error("  1÷ 0\n     ^\nDivision by zero".to_string())
+    );
+
+    let error = repl.evaluate("√(a-3)").unwrap_err();
+    assert_eq!(
+        error.kind,
+        CalculatorError::DomainError("√(-1)".to_string())
+    );
+    assert_eq!(error.span, 0..6);
+}
+
+#[test]
+fn test_commands_switch_modes() {
+    let mut repl = Repl::new(EvalOptions::default());
+    assert_eq!(repl.handle_line(":angle"), output("angle: degrees"));
+    assert_eq!(repl.handle_line(":angle rad"), output("angle: radians"));
+    assert_eq!(repl.options.angle_mode, AngleMode::Radians);
+    assert_eq!(repl.handle_line("cos(π)"), output("-1"));
+
+    assert_eq!(repl.handle_line(":precision 3"), output("precision: 3"));
+    assert_eq!(repl.handle_line(":format fixed"), output("format: fixed"));
+    assert_eq!(repl.handle_line("2/3"), output("0.667"));
+
+    assert!(matches!(repl.handle_line(":precision 99"), Reply::Error(_)));
+    assert!(matches!(repl.handle_line(":format roman"), Reply::Error(_)));
+    assert!(matches!(repl.handle_line(":vars a"), Reply::Error(_)));
+    assert!(matches!(repl.handle_line(":bogus"), Reply::Error(_)));
+    assert!(matches!(repl.handle_line(":help"), Reply::Output(_)));
+    assert_eq!(repl.handle_line(":quit"), Reply::Quit);
+}
+
+#[test]
+fn test_parse_repl_command() {
+    let parse = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+    assert_eq!(
+        parse(&["repl"]),
+        Ok(Command::Repl {
+            options: EvalOptions::default()
+        })
+    );
+    assert!(matches!(
+        parse(&["repl", "--angle", "radians"]),
+        Ok(Command::Repl { options }) if options.angle_mode == AngleMode::Radians
+    ));
+    assert!(parse(&["repl", "-e", "1"]).is_err());
+    // Only the first argument is a subcommand
+    assert!(parse(&["-p", "2", "repl"]).is_err());
+}
+
+#[test]
+fn test_repl_reads_piped_input() {
+    let config = std::env::temp_dir().join(format!("rust-calculator-repl-{}", std::process::id()));
+    let mut child = Process::new(env!("CARGO_BIN_EXE_rust-calculator"))
+        .arg("repl")
+        .env("HOME", &config)
+        .env("XDG_CONFIG_HOME", &config)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all("r = 3\nπ x r^2\n1/0\n:quit\n".as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    let _ = std::fs::remove_dir_all(&config);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "r = 3\n28.27433388\n"
+    );
+    assert!(
+        String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("Division by zero")
+    );
+}
@@ -0,0 +1,223 @@
+#![cfg(feature = "std")]
+
+use rust_calculator::{CalculatorError, Constant, Operation, RpnCalculator};
+
+fn push(rpn: &mut RpnCalculator, digits: &[u8]) {
+    for &digit in digits {
+        rpn.handle_number_input(digit);
+    }
+    rpn.handle_enter();
+}
+
+#[test]
+fn test_new_rpn_calculator() {
+    let rpn = RpnCalculator::new();
+    assert!(rpn.stack.is_empty());
+    assert_eq!(rpn.entry, "");
+    assert_eq!(rpn.last_x, None);
+    assert_eq!(rpn.level_display(0), "0");
+    assert_eq!(rpn.level_display(1), "");
+}
+
+#[test]
+fn test_number_entry() {
+    let mut rpn = RpnCalculator::new();
+    rpn.handle_number_input(1);
+    rpn.handle_number_input(2);
+    rpn.handle_decimal_input();
+    rpn.handle_number_input(5);
+    rpn.handle_decimal_input(); // Ignored, already has a decimal point
+    assert_eq!(rpn.entry, "12.5");
+    assert!(rpn.stack.is_empty());
+    assert_eq!(rpn.x_value(), Some(12.5));
+}
+
+#[test]
+fn test_enter_pushes_entry_and_duplicates_x() {
+    let mut rpn = RpnCalculator::new();
+    push(&mut rpn, &[4]);
+    assert_eq!(rpn.stack, vec![4.0]);
+    assert_eq!(rpn.entry, "");
+
+    rpn.handle_enter();
+    assert_eq!(rpn.stack, vec![4.0, 4.0]);
+}
+
+#[test]
+fn test_operation_applies_immediately() {
+    let mut rpn = RpnCalculator::new();
+    push(&mut rpn, &[7]);
+    rpn.handle_number_input(8);
+    rpn.handle_number_input(3);
+    rpn.handle_operation_input(Operation::Subtract);
+    assert_eq!(rpn.stack, vec![-76.0]);
+    assert_eq!(rpn.last_x, Some(83.0));
+}
+
+#[test]
+fn test_chained_operations() {
+    // 7 ENTER 8 ENTER 3 x + = 31
+    let mut rpn = RpnCalculator::new();
+    push(&mut rpn, &[7]);
+    push(&mut rpn, &[8]);
+    rpn.handle_number_input(3);
+    rpn.handle_operation_input(Operation::Multiply);
+    rpn.handle_operation_input(Operation::Add);
+    assert_eq!(rpn.stack, vec![31.0]);
+    assert_eq!(rpn.level_display(0), "31");
+}
+
+#[test]
+fn test_operation_with_too_few_arguments() {
+    let mut rpn = RpnCalculator::new();
+    rpn.handle_number_input(5);
+    rpn.handle_operation_input(Operation::Add);
+    assert_eq!(rpn.stack, vec![5.0]);
+    assert_eq!(rpn.error, Some(CalculatorError::TooFewArguments));
+    assert_eq!(rpn.level_display(0), "Too few arguments");
+
+    // The next input clears the error
+    rpn.handle_number_input(1);
+    assert_eq!(rpn.error, None);
+}
+
+#[test]
+fn test_division_by_zero_keeps_stack() {
+    let mut rpn = RpnCalculator::new();
+    push(&mut rpn, &[5]);
+    rpn.handle_number_input(0);
+    rpn.handle_operation_input(Operation::Divide);
+    assert_eq!(rpn.stack, vec![5.0, 0.0]);
+    assert_eq!(rpn.error, Some(CalculatorError::DivisionByZero));
+}
+
+#[test]
+fn test_swap() {
+    let mut rpn = RpnCalculator::new();
+    push(&mut rpn, &[1]);
+    rpn.handle_number_input(2);
+    rpn.handle_swap();
+    assert_eq!(rpn.stack, vec![2.0, 1.0]);
+
+    let mut single = RpnCalculator::new();
+    push(&mut single, &[1]);
+    single.handle_swap();
+    assert_eq!(single.error, Some(CalculatorError::TooFewArguments));
+}
+
+#[test]
+fn test_drop() {
+    let mut rpn = RpnCalculator::new();
+    push(&mut rpn, &[1]);
+    push(&mut rpn, &[2]);
+    rpn.handle_number_input(3);
+
+    // Drop discards the entry first
+    rpn.handle_drop();
+    assert_eq!(rpn.entry, "");
+    assert_eq!(rpn.stack, vec![1.0, 2.0]);
+
+    rpn.handle_drop();
+    assert_eq!(rpn.stack, vec![1.0]);
+}
+
+#[test]
+fn test_roll_down() {
+    let mut rpn = RpnCalculator::new();
+    push(&mut rpn, &[1]);
+    push(&mut rpn, &[2]);
+    push(&mut rpn, &[3]);
+    rpn.handle_number_input(4);
+    rpn.handle_roll_down();
+    // T=1 Z=2 Y=3 X=4 becomes T=4 Z=1 Y=2 X=3
+    assert_eq!(rpn.stack, vec![4.0, 1.0, 2.0, 3.0]);
+
+    let mut empty = RpnCalculator::new();
+    empty.handle_roll_down();
+    assert!(empty.stack.is_empty());
+    assert_eq!(empty.error, Some(CalculatorError::TooFewArguments));
+}
+
+#[test]
+fn test_last_x() {
+    let mut rpn = RpnCalculator::new();
+    rpn.handle_last_x();
+    assert_eq!(rpn.error, Some(CalculatorError::NoLastX));
+
+    push(&mut rpn, &[6]);
+    rpn.handle_number_input(2);
+    rpn.handle_operation_input(Operation::Divide);
+    rpn.handle_last_x();
+    assert_eq!(rpn.stack, vec![3.0, 2.0]);
+}
+
+#[test]
+fn test_sign_toggle() {
+    let mut rpn = RpnCalculator::new();
+    rpn.handle_number_input(5);
+    rpn.handle_sign_toggle_input();
+    assert_eq!(rpn.entry, "-5");
+    rpn.handle_sign_toggle_input();
+    assert_eq!(rpn.entry, "5");
+
+    rpn.handle_enter();
+    rpn.handle_sign_toggle_input();
+    assert_eq!(rpn.stack, vec![-5.0]);
+}
+
+#[test]
+fn test_percentage() {
+    let mut rpn = RpnCalculator::new();
+    push(&mut rpn, &[2, 0, 0]);
+    rpn.handle_number_input(1);
+    rpn.handle_number_input(5);
+    rpn.handle_percentage_input();
+    assert_eq!(rpn.stack, vec![200.0, 30.0]);
+    assert_eq!(rpn.last_x, Some(15.0));
+}
+
+#[test]
+fn test_backspace_and_clear() {
+    let mut rpn = RpnCalculator::new();
+    push(&mut rpn, &[9]);
+    rpn.handle_number_input(1);
+    rpn.handle_number_input(2);
+    rpn.handle_backspace_input();
+    assert_eq!(rpn.entry, "1");
+
+    rpn.handle_clear_input();
+    assert!(rpn.stack.is_empty());
+    assert_eq!(rpn.entry, "");
+    assert_eq!(rpn.last_x, None);
+}
+
+#[test]
+fn test_level_display_lifts_stack_while_typing() {
+    let mut rpn = RpnCalculator::new();
+    push(&mut rpn, &[1]);
+    push(&mut rpn, &[2]);
+    assert_eq!(rpn.level_display(0), "2");
+    assert_eq!(rpn.level_display(1), "1");
+
+    rpn.handle_number_input(3);
+    assert_eq!(rpn.level_display(0), "3");
+    assert_eq!(rpn.level_display(1), "2");
+    assert_eq!(rpn.level_display(2), "1");
+    assert_eq!(rpn.level_display(3), "");
+}
+
+#[test]
+fn test_unary_and_constant_input() {
+    let mut rpn = RpnCalculator::new();
+    push(&mut rpn, &[1, 6]);
+    rpn.handle_unary_input(|x| Ok(x.sqrt()));
+    assert_eq!(rpn.stack, vec![4.0]);
+    assert_eq!(rpn.last_x, Some(16.0));
+
+    rpn.handle_constant_input(Constant::Pi);
+    assert_eq!(rpn.stack, vec![4.0, std::f64::consts::PI]);
+
+    rpn.handle_clear_input();
+    rpn.handle_unary_input(|x| Ok(x.sqrt()));
+    assert_eq!(rpn.error, Some(CalculatorError::TooFewArguments));
+}
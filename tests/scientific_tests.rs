@@ -0,0 +1,51 @@
+#![cfg(feature = "std")]
+
+use rust_calculator::{Constant, Function, ScientificAction, ScientificKey};
+
+#[test]
+fn test_layout_contains_every_key_once() {
+    let keys: Vec<ScientificKey> = ScientificKey::LAYOUT.iter().flatten().copied().collect();
+    assert_eq!(keys.len(), 12);
+    for (i, key) in keys.iter().enumerate() {
+        assert!(!keys[i + 1..].contains(key), "{key:?} appears twice");
+    }
+}
+
+#[test]
+fn test_second_layer_labels() {
+    assert_eq!(ScientificKey::Square.label(false), "x²");
+    assert_eq!(ScientificKey::Square.label(true), "x³");
+    assert_eq!(ScientificKey::Ln.label(true), "eˣ");
+    assert_eq!(ScientificKey::Log.label(true), "10ˣ");
+    // Keys without a second function keep their label
+    assert_eq!(ScientificKey::Pi.label(true), "π");
+}
+
+#[test]
+fn test_second_layer_actions() {
+    assert_eq!(
+        ScientificKey::Square.action(false),
+        ScientificAction::RaiseTo(2)
+    );
+    assert_eq!(
+        ScientificKey::Square.action(true),
+        ScientificAction::RaiseTo(3)
+    );
+    assert_eq!(
+        ScientificKey::Reciprocal.action(false),
+        ScientificAction::RaiseTo(-1)
+    );
+    assert_eq!(
+        ScientificKey::Reciprocal.action(true),
+        ScientificAction::Function(Function::Abs)
+    );
+    assert_eq!(
+        ScientificKey::Cos.action(true),
+        ScientificAction::Function(Function::Acos)
+    );
+    assert_eq!(ScientificKey::Ln.action(true), ScientificAction::Exp);
+    assert_eq!(
+        ScientificKey::E.action(false),
+        ScientificAction::Constant(Constant::E)
+    );
+}
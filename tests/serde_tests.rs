@@ -0,0 +1,324 @@
+#![cfg(feature = "serde")]
+
+use rust_calculator::focus::FocusDirection;
+use rust_calculator::scientific::ScientificKey;
+use rust_calculator::serialization::{FORMAT_VERSION, FormatError, from_json, to_json};
+use rust_calculator::settings::{DecimalSeparator, DisplayFormat};
+use rust_calculator::theme::{CustomTheme, Palette};
+use rust_calculator::{
+    AngleMode, Calculator, CalculatorError, CalculatorUIState, ExpressionError, Locale, Operation,
+    Settings, SyntaxError, ThemeChoice, UIMessage,
+};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fmt::Debug;
+
+/// Asserts that a value survives `to_json` and `from_json` unchanged.
+fn assert_round_trip<T: Serialize + DeserializeOwned + PartialEq + Debug>(value: &T) {
+    let json = to_json(value).unwrap();
+    assert_eq!(&from_json::<T>(&json).unwrap(), value, "{}", json);
+}
+
+/// Asserts that `values` has at least one value of each of the `count` variants that
+/// `variant` numbers.
+fn assert_every_variant<T: Debug>(values: &[T], variant: impl Fn(&T) -> usize, count: usize) {
+    let mut seen = vec![false; count];
+    for value in values {
+        seen[variant(value)] = true;
+    }
+    let missing: Vec<usize> = (0..count).filter(|&index| !seen[index]).collect();
+    assert!(missing.is_empty(), "variants {:?} are not listed", missing);
+}
+
+const OPERATIONS: [Operation; 5] = [
+    Operation::Add,
+    Operation::Subtract,
+    Operation::Multiply,
+    Operation::Divide,
+    Operation::Power,
+];
+
+fn syntax_errors() -> Vec<SyntaxError> {
+    vec![
+        SyntaxError::Empty,
+        SyntaxError::MultipleDecimalPoints("1.2.".to_string()),
+        SyntaxError::MultipleExponents("1e2e".to_string()),
+        SyntaxError::Unexpected("+".to_string()),
+        SyntaxError::ConsecutiveOperators,
+        SyntaxError::MissingOperand,
+        SyntaxError::TooManyOperands,
+        SyntaxError::ExpectedParenthesis("sin".to_string()),
+        SyntaxError::UnknownFunction("foo".to_string()),
+        SyntaxError::MismatchedParentheses,
+    ]
+}
+
+fn calculator_errors() -> Vec<CalculatorError> {
+    // A new variant fails to compile here, and one missing from the list below fails the test
+    let variant = |error: &CalculatorError| match error {
+        CalculatorError::DivisionByZero => 0,
+        CalculatorError::InvalidNumber(_) => 1,
+        CalculatorError::InvalidExpression(_) => 2,
+        CalculatorError::InputTooLong => 3,
+        CalculatorError::InvalidCharacters(_) => 4,
+        CalculatorError::NumberOutOfRange(_) => 5,
+        CalculatorError::DomainError(_) => 6,
+        CalculatorError::TooFewArguments => 7,
+        CalculatorError::NoLastX => 8,
+        CalculatorError::InvalidVariableName(_) => 9,
+    };
+    let errors: Vec<CalculatorError> = [
+        CalculatorError::DivisionByZero,
+        CalculatorError::InvalidNumber("1..2".to_string()),
+        CalculatorError::InputTooLong,
+        CalculatorError::InvalidCharacters("ab".to_string()),
+        CalculatorError::NumberOutOfRange("1e200".to_string()),
+        CalculatorError::DomainError("√(-4)".to_string()),
+        CalculatorError::TooFewArguments,
+        CalculatorError::NoLastX,
+        CalculatorError::InvalidVariableName("sin".to_string()),
+    ]
+    .into_iter()
+    .chain(syntax_errors().into_iter().map(CalculatorError::from))
+    .collect();
+    assert_every_variant(&errors, variant, 10);
+    errors
+}
+
+fn messages() -> Vec<UIMessage> {
+    // A new variant fails to compile here, and one missing from the list below fails the test
+    let variant = |message: &UIMessage| match message {
+        UIMessage::NumberPressed(_) => 0,
+        UIMessage::DecimalPressed => 1,
+        UIMessage::OperationPressed(_) => 2,
+        UIMessage::EqualsPressed => 3,
+        UIMessage::ClearPressed => 4,
+        UIMessage::BackspacePressed => 5,
+        UIMessage::PercentagePressed => 6,
+        UIMessage::SignTogglePressed => 7,
+        UIMessage::OpenParenPressed => 8,
+        UIMessage::CloseParenPressed => 9,
+        UIMessage::DeletePressed => 10,
+        UIMessage::CursorLeft => 11,
+        UIMessage::CursorRight => 12,
+        UIMessage::CursorHome => 13,
+        UIMessage::CursorEnd => 14,
+        UIMessage::PastePressed => 15,
+        UIMessage::LoadExpression(_) => 16,
+        UIMessage::CopyResult => 17,
+        UIMessage::ModeTogglePressed => 18,
+        UIMessage::EnterPressed => 19,
+        UIMessage::SwapPressed => 20,
+        UIMessage::DropPressed => 21,
+        UIMessage::RollDownPressed => 22,
+        UIMessage::LastXPressed => 23,
+        UIMessage::ScientificTogglePressed => 24,
+        UIMessage::SecondPressed => 25,
+        UIMessage::AngleModePressed => 26,
+        UIMessage::ScientificKeyPressed(_) => 27,
+        UIMessage::ThemeSelected(_) => 28,
+        UIMessage::NextTheme => 29,
+        UIMessage::GraphTogglePressed => 30,
+        UIMessage::GraphInputChanged(_) => 31,
+        UIMessage::GraphZoom { .. } => 32,
+        UIMessage::GraphPan { .. } => 33,
+        UIMessage::GraphResetPressed => 34,
+        UIMessage::SettingsTogglePressed => 35,
+        UIMessage::SettingsChanged(_) => 36,
+        UIMessage::FocusNext => 37,
+        UIMessage::FocusPrevious => 38,
+        UIMessage::FocusMove(_) => 39,
+        UIMessage::FocusActivate => 40,
+        UIMessage::FocusClear => 41,
+    };
+    let mut messages = vec![
+        UIMessage::NumberPressed(7),
+        UIMessage::DecimalPressed,
+        UIMessage::EqualsPressed,
+        UIMessage::ClearPressed,
+        UIMessage::BackspacePressed,
+        UIMessage::PercentagePressed,
+        UIMessage::SignTogglePressed,
+        UIMessage::OpenParenPressed,
+        UIMessage::CloseParenPressed,
+        UIMessage::DeletePressed,
+        UIMessage::CursorLeft,
+        UIMessage::CursorRight,
+        UIMessage::CursorHome,
+        UIMessage::CursorEnd,
+        UIMessage::PastePressed,
+        UIMessage::LoadExpression("12x(3+4)".to_string()),
+        UIMessage::CopyResult,
+        UIMessage::ModeTogglePressed,
+        UIMessage::EnterPressed,
+        UIMessage::SwapPressed,
+        UIMessage::DropPressed,
+        UIMessage::RollDownPressed,
+        UIMessage::LastXPressed,
+        UIMessage::ScientificTogglePressed,
+        UIMessage::SecondPressed,
+        UIMessage::AngleModePressed,
+        UIMessage::ThemeSelected(ThemeChoice::Custom("Solarized".to_string())),
+        UIMessage::NextTheme,
+        UIMessage::GraphTogglePressed,
+        UIMessage::GraphInputChanged("x^2; sin(x)".to_string()),
+        UIMessage::GraphZoom {
+            factor: 0.5,
+            x: 1.25,
+            y: -3.0,
+        },
+        UIMessage::GraphPan { dx: 2.0, dy: -0.5 },
+        UIMessage::GraphResetPressed,
+        UIMessage::SettingsTogglePressed,
+        UIMessage::SettingsChanged(Settings {
+            precision: 3,
+            locale: Locale::German,
+            keymap: Some("/tmp/keys.toml".into()),
+            ..Settings::default()
+        }),
+        UIMessage::FocusNext,
+        UIMessage::FocusPrevious,
+        UIMessage::FocusActivate,
+        UIMessage::FocusClear,
+    ];
+    messages.extend(OPERATIONS.map(UIMessage::OperationPressed));
+    messages.extend(
+        ScientificKey::LAYOUT
+            .as_flattened()
+            .iter()
+            .map(|key| UIMessage::ScientificKeyPressed(*key)),
+    );
+    messages.extend(ThemeChoice::BUILT_IN.map(UIMessage::ThemeSelected));
+    messages.extend(
+        [
+            FocusDirection::Up,
+            FocusDirection::Down,
+            FocusDirection::Left,
+            FocusDirection::Right,
+        ]
+        .map(UIMessage::FocusMove),
+    );
+    assert_every_variant(&messages, variant, 42);
+    messages
+}
+
+#[test]
+fn test_every_message_round_trips() {
+    for message in messages() {
+        assert_round_trip(&message);
+    }
+}
+
+#[test]
+fn test_every_error_and_operation_round_trips() {
+    for error in calculator_errors() {
+        assert_round_trip(&error);
+        assert_round_trip(&ExpressionError::new(error, 2..5));
+    }
+    for operation in OPERATIONS {
+        assert_round_trip(&operation);
+    }
+    for mode in [AngleMode::Degrees, AngleMode::Radians] {
+        assert_round_trip(&mode);
+    }
+    for format in DisplayFormat::ALL {
+        assert_round_trip(&format);
+    }
+    for separator in DecimalSeparator::ALL {
+        assert_round_trip(&separator);
+    }
+    for locale in Locale::ALL {
+        assert_round_trip(&locale);
+    }
+}
+
+#[test]
+fn test_calculator_state_round_trips() {
+    let mut calculator = Calculator::new();
+    assert_round_trip(&calculator);
+    calculator.expression = "1÷(2-2)".to_string();
+    calculator.cursor = Some(3);
+    calculator.error = calculator.evaluate_spanned(&calculator.expression).err();
+    assert!(calculator.error.is_some());
+    assert_round_trip(&calculator);
+
+    let mut state = CalculatorUIState::new();
+    assert_round_trip(&state);
+    state.custom_themes.push(CustomTheme {
+        name: "Solarized".to_string(),
+        palette: Palette::light(),
+    });
+    for message in [
+        UIMessage::NumberPressed(4),
+        UIMessage::OperationPressed(Operation::Divide),
+        UIMessage::NumberPressed(0),
+        UIMessage::EqualsPressed,
+        UIMessage::ScientificTogglePressed,
+        UIMessage::GraphInputChanged("x^2; 1/".to_string()),
+        UIMessage::GraphZoom {
+            factor: 2.0,
+            x: 0.0,
+            y: 0.0,
+        },
+        UIMessage::ModeTogglePressed,
+        UIMessage::NumberPressed(9),
+        UIMessage::EnterPressed,
+        UIMessage::SwapPressed,
+        UIMessage::FocusNext,
+    ] {
+        state.process_message(message);
+        assert_round_trip(&state);
+    }
+    assert!(state.rpn.error.is_some() && state.graph.error.is_some());
+}
+
+/// Plain JSON, without the version envelope of `to_json`.
+fn json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap()
+}
+
+#[test]
+fn test_representation_is_stable() {
+    assert_eq!(FORMAT_VERSION, 1);
+    assert_eq!(json(&Operation::Subtract), r#""subtract""#);
+    assert_eq!(
+        json(&UIMessage::GraphPan { dx: 1.0, dy: 2.0 }),
+        r#"{"graph_pan":{"dx":1.0,"dy":2.0}}"#
+    );
+    assert_eq!(
+        json(&ThemeChoice::Custom("Mono".to_string())),
+        r#"{"custom":"Mono"}"#
+    );
+    assert_eq!(
+        json(&ExpressionError::new(
+            CalculatorError::from(SyntaxError::MissingOperand),
+            1..2
+        )),
+        r#"{"kind":{"invalid_expression":"missing_operand"},"span":{"start":1,"end":2}}"#
+    );
+    assert_eq!(json(&Palette::dark().background), r##""#2b2d31""##);
+}
+
+#[test]
+fn test_versions_and_invalid_data_are_rejected() {
+    assert!(matches!(
+        from_json::<Operation>(r#"{"version":0,"data":"add"}"#),
+        Err(FormatError::UnsupportedVersion(0))
+    ));
+    assert!(matches!(
+        from_json::<Operation>(r#"{"data":"add"}"#),
+        Err(FormatError::Json(_))
+    ));
+    assert!(matches!(
+        from_json::<Operation>(r#"{"version":1,"data":"modulo"}"#),
+        Err(FormatError::Json(_))
+    ));
+
+    let error = from_json::<UIMessage>(r#"{"version":1,"data":{"settings_changed":{}}}"#);
+    assert!(error.is_err());
+    // Graph functions are checked like typed ones
+    let state = to_json(&CalculatorUIState::new()).unwrap();
+    let broken = state.replace(r#""functions":[]"#, r#""functions":["2x^"]"#);
+    assert_ne!(state, broken);
+    assert!(from_json::<CalculatorUIState>(&broken).is_err());
+}
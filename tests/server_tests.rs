@@ -0,0 +1,223 @@
+#![cfg(feature = "std")]
+
+use rust_calculator::cli::{Command, EvalOptions, Transport, parse_args};
+use rust_calculator::server::{CALCULATION_ERROR, INVALID_PARAMS, METHOD_NOT_FOUND, Server};
+use serde_json::{Value, json};
+use std::io::Write;
+use std::process::{Command as Process, Stdio};
+
+fn request(server: &mut Server, method: &str, params: Value) -> Value {
+    let message = json!({"jsonrpc": "2.0", "id": 7, "method": method, "params": params});
+    let response = server.handle_message(&message.to_string()).unwrap();
+    let response: Value = serde_json::from_str(&response).unwrap();
+    assert_eq!(response["id"], 7);
+    response
+}
+
+#[test]
+fn test_evaluate_and_errors() {
+    let mut server = Server::new(EvalOptions::default());
+    let response = request(&mut server, "evaluate", json!({"expression": "2^0.5"}));
+    assert_eq!(
+        response["result"],
+        json!({"value": std::f64::consts::SQRT_2, "formatted": "1.41421356"})
+    );
+
+    let response = request(&mut server, "evaluate", json!({"expression": "1+4÷(2-2)"}));
+    assert_eq!(
+        response["error"],
+        json!({
+            "code": CALCULATION_ERROR,
+            "message": "Division by zero",
+            "data": {"kind": "division_by_zero", "start": 5, "end": 8},
+        })
+    );
+}
+
+#[test]
+fn test_parse_returns_tokens_and_spans() {
+    let mut server = Server::new(EvalOptions::default());
+    let response = request(&mut server, "parse", json!({"expression": "√(4)x π"}));
+    let tokens = &response["result"]["tokens"];
+    assert_eq!(tokens.as_array().unwrap().len(), 6);
+    assert_eq!(
+        tokens[0],
+        json!({"kind": "function", "text": "√", "start": 0, "end": 1})
+    );
+    assert_eq!(
+        tokens[2],
+        json!({"kind": "number", "text": "4", "start": 2, "end": 3, "value": 4.0})
+    );
+    assert_eq!(tokens[5]["text"], "π");
+
+    let response = request(&mut server, "parse", json!({"expression": "2+q"}));
+    assert_eq!(response["error"]["data"]["start"], 2);
+}
+
+#[test]
+fn test_parse_reads_session_variables() {
+    let mut server = Server::new(EvalOptions::default());
+    let set = json!({"session": "a.md", "name": "rate", "value": 2});
+    request(&mut server, "setVariable", set);
+    let evaluate = json!({"session": "a.md", "expression": "rate x 3"});
+    assert_eq!(
+        request(&mut server, "evaluate", evaluate)["result"]["value"],
+        6.0
+    );
+
+    let parse = json!({"session": "a.md", "expression": "rate x 3"});
+    let tokens = &request(&mut server, "parse", parse)["result"]["tokens"];
+    assert_eq!(
+        tokens,
+        &json!([
+            {"kind": "variable", "text": "rate", "start": 0, "end": 4, "value": 2.0},
+            {"kind": "operator", "text": "x", "start": 5, "end": 6},
+            {"kind": "number", "text": "3", "start": 7, "end": 8, "value": 3.0},
+        ])
+    );
+
+    // Without the variable the name is an unknown function, at its place in the text
+    let parse = json!({"session": "b.md", "expression": "1+rate x 3"});
+    let response = request(&mut server, "parse", parse);
+    assert_eq!(response["error"]["data"]["start"], 2);
+    assert_eq!(response["error"]["data"]["end"], 6);
+}
+
+#[test]
+fn test_sessions_are_independent() {
+    let mut server = Server::new(EvalOptions::default());
+    let set = json!({"session": "a.md", "name": "rate", "value": 1.5});
+    assert_eq!(
+        request(&mut server, "setVariable", set)["result"],
+        Value::Null
+    );
+    let configure = json!({"session": "a.md", "angle": "radians", "precision": 2});
+    request(&mut server, "configure", configure);
+
+    let evaluate = json!({"session": "a.md", "expression": "rate x cos(π)"});
+    assert_eq!(
+        request(&mut server, "evaluate", evaluate)["result"],
+        json!({"value": -1.5, "formatted": "-1.5"})
+    );
+    let get = json!({"session": "a.md", "name": "rate"});
+    assert_eq!(request(&mut server, "getVariable", get)["result"], 1.5);
+
+    // Another session has neither the variable nor the angle mode
+    let get = json!({"session": "b.md", "name": "rate"});
+    assert_eq!(
+        request(&mut server, "getVariable", get)["result"],
+        Value::Null
+    );
+    let evaluate = json!({"expression": "cos(180)"});
+    assert_eq!(
+        request(&mut server, "evaluate", evaluate)["result"]["value"],
+        -1.0
+    );
+
+    let close = json!({"session": "a.md"});
+    assert_eq!(
+        request(&mut server, "closeSession", close.clone())["result"],
+        true
+    );
+    assert_eq!(request(&mut server, "closeSession", close)["result"], false);
+}
+
+#[test]
+fn test_format() {
+    let mut server = Server::new(EvalOptions::default());
+    let params = json!({"value": 1234.5678, "precision": 2, "format": "fixed"});
+    assert_eq!(request(&mut server, "format", params)["result"], "1234.57");
+    let params = json!({"value": 1234.5678});
+    assert_eq!(
+        request(&mut server, "format", params)["result"],
+        "1234.5678"
+    );
+    let params = json!({"value": 1, "format": "hex"});
+    assert_eq!(
+        request(&mut server, "format", params)["error"]["code"],
+        INVALID_PARAMS
+    );
+}
+
+#[test]
+fn test_protocol_errors_batches_and_notifications() {
+    let mut server = Server::new(EvalOptions::default());
+    let response = request(&mut server, "solve", json!({}));
+    assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+    let response = request(&mut server, "evaluate", json!({"expr": "1"}));
+    assert_eq!(response["error"]["code"], INVALID_PARAMS);
+
+    let response: Value =
+        serde_json::from_str(&server.handle_message("{not json").unwrap()).unwrap();
+    assert_eq!(response["error"]["code"], -32700);
+    assert_eq!(response["id"], Value::Null);
+
+    // Notifications are carried out but not answered
+    let notification =
+        r#"{"jsonrpc":"2.0","method":"setVariable","params":{"name":"n","value":3}}"#;
+    assert_eq!(server.handle_message(notification), None);
+    let batch = json!([
+        {"jsonrpc": "2.0", "id": 1, "method": "evaluate", "params": {"expression": "n+1"}},
+        {"jsonrpc": "2.0", "method": "evaluate", "params": {"expression": "n"}},
+        {"jsonrpc": "2.0", "id": 2, "method": "getVariable", "params": {"name": "n"}},
+    ]);
+    let response: Value =
+        serde_json::from_str(&server.handle_message(&batch.to_string()).unwrap()).unwrap();
+    assert_eq!(response[0]["result"]["value"], 4.0);
+    assert_eq!(response[1]["result"], 3.0);
+    assert_eq!(response.as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn test_parse_serve_command() {
+    let parse = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+    assert_eq!(
+        parse(&["serve", "--stdio"]),
+        Ok(Command::Serve {
+            transport: Transport::Stdio,
+            options: EvalOptions::default()
+        })
+    );
+    assert!(parse(&["serve"]).is_err());
+    assert!(parse(&["--stdio"]).is_err());
+    assert!(parse(&["serve", "--stdio", "-e", "1"]).is_err());
+    assert!(parse(&["serve", "--stdio", "--http", "127.0.0.1:0"]).is_err());
+}
+
+#[test]
+fn test_serve_over_stdio() {
+    let mut child = Process::new(env!("CARGO_BIN_EXE_rust-calculator"))
+        .args(["serve", "--stdio", "--precision", "3"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(
+            concat!(
+                r#"{"jsonrpc":"2.0","id":1,"method":"setVariable","params":{"name":"r","value":2}}"#,
+                "\n\n",
+                r#"{"jsonrpc":"2.0","id":2,"method":"evaluate","params":{"expression":"π x r^2"}}"#,
+                "\n",
+                r#"{"jsonrpc":"2.0","id":3,"method":"evaluate","params":{"expression":"1/0"}}"#,
+                "\n",
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(output.status.code(), Some(0));
+
+    let responses: Vec<Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(responses.len(), 3);
+    assert_eq!(responses[0]["result"], Value::Null);
+    assert_eq!(responses[1]["result"]["formatted"], "12.566");
+    assert_eq!(responses[2]["error"]["data"]["kind"], "division_by_zero");
+}
@@ -0,0 +1,183 @@
+#![cfg(feature = "std")]
+
+use rust_calculator::settings::{
+    DecimalSeparator, DisplayFormat, NumberFormat, Settings, SettingsWarning,
+};
+use rust_calculator::{AngleMode, Locale, ThemeChoice};
+use std::path::PathBuf;
+
+#[test]
+fn test_parse_full_settings_file() {
+    let (settings, warnings) = Settings::parse(
+        r#"
+        precision = 3
+        display_format = "fixed"
+        angle_mode = "radians"
+        theme = "high-contrast"
+        keymap = "/home/me/keys.toml"
+        decimal_separator = "comma"
+        locale = "de"
+        "#,
+    );
+    assert!(warnings.is_empty(), "{warnings:?}");
+    assert_eq!(
+        settings,
+        Settings {
+            precision: 3,
+            display_format: DisplayFormat::Fixed,
+            angle_mode: AngleMode::Radians,
+            theme: ThemeChoice::HighContrast,
+            keymap: Some(PathBuf::from("/home/me/keys.toml")),
+            decimal_separator: DecimalSeparator::Comma,
+            locale: Locale::German,
+        }
+    );
+}
+
+#[test]
+fn test_missing_keys_keep_defaults() {
+    let (settings, warnings) = Settings::parse("theme = \"Solarized\"");
+    assert!(warnings.is_empty());
+    assert_eq!(
+        settings,
+        Settings {
+            theme: ThemeChoice::Custom("Solarized".to_string()),
+            ..Settings::default()
+        }
+    );
+}
+
+#[test]
+fn test_invalid_values_fall_back_individually() {
+    let (settings, warnings) = Settings::parse(
+        r#"
+        precision = -1
+        display_format = "engineering"
+        angle_mode = 90
+        decimal_separator = "comma"
+        volume = 11
+        "#,
+    );
+    assert_eq!(
+        settings,
+        Settings {
+            decimal_separator: DecimalSeparator::Comma,
+            ..Settings::default()
+        }
+    );
+    assert_eq!(
+        warnings,
+        [
+            SettingsWarning::InvalidAngleMode,
+            SettingsWarning::InvalidDisplayFormat,
+            SettingsWarning::InvalidPrecision,
+            SettingsWarning::UnknownSetting("volume".to_string()),
+        ]
+    );
+    assert_eq!(
+        warnings[2].to_string(),
+        "`precision` must be a whole number from 0 to 15, using 8"
+    );
+}
+
+#[test]
+fn test_precision_range() {
+    let (settings, warnings) = Settings::parse("precision = 15");
+    assert_eq!(settings.precision, 15);
+    assert!(warnings.is_empty());
+    let (settings, warnings) = Settings::parse("precision = 16");
+    assert_eq!(settings.precision, Settings::DEFAULT_PRECISION);
+    assert_eq!(warnings.len(), 1);
+}
+
+#[test]
+fn test_invalid_toml_gives_defaults() {
+    let (settings, warnings) = Settings::parse("precision = = 3");
+    assert_eq!(settings, Settings::default());
+    assert!(matches!(warnings[..], [SettingsWarning::InvalidToml(_)]));
+}
+
+#[test]
+fn test_locale_brings_its_decimal_separator() {
+    let (settings, warnings) = Settings::parse("locale = \"de-DE\"");
+    assert!(warnings.is_empty());
+    assert_eq!(settings.locale, Locale::German);
+    assert_eq!(settings.decimal_separator, DecimalSeparator::Comma);
+
+    // An explicit separator wins
+    let (settings, _) = Settings::parse("locale = \"de\"\ndecimal_separator = \"point\"");
+    assert_eq!(settings.decimal_separator, DecimalSeparator::Point);
+
+    let (settings, warnings) = Settings::parse("locale = \"klingon\"");
+    assert_eq!(settings.locale, Locale::English);
+    assert_eq!(warnings, [SettingsWarning::InvalidLocale]);
+}
+
+#[test]
+fn test_round_trip() {
+    let settings = Settings {
+        precision: 0,
+        display_format: DisplayFormat::Scientific,
+        angle_mode: AngleMode::Radians,
+        theme: ThemeChoice::Custom("Night \"Owl\"".to_string()),
+        keymap: Some(PathBuf::from("C:\\Users\\me\\keys.toml")),
+        // A separator other than the language's usual one survives the round trip
+        decimal_separator: DecimalSeparator::Comma,
+        locale: Locale::English,
+    };
+    assert_eq!(Settings::parse(&settings.to_toml()), (settings, Vec::new()));
+    assert_eq!(
+        Settings::parse(&Settings::default().to_toml()),
+        (Settings::default(), Vec::new())
+    );
+}
+
+#[test]
+fn test_save_and_load() {
+    let dir = std::env::temp_dir().join(format!("rust-calculator-settings-{}", std::process::id()));
+    let path = dir.join("nested").join("settings.toml");
+    let settings = Settings {
+        precision: 4,
+        ..Settings::default()
+    };
+    settings.save(&path).unwrap();
+    assert_eq!(Settings::load(&path), (settings, Vec::new()));
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    // A missing file is not an error
+    assert_eq!(Settings::load(&path), (Settings::default(), Vec::new()));
+}
+
+#[test]
+fn test_number_formats() {
+    let auto = NumberFormat::default();
+    assert_eq!(auto.format(1.0 / 3.0), "0.33333333");
+    assert_eq!(auto.format(84.0), "84");
+    assert_eq!(auto.format(0.00001), "1.0000e-5");
+
+    let short = NumberFormat {
+        precision: 2,
+        display_format: DisplayFormat::Auto,
+    };
+    assert_eq!(short.format(1.0 / 3.0), "0.33");
+    assert_eq!(short.format(1234567.0), "1.23e6");
+
+    let whole = NumberFormat {
+        precision: 0,
+        display_format: DisplayFormat::Auto,
+    };
+    assert_eq!(whole.format(120.0), "120");
+
+    let fixed = NumberFormat {
+        precision: 3,
+        display_format: DisplayFormat::Fixed,
+    };
+    assert_eq!(fixed.format(84.0), "84.000");
+    assert_eq!(fixed.format(1e20), "1.000e20");
+
+    let scientific = NumberFormat {
+        precision: 2,
+        display_format: DisplayFormat::Scientific,
+    };
+    assert_eq!(scientific.format(84.0), "8.40e1");
+}
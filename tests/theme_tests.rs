@@ -0,0 +1,103 @@
+#![cfg(feature = "std")]
+
+use rust_calculator::theme::{Rgb, load_custom_themes, parse_custom_themes};
+use rust_calculator::{CalculatorUIState, Palette, ThemeChoice, UIMessage};
+
+#[test]
+fn test_rgb_from_hex() {
+    assert_eq!(Rgb::from_hex("#000000"), Ok(Rgb(0, 0, 0)));
+    assert_eq!(Rgb::from_hex("#FFd700"), Ok(Rgb(255, 215, 0)));
+    assert!(Rgb::from_hex("ffd700").is_err());
+    assert!(Rgb::from_hex("#ffd70").is_err());
+    assert!(Rgb::from_hex("#gggggg").is_err());
+}
+
+#[test]
+fn test_built_in_palettes_differ() {
+    assert!(Palette::dark().dark);
+    assert!(!Palette::light().dark);
+    assert!(Palette::high_contrast().outlined);
+    assert_ne!(Palette::dark(), Palette::light());
+}
+
+#[test]
+fn test_system_choice_follows_system_preference() {
+    assert_eq!(ThemeChoice::System.palette(&[], true), Palette::dark());
+    assert_eq!(ThemeChoice::System.palette(&[], false), Palette::light());
+    assert_eq!(ThemeChoice::Light.palette(&[], true), Palette::light());
+}
+
+#[test]
+fn test_custom_theme_overrides_base_palette() {
+    let themes = parse_custom_themes(
+        r##"
+        [[theme]]
+        name = "Paper"
+        base = "light"
+        operator_key = "#336699"
+        outlined = true
+        "##,
+    )
+    .unwrap();
+    assert_eq!(themes.len(), 1);
+    let palette = &themes[0].palette;
+    assert_eq!(palette.operator_key, Rgb(0x33, 0x66, 0x99));
+    assert!(palette.outlined);
+    // Everything else comes from the base
+    assert_eq!(palette.background, Palette::light().background);
+
+    let choice = ThemeChoice::Custom("Paper".to_string());
+    assert_eq!(&choice.palette(&themes, true), palette);
+    // Unknown names fall back to dark
+    assert_eq!(
+        ThemeChoice::Custom("Missing".to_string()).palette(&themes, false),
+        Palette::dark()
+    );
+}
+
+#[test]
+fn test_invalid_theme_files_are_rejected() {
+    let cases = [
+        "[[theme]]\nname = \"A\"\nbackground = \"red\"",
+        "[[theme]]\nname = \"A\"\nbase = \"sepia\"",
+        "[[theme]]\nname = \"A\"\nshadow = \"#000000\"",
+        "[[theme]]\nname = \"  \"",
+        "[[theme]]\nname = \"Dark\"",
+        "[[theme]]\nname = \"A\"\n[[theme]]\nname = \"A\"",
+        "not toml at all",
+    ];
+    for text in cases {
+        assert!(parse_custom_themes(text).is_err(), "accepted: {text}");
+    }
+    assert_eq!(parse_custom_themes(""), Ok(Vec::new()));
+}
+
+#[test]
+fn test_missing_theme_file_means_no_custom_themes() {
+    let path = std::env::temp_dir().join("rust-calculator-no-such-dir/themes.toml");
+    assert_eq!(load_custom_themes(&path), Ok(Vec::new()));
+}
+
+#[test]
+fn test_theme_messages() {
+    let mut ui_state = CalculatorUIState::new();
+    assert_eq!(ui_state.theme, ThemeChoice::Dark);
+
+    ui_state.process_message(UIMessage::ThemeSelected(ThemeChoice::HighContrast));
+    assert_eq!(ui_state.palette(false), Palette::high_contrast());
+
+    ui_state.custom_themes = parse_custom_themes("[[theme]]\nname = \"Mine\"").unwrap();
+    ui_state.process_message(UIMessage::NextTheme);
+    assert_eq!(ui_state.theme, ThemeChoice::Custom("Mine".to_string()));
+    // Cycling wraps around to the first built-in theme
+    ui_state.process_message(UIMessage::NextTheme);
+    assert_eq!(ui_state.theme, ThemeChoice::System);
+}
+
+#[test]
+fn test_theme_change_keeps_expression() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.process_message(UIMessage::NumberPressed(7));
+    ui_state.process_message(UIMessage::NextTheme);
+    assert_eq!(ui_state.calculator.expression, "7");
+}
@@ -1,4 +1,10 @@
-use rust_calculator::{CalculatorUIState, MessageResult, Operation, UIMessage};
+#![cfg(feature = "std")]
+
+use rust_calculator::settings::{DecimalSeparator, DisplayFormat};
+use rust_calculator::{
+    AngleMode, CalculatorUIState, InputMode, MessageResult, Operation, ScientificKey, Settings,
+    ThemeChoice, UIMessage, Viewport,
+};
 
 #[test]
 fn test_ui_state_creation() {
@@ -116,3 +122,453 @@ fn test_scroll_behavior_complex_expression() {
         MessageResult::NoScroll
     ); // "1" - no scroll (shorter)
 }
+
+#[test]
+fn test_cursor_messages_scroll_to_cursor() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.calculator.expression = "1234".to_string();
+
+    assert_eq!(
+        ui_state.process_message(UIMessage::CursorHome),
+        MessageResult::ScrollToCursor(0.0)
+    );
+    assert_eq!(
+        ui_state.process_message(UIMessage::CursorRight),
+        MessageResult::ScrollToCursor(0.25)
+    );
+    assert_eq!(
+        ui_state.process_message(UIMessage::NumberPressed(9)),
+        MessageResult::ScrollToCursor(0.4)
+    );
+    assert_eq!(ui_state.calculator.expression, "19234");
+    assert_eq!(
+        ui_state.process_message(UIMessage::CursorEnd),
+        MessageResult::ScrollToEnd
+    );
+}
+
+#[test]
+fn test_delete_message() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.calculator.expression = "123".to_string();
+    ui_state.process_message(UIMessage::CursorLeft);
+    ui_state.process_message(UIMessage::CursorLeft);
+    ui_state.process_message(UIMessage::DeletePressed);
+    assert_eq!(ui_state.calculator.expression, "13");
+    assert_eq!(ui_state.calculator.cursor, Some(1));
+}
+
+#[test]
+fn test_paren_messages() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.process_message(UIMessage::NumberPressed(3));
+    ui_state.process_message(UIMessage::OpenParenPressed);
+    ui_state.process_message(UIMessage::NumberPressed(1));
+    ui_state.process_message(UIMessage::OperationPressed(Operation::Add));
+    ui_state.process_message(UIMessage::NumberPressed(1));
+    ui_state.process_message(UIMessage::CloseParenPressed);
+    assert_eq!(ui_state.calculator.expression, "3x(1+1)");
+
+    ui_state.process_message(UIMessage::EqualsPressed);
+    assert_eq!(ui_state.calculator.display, "6");
+}
+
+#[test]
+fn test_preview_result_while_typing() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.process_message(UIMessage::NumberPressed(7));
+    assert_eq!(ui_state.preview, None); // A lone number has nothing to preview
+
+    ui_state.process_message(UIMessage::OperationPressed(Operation::Add));
+    assert_eq!(ui_state.preview, None); // Trailing operator is ignored
+
+    ui_state.process_message(UIMessage::NumberPressed(8));
+    assert_eq!(ui_state.preview, Some("15".to_string()));
+
+    ui_state.process_message(UIMessage::OperationPressed(Operation::Multiply));
+    assert_eq!(ui_state.preview, Some("15".to_string()));
+
+    ui_state.process_message(UIMessage::NumberPressed(3));
+    assert_eq!(ui_state.preview, Some("31".to_string()));
+
+    ui_state.process_message(UIMessage::EqualsPressed);
+    assert_eq!(ui_state.preview, None);
+}
+
+#[test]
+fn test_preview_result_closes_parens() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.calculator.expression = "2x(3+4".to_string();
+    assert_eq!(ui_state.preview_result(), Some("14".to_string()));
+
+    ui_state.calculator.expression = "2x(3+4)x(".to_string();
+    assert_eq!(ui_state.preview_result(), Some("14".to_string()));
+}
+
+#[test]
+fn test_preview_result_quiet_on_error() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.calculator.expression = "5÷0".to_string();
+    assert_eq!(ui_state.preview_result(), None);
+}
+
+#[test]
+fn test_preview_result_does_not_mutate() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.calculator.expression = "1+2+".to_string();
+    ui_state.calculator.display = "1+2+".to_string();
+    assert_eq!(ui_state.preview_result(), Some("3".to_string()));
+    assert_eq!(ui_state.calculator.expression, "1+2+");
+    assert_eq!(ui_state.calculator.display, "1+2+");
+}
+
+#[test]
+fn test_load_expression_message() {
+    let mut ui_state = CalculatorUIState::new();
+    let result = ui_state.process_message(UIMessage::LoadExpression("7 + 8 * 3".to_string()));
+    assert_eq!(result, MessageResult::ScrollToEnd);
+    assert_eq!(ui_state.calculator.expression, "7+8x3");
+    assert_eq!(ui_state.preview, Some("31".to_string()));
+    assert_eq!(ui_state.notice, None);
+}
+
+#[test]
+fn test_load_expression_message_rejected() {
+    let mut ui_state = CalculatorUIState::new();
+    let result = ui_state.process_message(UIMessage::LoadExpression("hello".to_string()));
+    assert_eq!(result, MessageResult::NoScroll);
+    assert_eq!(ui_state.calculator.expression, "0");
+    assert_eq!(
+        ui_state.notice,
+        Some("Paste rejected: Invalid characters: hllo".to_string())
+    );
+
+    // The notice goes away with the next input
+    ui_state.process_message(UIMessage::NumberPressed(1));
+    assert_eq!(ui_state.notice, None);
+}
+
+#[test]
+fn test_copy_result_full_precision() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.calculator.expression = "1÷3".to_string();
+    assert_eq!(
+        ui_state.process_message(UIMessage::CopyResult),
+        MessageResult::CopyToClipboard((1.0_f64 / 3.0).to_string())
+    );
+
+    ui_state.process_message(UIMessage::EqualsPressed);
+    assert_eq!(ui_state.calculator.display, "0.33333333");
+    assert_eq!(
+        ui_state.process_message(UIMessage::CopyResult),
+        MessageResult::CopyToClipboard("0.3333333333333333".to_string())
+    );
+}
+
+#[test]
+fn test_copy_result_nothing_to_copy() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.calculator.expression = "5÷0".to_string();
+    assert_eq!(
+        ui_state.process_message(UIMessage::CopyResult),
+        MessageResult::NoScroll
+    );
+}
+
+#[test]
+fn test_paste_reads_clipboard() {
+    let mut ui_state = CalculatorUIState::new();
+    assert_eq!(
+        ui_state.process_message(UIMessage::PastePressed),
+        MessageResult::ReadClipboard
+    );
+}
+
+#[test]
+fn test_mode_toggle() {
+    let mut ui_state = CalculatorUIState::new();
+    assert_eq!(ui_state.mode, InputMode::Algebraic);
+    ui_state.process_message(UIMessage::ModeTogglePressed);
+    assert_eq!(ui_state.mode, InputMode::Rpn);
+    ui_state.process_message(UIMessage::ModeTogglePressed);
+    assert_eq!(ui_state.mode, InputMode::Algebraic);
+}
+
+#[test]
+fn test_rpn_mode_routes_messages_to_stack() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.process_message(UIMessage::ModeTogglePressed);
+
+    ui_state.process_message(UIMessage::NumberPressed(7));
+    ui_state.process_message(UIMessage::EnterPressed);
+    ui_state.process_message(UIMessage::NumberPressed(8));
+    ui_state.process_message(UIMessage::EqualsPressed); // Acts as ENTER in RPN mode
+    ui_state.process_message(UIMessage::NumberPressed(3));
+    ui_state.process_message(UIMessage::OperationPressed(Operation::Multiply));
+    let result = ui_state.process_message(UIMessage::OperationPressed(Operation::Add));
+
+    assert_eq!(result, MessageResult::NoScroll);
+    assert_eq!(ui_state.rpn.stack, vec![31.0]);
+    assert_eq!(ui_state.preview, None);
+    // The algebraic calculator is untouched
+    assert_eq!(ui_state.calculator.expression, "0");
+
+    assert_eq!(
+        ui_state.process_message(UIMessage::CopyResult),
+        MessageResult::CopyToClipboard("31".to_string())
+    );
+}
+
+#[test]
+fn test_rpn_paste_loads_number_into_entry() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.process_message(UIMessage::ModeTogglePressed);
+    ui_state.process_message(UIMessage::LoadExpression(" 1,250.5 ".to_string()));
+    assert_eq!(ui_state.rpn.entry, "1250.5");
+
+    ui_state.process_message(UIMessage::LoadExpression("2+3".to_string()));
+    assert!(ui_state.notice.is_some());
+    assert_eq!(ui_state.rpn.entry, "1250.5");
+}
+
+#[test]
+fn test_stack_messages_ignored_in_algebraic_mode() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.process_message(UIMessage::NumberPressed(4));
+    for message in [
+        UIMessage::EnterPressed,
+        UIMessage::SwapPressed,
+        UIMessage::DropPressed,
+        UIMessage::RollDownPressed,
+        UIMessage::LastXPressed,
+    ] {
+        assert_eq!(ui_state.process_message(message), MessageResult::NoScroll);
+    }
+    assert_eq!(ui_state.calculator.expression, "4");
+    assert!(ui_state.rpn.stack.is_empty());
+}
+
+#[test]
+fn test_scientific_panel_toggle_resets_second_layer() {
+    let mut ui_state = CalculatorUIState::new();
+    assert!(!ui_state.scientific_panel);
+    ui_state.process_message(UIMessage::ScientificTogglePressed);
+    assert!(ui_state.scientific_panel);
+
+    ui_state.process_message(UIMessage::SecondPressed);
+    assert!(ui_state.second_function);
+    ui_state.process_message(UIMessage::ScientificTogglePressed);
+    assert!(!ui_state.scientific_panel);
+    assert!(!ui_state.second_function);
+}
+
+#[test]
+fn test_scientific_key_uses_second_layer_once() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.process_message(UIMessage::SecondPressed);
+    ui_state.process_message(UIMessage::ScientificKeyPressed(ScientificKey::Sin));
+    assert_eq!(ui_state.calculator.expression, "asin(");
+    assert!(!ui_state.second_function);
+
+    ui_state.process_message(UIMessage::NumberPressed(1));
+    ui_state.process_message(UIMessage::EqualsPressed);
+    assert_eq!(ui_state.calculator.display, "90");
+}
+
+#[test]
+fn test_scientific_keys_build_expression() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.process_message(UIMessage::NumberPressed(3));
+    ui_state.process_message(UIMessage::ScientificKeyPressed(ScientificKey::Square));
+    assert_eq!(ui_state.calculator.expression, "3^(2)");
+    assert_eq!(ui_state.preview, Some("9".to_string()));
+
+    ui_state.process_message(UIMessage::ScientificKeyPressed(ScientificKey::Power));
+    ui_state.process_message(UIMessage::NumberPressed(2));
+    ui_state.process_message(UIMessage::EqualsPressed);
+    assert_eq!(ui_state.calculator.display, "81");
+}
+
+#[test]
+fn test_angle_mode_toggle_changes_results() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.process_message(UIMessage::AngleModePressed);
+    assert_eq!(ui_state.calculator.angle_mode, AngleMode::Radians);
+
+    ui_state.process_message(UIMessage::ScientificKeyPressed(ScientificKey::Cos));
+    ui_state.process_message(UIMessage::NumberPressed(0));
+    ui_state.process_message(UIMessage::EqualsPressed);
+    assert_eq!(ui_state.calculator.display, "1");
+}
+
+#[test]
+fn test_scientific_keys_in_rpn_mode() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.process_message(UIMessage::ModeTogglePressed);
+    ui_state.process_message(UIMessage::NumberPressed(5));
+    ui_state.process_message(UIMessage::ScientificKeyPressed(ScientificKey::Factorial));
+    assert_eq!(ui_state.rpn.stack, vec![120.0]);
+
+    ui_state.process_message(UIMessage::ScientificKeyPressed(ScientificKey::Reciprocal));
+    assert_eq!(ui_state.rpn.x_value(), Some(1.0 / 120.0));
+}
+
+#[test]
+fn test_graph_messages() {
+    let mut ui_state = CalculatorUIState::new();
+    assert!(!ui_state.graph_view);
+    ui_state.process_message(UIMessage::GraphTogglePressed);
+    assert!(ui_state.graph_view);
+
+    ui_state.process_message(UIMessage::GraphInputChanged("x^2; cos(x)".to_string()));
+    assert_eq!(ui_state.graph.functions.len(), 2);
+
+    ui_state.process_message(UIMessage::GraphZoom {
+        factor: 0.5,
+        x: 0.0,
+        y: 0.0,
+    });
+    ui_state.process_message(UIMessage::GraphPan { dx: 1.0, dy: 0.0 });
+    assert_eq!(ui_state.graph.viewport.x_min, -4.0);
+    assert_eq!(ui_state.graph.viewport.x_max, 6.0);
+
+    ui_state.process_message(UIMessage::GraphResetPressed);
+    assert_eq!(ui_state.graph.viewport, Viewport::default());
+
+    // The calculator is untouched by graph messages
+    assert_eq!(ui_state.calculator.expression, "0");
+}
+
+#[test]
+fn test_display_lines_after_equals() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.calculator.expression = "12x(3+4".to_string();
+    ui_state.process_message(UIMessage::CursorEnd);
+    let lines = ui_state.display_lines();
+    assert_eq!(lines.expression, "12x(3+4");
+    assert_eq!(lines.result, "84");
+    assert!(!lines.evaluated);
+
+    ui_state.process_message(UIMessage::EqualsPressed);
+    let lines = ui_state.display_lines();
+    assert_eq!(lines.expression, "12x(3+4)=");
+    assert_eq!(lines.result, "84");
+    assert!(lines.evaluated);
+
+    // Copying keeps the evaluation on screen, further input continues from the result
+    ui_state.process_message(UIMessage::CopyResult);
+    assert!(ui_state.display_lines().evaluated);
+    ui_state.process_message(UIMessage::OperationPressed(Operation::Add));
+    ui_state.process_message(UIMessage::NumberPressed(1));
+    let lines = ui_state.display_lines();
+    assert_eq!(lines.expression, "84+1");
+    assert_eq!(lines.result, "85");
+    assert!(!lines.evaluated);
+}
+
+#[test]
+fn test_display_lines_share_formatting() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.calculator.expression = "2000000000x3-1".to_string();
+    ui_state.process_message(UIMessage::EqualsPressed);
+    let lines = ui_state.display_lines();
+    assert_eq!(lines.expression, "2.0e9x3-1=");
+    assert_eq!(lines.result, "6.0000e9");
+}
+
+#[test]
+fn test_display_lines_caret_keeps_formatting() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.calculator.expression = "2000000000x1.5".to_string();
+    ui_state.process_message(UIMessage::CursorEnd);
+    assert_eq!(ui_state.display_lines().caret, None);
+
+    ui_state.process_message(UIMessage::CursorLeft);
+    ui_state.process_message(UIMessage::CursorLeft);
+    ui_state.process_message(UIMessage::CursorLeft);
+    ui_state.process_message(UIMessage::CursorLeft);
+    ui_state.settings.decimal_separator = DecimalSeparator::Comma;
+    let lines = ui_state.display_lines();
+    assert_eq!(lines.expression, "2,0e9x1,5");
+    assert_eq!(lines.caret, Some(5));
+}
+
+#[test]
+fn test_display_lines_keep_failed_expression() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.calculator.expression = "10/0".to_string();
+    ui_state.process_message(UIMessage::EqualsPressed);
+    let lines = ui_state.display_lines();
+    assert_eq!(lines.expression, "10/0");
+    assert_eq!(lines.result, "");
+    assert!(!lines.evaluated);
+    assert!(ui_state.calculator.error.is_some());
+}
+
+#[test]
+fn test_settings_change_result_format() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.calculator.expression = "1/3".to_string();
+    ui_state.process_message(UIMessage::EqualsPressed);
+    assert_eq!(ui_state.display_lines().result, "0.33333333");
+
+    let settings = Settings {
+        precision: 2,
+        display_format: DisplayFormat::Fixed,
+        decimal_separator: DecimalSeparator::Comma,
+        ..ui_state.settings.clone()
+    };
+    ui_state.process_message(UIMessage::SettingsChanged(settings));
+    // The result on screen is reformatted
+    let lines = ui_state.display_lines();
+    assert_eq!(lines.expression, "1/3=");
+    assert_eq!(lines.result, "0,33");
+
+    // The preview follows the same settings
+    ui_state.process_message(UIMessage::ClearPressed);
+    ui_state.process_message(UIMessage::NumberPressed(1));
+    ui_state.process_message(UIMessage::DecimalPressed);
+    ui_state.process_message(UIMessage::NumberPressed(5));
+    ui_state.process_message(UIMessage::OperationPressed(Operation::Multiply));
+    ui_state.process_message(UIMessage::NumberPressed(3));
+    let lines = ui_state.display_lines();
+    assert_eq!(lines.expression, "1,5x3");
+    assert_eq!(lines.result, "4,50");
+}
+
+#[test]
+fn test_settings_follow_angle_mode_and_theme_changes() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.process_message(UIMessage::AngleModePressed);
+    assert_eq!(ui_state.settings.angle_mode, AngleMode::Radians);
+    ui_state.process_message(UIMessage::ThemeSelected(ThemeChoice::Light));
+    assert_eq!(ui_state.settings.theme, ThemeChoice::Light);
+
+    let settings = Settings {
+        angle_mode: AngleMode::Degrees,
+        ..ui_state.settings.clone()
+    };
+    ui_state.process_message(UIMessage::SettingsChanged(settings));
+    assert_eq!(ui_state.calculator.angle_mode, AngleMode::Degrees);
+}
+
+#[test]
+fn test_paste_with_decimal_comma() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.apply_settings(Settings {
+        decimal_separator: DecimalSeparator::Comma,
+        ..Settings::default()
+    });
+    ui_state.process_message(UIMessage::LoadExpression("2,5+1".to_string()));
+    assert_eq!(ui_state.calculator.expression, "2.5+1");
+    assert_eq!(ui_state.display_lines().result, "3,5");
+}
+
+#[test]
+fn test_settings_toggle() {
+    let mut ui_state = CalculatorUIState::new();
+    ui_state.process_message(UIMessage::SettingsTogglePressed);
+    assert!(ui_state.settings_view);
+    ui_state.process_message(UIMessage::SettingsTogglePressed);
+    assert!(!ui_state.settings_view);
+}